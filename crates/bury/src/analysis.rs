@@ -0,0 +1,350 @@
+//! One-call analysis of a directory
+//!
+//! [`analyze_path`] scans, parses, and analyzes a tree with a [`Config`], and
+//! [`analyze_paths`] does the same for several trees at once, so references
+//! across them are followed. This is the code path used by the `bury` binary. Embedders that need finer control can
+//! still drive [`Scanner`](crate::Scanner), [`SourceParser`] and [`Analyzer`] directly.
+
+use crate::cache::ParseCache;
+use crate::{Analyzer, CallGraph, Config, DeadCodeFinding, Language, Result, SourceParser};
+use neural_shared::parser::Location;
+use neural_shared::report::AnalysisStats;
+use neural_shared::scanner::ScanOutcome;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
+
+/// Result of analyzing a directory
+#[derive(Debug, Clone, Default)]
+pub struct AnalysisResult {
+    /// Dead code at or above the configured minimum confidence
+    pub findings: Vec<DeadCodeFinding>,
+    /// Files scanned, definitions, and files per language
+    pub stats: AnalysisStats,
+    /// Files that could not be read or parsed, or were left out for exceeding
+    /// `max_file_size`, with the reason
+    pub skipped: Vec<(PathBuf, String)>,
+    /// Syntax errors in files that were analyzed; their results may be incomplete
+    pub parse_errors: Vec<Location>,
+    /// Call graph the findings were derived from
+    pub call_graph: CallGraph,
+    /// Files left out for exceeding `max_file_size` (also listed in `skipped`)
+    pub too_large: Vec<PathBuf>,
+}
+
+/// Scan, parse, and analyze every source file under `path`
+pub fn analyze_path(path: &Path, config: &Config) -> Result<AnalysisResult> {
+    analyze_path_with_cache(path, config, None)
+}
+
+/// Like [`analyze_path`], reusing unchanged files from `cache` and recording
+/// newly parsed ones in it
+pub fn analyze_path_with_cache(
+    path: &Path,
+    config: &Config,
+    cache: Option<&mut ParseCache>,
+) -> Result<AnalysisResult> {
+    analyze_paths_with_cache(&[path.to_path_buf()], config, cache)
+}
+
+/// Scan every path and analyze all their files together, so a definition in one
+/// tree used from another is not dead. Files reached through more than one path
+/// (nested or repeated paths) are analyzed once.
+pub fn analyze_paths(paths: &[PathBuf], config: &Config) -> Result<AnalysisResult> {
+    analyze_paths_with_cache(paths, config, None)
+}
+
+/// The files [`analyze_paths`] would analyze: every file under `paths` with one
+/// of the configured extensions, minus ignored, excluded and too large ones,
+/// each listed once
+pub fn scan_paths(paths: &[PathBuf], config: &Config) -> Result<ScanOutcome> {
+    let mut outcome = ScanOutcome::default();
+    let mut seen = HashSet::new();
+    for path in paths {
+        let scanned = config.scanner(path).scan_detailed()?;
+        for file in scanned.files {
+            // `src` and `./src/app` reach the same files under different names
+            let key = file.canonicalize().unwrap_or_else(|_| file.clone());
+            if seen.insert(key) {
+                outcome.files.push(file);
+            }
+        }
+        for file in scanned.too_large {
+            let key = file.canonicalize().unwrap_or_else(|_| file.clone());
+            if seen.insert(key) {
+                outcome.too_large.push(file);
+            }
+        }
+    }
+    Ok(outcome)
+}
+
+/// Read newline-separated file paths, such as the output of `git diff
+/// --name-only`, and keep the ones a scan of `root` would analyze.
+///
+/// Files that don't exist (e.g. deleted in the diff), lie outside `root`, lack
+/// one of the configured extensions, or are ignored or excluded are dropped.
+/// Files over `max_file_size` are listed in `too_large`, as in a scan.
+pub fn read_file_list(reader: impl BufRead, root: &Path, config: &Config) -> Result<ScanOutcome> {
+    let mut paths = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        let line = line.trim();
+        if !line.is_empty() {
+            paths.push(PathBuf::from(line));
+        }
+    }
+    Ok(config.scanner(root).scan_only(&paths)?)
+}
+
+/// Like [`analyze_paths`], reusing unchanged files from `cache` and recording
+/// newly parsed ones in it
+pub fn analyze_paths_with_cache(
+    paths: &[PathBuf],
+    config: &Config,
+    cache: Option<&mut ParseCache>,
+) -> Result<AnalysisResult> {
+    analyze_scanned_with_cache(&scan_paths(paths, config)?, config, cache)
+}
+
+/// Analyze the files of a scan, listing its too large files as skipped
+pub fn analyze_scanned_with_cache(
+    scanned: &ScanOutcome,
+    config: &Config,
+    cache: Option<&mut ParseCache>,
+) -> Result<AnalysisResult> {
+    let mut result = analyze_files_with_cache(&scanned.files, config, cache)?;
+    result.skipped.extend(
+        scanned
+            .too_large
+            .iter()
+            .map(|file| (file.clone(), too_large_reason(config))),
+    );
+    result.too_large = scanned.too_large.clone();
+    Ok(result)
+}
+
+/// Why a file over `max_file_size` was skipped
+pub(crate) fn too_large_reason(config: &Config) -> String {
+    format!(
+        "larger than the {} byte size limit (--max-file-size)",
+        config.max_file_size
+    )
+}
+
+/// Analyze exactly `files`, without scanning. Only references between these
+/// files are followed, so a definition used from a file not in the list is
+/// reported as dead.
+pub fn analyze_files_with_cache(
+    files: &[PathBuf],
+    config: &Config,
+    mut cache: Option<&mut ParseCache>,
+) -> Result<AnalysisResult> {
+    let ignore_patterns = config.compile_ignore_patterns()?;
+
+    let parser = SourceParser::new()?.with_entry_point_decorators(config.entry_decorators.clone());
+    let mut analyzer = Analyzer::new()
+        .with_case_insensitive_names(config.ignore_case)
+        .with_ignore_patterns(ignore_patterns)
+        .with_min_confidence(config.min_confidence);
+    analyzer.add_entry_points(config.entry_points.clone());
+
+    let mut result = AnalysisResult {
+        stats: AnalysisStats {
+            total_files_scanned: files.len(),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    for file_path in files {
+        let parsed = match fs::read_to_string(file_path) {
+            Ok(source) => match cache.as_deref_mut() {
+                Some(cache) => {
+                    cache.get_or_parse(file_path, &source, || parser.parse(&source, file_path))
+                }
+                None => parser.parse(&source, file_path),
+            },
+            Err(e) => Err(e.into()),
+        };
+        let parsed = match parsed {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                result.skipped.push((file_path.clone(), e.to_string()));
+                continue;
+            }
+        };
+
+        log::trace!(
+            "   {}: {} definitions",
+            file_path.display(),
+            parsed.definitions.len()
+        );
+
+        // Parsing succeeded, so the language is known
+        if let Ok(language) = Language::from_path(file_path) {
+            result.stats.record_file(language, parsed.definitions.len());
+        }
+        result
+            .parse_errors
+            .extend(parsed.parse_errors.iter().cloned());
+
+        analyzer.add_file(parsed);
+    }
+
+    result.findings = analyzer.analyze();
+    result.call_graph = analyzer.call_graph();
+
+    Ok(result)
+}
+
+/// Longest symbol span included in a snippet; longer definitions are cut off
+const MAX_SNIPPET_LINES: usize = 10;
+
+/// Fill in each finding's snippet with the symbol's source lines and `context`
+/// lines before and after.
+///
+/// Lines are numbered, and the symbol's own lines are marked with `>`. Files
+/// are read again here, so this is only worth doing for findings that will be
+/// shown; findings whose file cannot be read are left without a snippet.
+pub fn add_snippets(findings: &mut [DeadCodeFinding], context: usize) {
+    let mut sources: HashMap<String, Option<Vec<String>>> = HashMap::new();
+
+    for finding in findings {
+        let location = &finding.symbol.location;
+        let lines = sources.entry(location.file.clone()).or_insert_with(|| {
+            fs::read_to_string(&location.file)
+                .ok()
+                .map(|source| source.lines().map(str::to_string).collect())
+        });
+        if let Some(lines) = lines {
+            finding.snippet = snippet(lines, location, context);
+        }
+    }
+}
+
+fn snippet(lines: &[String], location: &Location, context: usize) -> Option<String> {
+    if location.line == 0 || location.line > lines.len() {
+        return None;
+    }
+
+    // Locations cached before end positions were recorded have no end
+    let end = location.end_line.clamp(location.line, lines.len());
+    let truncated = end - location.line + 1 > MAX_SNIPPET_LINES;
+    let (end, last) = if truncated {
+        let end = location.line + MAX_SNIPPET_LINES - 1;
+        (end, end)
+    } else {
+        (end, (end + context).min(lines.len()))
+    };
+    let first = location.line.saturating_sub(context).max(1);
+    let width = last.to_string().len();
+
+    let mut snippet = String::new();
+    for number in first..=last {
+        let marker = if (location.line..=end).contains(&number) {
+            '>'
+        } else {
+            ' '
+        };
+        snippet.push_str(&format!(
+            "{} {:>width$} | {}\n",
+            marker,
+            number,
+            lines[number - 1]
+        ));
+    }
+    if truncated {
+        snippet.push_str(&format!("  {:>width$} | ...\n", ""));
+    }
+
+    Some(snippet)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Confidence, Symbol, SymbolKind};
+
+    fn finding(file: &str, line: usize, end_line: usize) -> DeadCodeFinding {
+        DeadCodeFinding {
+            symbol: Symbol::new(
+                "unused".to_string(),
+                SymbolKind::Function,
+                Location {
+                    file: file.to_string(),
+                    line,
+                    column: 0,
+                    end_line,
+                    end_column: 0,
+                },
+            ),
+            reason: "Not reachable from any entry point".to_string(),
+            confidence: Confidence::High,
+            snippet: None,
+        }
+    }
+
+    #[test]
+    fn test_snippet_contains_symbol_lines_and_context() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("app.py");
+        fs::write(
+            &file,
+            "import os\n\ndef unused():\n    return 1\n\ndef main():\n    pass\n",
+        )
+        .unwrap();
+
+        let mut findings = [finding(file.to_str().unwrap(), 3, 4)];
+        add_snippets(&mut findings, 1);
+
+        assert_eq!(
+            findings[0].snippet.as_deref(),
+            Some("  2 | \n> 3 | def unused():\n> 4 |     return 1\n  5 | \n")
+        );
+    }
+
+    #[test]
+    fn test_long_symbols_are_truncated() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("big.py");
+        let body: String = (0..20).map(|i| format!("    x = {}\n", i)).collect();
+        fs::write(&file, format!("def big():\n{}", body)).unwrap();
+
+        let mut findings = [finding(file.to_str().unwrap(), 1, 21)];
+        add_snippets(&mut findings, 2);
+
+        let snippet = findings[0].snippet.as_deref().unwrap();
+        assert_eq!(snippet.lines().count(), MAX_SNIPPET_LINES + 1);
+        assert!(snippet.starts_with(">  1 | def big():\n"));
+        assert!(snippet.ends_with("     | ...\n"));
+    }
+
+    #[test]
+    fn test_missing_file_leaves_no_snippet() {
+        let mut findings = [finding("does/not/exist.py", 1, 1)];
+        add_snippets(&mut findings, 2);
+
+        assert!(findings[0].snippet.is_none());
+    }
+
+    #[test]
+    fn test_read_file_list_keeps_existing_supported_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let app = dir.path().join("app.py");
+        let notes = dir.path().join("notes.md");
+        fs::write(&app, "").unwrap();
+        fs::write(&notes, "").unwrap();
+
+        let input = format!(
+            "{app}\n\n{notes}\n{deleted}\n  {app}  \n",
+            app = app.display(),
+            notes = notes.display(),
+            deleted = dir.path().join("deleted.py").display(),
+        );
+        let scanned = read_file_list(input.as_bytes(), dir.path(), &Config::default()).unwrap();
+
+        assert_eq!(scanned.files, vec![app]);
+    }
+}
@@ -0,0 +1,70 @@
+//! Call graph export, to inspect why symbols are (un)reachable
+
+use serde::{Deserialize, Serialize};
+
+/// Snapshot of the analyzer's call graph.
+///
+/// Node names are the names used for matching: methods resolved through `self`
+/// appear as `Class.method`, functions and classes defined in several files as
+/// `file::name`, and names are lowercased with case-insensitive matching. Nodes
+/// and edges are sorted, so exports are stable across runs.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CallGraph {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+/// A definition, or a name called from a definition
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GraphNode {
+    pub name: String,
+    /// Where reachability analysis starts (module-level code, exports,
+    /// `--entry-point` names, ...)
+    pub entry_point: bool,
+    /// Reached from an entry point
+    pub reachable: bool,
+}
+
+/// `from` calls or references `to`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GraphEdge {
+    pub from: String,
+    pub to: String,
+}
+
+impl CallGraph {
+    /// Render as a Graphviz DOT digraph: entry points are green, unreachable
+    /// nodes red
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph bury {\n");
+        dot.push_str("    rankdir=LR;\n");
+        dot.push_str("    node [shape=box, style=filled, fillcolor=white];\n");
+
+        for node in &self.nodes {
+            let attributes = if node.entry_point {
+                " [fillcolor=palegreen, penwidth=2]"
+            } else if !node.reachable {
+                " [fillcolor=lightcoral]"
+            } else {
+                ""
+            };
+            dot.push_str(&format!("    {}{};\n", quote(&node.name), attributes));
+        }
+
+        for edge in &self.edges {
+            dot.push_str(&format!(
+                "    {} -> {};\n",
+                quote(&edge.from),
+                quote(&edge.to)
+            ));
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// Quote a DOT identifier
+fn quote(name: &str) -> String {
+    format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+}
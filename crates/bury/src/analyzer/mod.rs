@@ -1,104 +1,555 @@
 //! Dead code analysis using reachability
 
-use neural_shared::{ParsedFile, Symbol};
+use neural_shared::parser::UnreachableCode;
+use neural_shared::{MethodKind, ParsedFile, Symbol, SymbolKind};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+
+mod graph;
+
+pub use graph::{CallGraph, GraphEdge, GraphNode};
 
 /// Dead code analyzer
 pub struct Analyzer {
-    /// All definitions found in the codebase
-    definitions: HashMap<String, Symbol>,
-    /// All usages found in the codebase (function -> [called functions])
+    /// All definitions found in the codebase. Methods of different classes may
+    /// share a name, so definitions are not keyed by name.
+    definitions: Vec<Symbol>,
+    /// Call graph built from scoped usages (caller -> [callees]). Functions and
+    /// classes are keyed by file and name (see [`local_key`]), methods by class
+    /// and name (see [`method_key`]), and names defined in other files by name
+    /// alone.
     call_graph: HashMap<String, Vec<String>>,
-    /// Entry points (functions called at the top level or from special contexts)
+    /// Name -> file-local keys of the functions and classes with that name. A
+    /// name reached without knowing its file (an import, an export, a call from
+    /// another file) reaches all of them.
+    definitions_by_name: HashMap<String, Vec<String>>,
+    /// File-local key -> name
+    local_names: HashMap<String, String>,
+    /// Names of all methods
+    method_names: HashSet<String>,
+    /// Name -> class-qualified keys of the methods with that name. A method
+    /// called on a receiver of unknown type reaches all of them.
+    methods_by_name: HashMap<String, Vec<String>>,
+    /// `receiver.method` keys of calls on a plain-named receiver -> method name.
+    /// When the receiver is a class defining the method (`Migration.run()`) the
+    /// key is that method's node; otherwise the call falls back to the name.
+    receiver_calls: HashMap<String, String>,
+    /// Entry points (functions called at module level or from special contexts)
     entry_points: HashSet<String>,
     /// Additional manually specified entry points
     manual_entry_points: HashSet<String>,
+    /// Definitions whose name matches one of these are manual entry points
+    ignore_patterns: Vec<Regex>,
+    /// Imports whose name is never referenced in their file
+    unused_imports: Vec<Symbol>,
+    /// Statements after a `return` (or similar) in the same block
+    unreachable_code: Vec<UnreachableCode>,
+    /// Classes defining `__getattr__`/`__getattribute__`, whose methods may be
+    /// looked up dynamically
+    dynamic_classes: HashSet<String>,
+    /// Match symbol names case-insensitively when building the call graph
+    case_insensitive: bool,
+    /// Findings below this confidence are dropped
+    min_confidence: Confidence,
 }
 
 impl Analyzer {
     pub fn new() -> Self {
         Self {
-            definitions: HashMap::new(),
+            definitions: Vec::new(),
             call_graph: HashMap::new(),
+            definitions_by_name: HashMap::new(),
+            local_names: HashMap::new(),
+            method_names: HashSet::new(),
+            methods_by_name: HashMap::new(),
+            receiver_calls: HashMap::new(),
             entry_points: HashSet::new(),
             manual_entry_points: HashSet::new(),
+            ignore_patterns: Vec::new(),
+            unused_imports: Vec::new(),
+            unreachable_code: Vec::new(),
+            dynamic_classes: HashSet::new(),
+            case_insensitive: false,
+            min_confidence: Confidence::Low,
+        }
+    }
+
+    /// Match symbol names case-insensitively (off by default).
+    ///
+    /// When enabled, the names of definitions, usages, and entry points are
+    /// lowercased before they are linked in the call graph, so `LoadConfig` and
+    /// `loadconfig` refer to the same symbol. Only the names used for matching are
+    /// normalized: findings still report the original symbol name and file path.
+    /// Must be set before files are added.
+    pub fn with_case_insensitive_names(mut self, enabled: bool) -> Self {
+        self.case_insensitive = enabled;
+        self
+    }
+
+    /// Only report findings with at least this confidence (`Low`, i.e. all
+    /// findings, by default)
+    pub fn with_min_confidence(mut self, min: Confidence) -> Self {
+        self.min_confidence = min;
+        self
+    }
+
+    /// Treat definitions whose name matches any of `patterns` as entry points,
+    /// e.g. `^handle_` for handlers a framework looks up by name.
+    ///
+    /// Patterns are matched against the original symbol name (the method name for
+    /// methods), unanchored. Must be set before files are added.
+    pub fn with_ignore_patterns(mut self, patterns: Vec<Regex>) -> Self {
+        self.ignore_patterns = patterns;
+        self
+    }
+
+    /// Normalize a symbol name for call-graph matching
+    fn normalize(&self, name: &str) -> String {
+        if self.case_insensitive {
+            name.to_lowercase()
+        } else {
+            name.to_string()
         }
     }
 
     /// Add entry points (functions that should always be considered used)
     pub fn add_entry_points(&mut self, entry_points: Vec<String>) {
-        self.manual_entry_points.extend(entry_points);
+        let normalized: Vec<String> = entry_points.iter().map(|e| self.normalize(e)).collect();
+        self.manual_entry_points.extend(normalized);
     }
 
     /// Add parsed file to analysis
     pub fn add_file(&mut self, parsed: ParsedFile) {
+        // Names of the functions and classes defined in this file. A bare name
+        // used in the file refers to these before anything defined elsewhere.
+        let local: HashSet<String> = parsed
+            .definitions
+            .iter()
+            .filter(|def| !matches!(def.kind, SymbolKind::Method { .. }))
+            .map(|def| self.normalize(&def.name))
+            .collect();
+        let resolve = |name: String| {
+            if local.contains(&name) {
+                local_key(&parsed.path, &name)
+            } else {
+                name
+            }
+        };
+
         // Add definitions
         for def in &parsed.definitions {
-            self.definitions.insert(def.name.clone(), def.clone());
+            let name = self.normalize(&def.name);
+            self.definitions.push(def.clone());
 
-            // Initialize call graph entry for this definition
-            if !self.call_graph.contains_key(&def.name) {
-                self.call_graph.insert(def.name.clone(), Vec::new());
+            if self.ignore_patterns.iter().any(|p| p.is_match(&def.name)) {
+                self.manual_entry_points.insert(name.clone());
             }
+
+            let key = match &def.kind {
+                SymbolKind::Method { class_name } => {
+                    if def.name == "__getattr__" || def.name == "__getattribute__" {
+                        self.dynamic_classes.insert(class_name.clone());
+                    }
+                    self.method_names.insert(name.clone());
+                    let key = self.normalize(&method_key(class_name, &def.name));
+                    let keys = self.methods_by_name.entry(name).or_default();
+                    if !keys.contains(&key) {
+                        keys.push(key.clone());
+                    }
+                    key
+                }
+                _ => {
+                    let key = local_key(&parsed.path, &name);
+                    let keys = self.definitions_by_name.entry(name.clone()).or_default();
+                    if !keys.contains(&key) {
+                        keys.push(key.clone());
+                    }
+                    self.local_names.insert(key.clone(), name);
+                    key
+                }
+            };
+
+            // A nested function lives as long as the function defining it
+            if let Some(parent) = &def.scope {
+                let parent = match enclosing_method(&parsed.definitions, parent, def.location.line)
+                {
+                    Some(class_name) => self.normalize(&method_key(class_name, parent)),
+                    None => resolve(self.normalize(parent)),
+                };
+                let calls = self.call_graph.entry(parent).or_default();
+                if !calls.contains(&key) {
+                    calls.push(key.clone());
+                }
+            }
+
+            // Initialize call graph entry for this definition
+            self.call_graph.entry(key).or_default();
         }
 
         // Add entry points from the file
         for entry_point in &parsed.entry_points {
-            self.entry_points.insert(entry_point.clone());
+            self.entry_points.insert(self.normalize(entry_point));
         }
 
-        // For the call graph, we need to associate usages with the functions that call them
-        // Since we don't track scope yet, we'll use a simple heuristic:
-        // All usages in a file can potentially be called by all definitions in that file
-        // This is conservative - better to mark something as alive when it might be dead
-        // than to mark something as dead when it's actually alive
-
-        // Build a list of all function calls in this file
-        let mut all_calls: Vec<String> = parsed.usages.iter().map(|u| u.name.clone()).collect();
-        all_calls.sort();
-        all_calls.dedup();
+        // Attach each usage to the function it appears in. Usages outside any
+        // function run when the module is loaded, so they are roots of the graph.
+        for usage in &parsed.usages {
+            let callee = match (self_call_class(&parsed.definitions, usage), &usage.receiver) {
+                (Some(class_name), _) => self.normalize(&method_key(class_name, &usage.name)),
+                // `obj.helper()` is not the `helper` function of this file. The
+                // receiver may name a class defined in a file not added yet, so
+                // it is resolved during the walk (see `successors`).
+                (None, Some(receiver)) => {
+                    let key = self.normalize(&method_key(receiver, &usage.name));
+                    self.receiver_calls
+                        .insert(key.clone(), self.normalize(&usage.name));
+                    key
+                }
+                (None, None) => resolve(self.normalize(&usage.name)),
+            };
+            match &usage.scope {
+                Some(caller) => {
+                    let caller =
+                        match enclosing_method(&parsed.definitions, caller, usage.location.line) {
+                            Some(class_name) => self.normalize(&method_key(class_name, caller)),
+                            None => resolve(self.normalize(caller)),
+                        };
+                    let calls = self.call_graph.entry(caller).or_default();
+                    if !calls.contains(&callee) {
+                        calls.push(callee);
+                    }
+                }
+                None => {
+                    self.entry_points.insert(callee);
+                }
+            }
+        }
 
-        // Associate calls with definitions
-        for def in &parsed.definitions {
-            if let Some(calls) = self.call_graph.get_mut(&def.name) {
-                calls.extend(all_calls.clone());
-                calls.sort();
-                calls.dedup();
+        // An import binds a name in its own file, so it is only used by that file
+        let used_names: HashSet<String> = parsed
+            .usages
+            .iter()
+            .map(|u| self.normalize(&u.name))
+            .collect();
+        for import in &parsed.imports {
+            if !used_names.contains(&self.normalize(&import.name)) {
+                self.unused_imports.push(import.clone());
             }
         }
+
+        self.unreachable_code
+            .extend(parsed.unreachable.iter().cloned());
     }
 
-    /// Perform reachability analysis and return dead code
+    /// Perform reachability analysis and return dead code, sorted by file,
+    /// position and name so output is stable whatever order files were added in
     pub fn analyze(&self) -> Vec<DeadCodeFinding> {
         // Step 1: Find all reachable symbols using BFS from entry points
         let reachable = self.find_reachable_symbols();
+        let clusters = self.dead_clusters(&reachable);
+        let unreachable = |key: &str| match clusters.get(key) {
+            Some(cluster) => format!(
+                "Not reachable from any entry point; only called within the dead cluster {}",
+                cluster
+            ),
+            None => "Not reachable from any entry point".to_string(),
+        };
 
         // Step 2: Identify dead code - anything defined but not reachable
         let mut dead_code = Vec::new();
 
-        for (name, symbol) in &self.definitions {
-            // Skip if manually marked as entry point
-            if self.manual_entry_points.contains(name) {
-                continue;
-            }
+        for symbol in &self.definitions {
+            let name = self.normalize(&symbol.name);
 
-            // Skip if reachable
-            if reachable.contains(name) {
+            // Skip if manually marked as entry point, or suppressed by an
+            // ignore comment
+            if self.manual_entry_points.contains(&name) || symbol.suppressed {
                 continue;
             }
 
+            // A method is only live if its class is instantiated (or subclassed,
+            // or otherwise referenced from live code) and the method is called.
+            // Calls on `self` or on the class itself reach only this class's
+            // method; calls on any other receiver match every method with that
+            // name. Reading a property counts as a call.
+            let reason = match &symbol.kind {
+                SymbolKind::Method { class_name } => {
+                    let class = self.normalize(class_name);
+                    // Go types are not definitions, so they keep their bare name
+                    let instantiated = reachable
+                        .contains(&local_key(&symbol.location.file, &class))
+                        || reachable.contains(&class)
+                        || self.manual_entry_points.contains(&class);
+                    let key = self.normalize(&method_key(class_name, &symbol.name));
+                    let called = reachable.contains(&key) || is_constructor(&symbol.name);
+
+                    if instantiated && called {
+                        continue;
+                    } else if called {
+                        match symbol.method_kind {
+                            // Reached through the class, no instance needed
+                            MethodKind::Static | MethodKind::Class => {
+                                format!("Class `{}` is never used", class_name)
+                            }
+                            MethodKind::Instance | MethodKind::Property => {
+                                format!("Class `{}` is never instantiated", class_name)
+                            }
+                        }
+                    } else {
+                        unreachable(&key)
+                    }
+                }
+                _ => {
+                    let key = local_key(&symbol.location.file, &name);
+                    if reachable.contains(&key) {
+                        continue;
+                    }
+                    unreachable(&key)
+                }
+            };
+
             // This symbol is dead code
+            let (confidence, note) = self.confidence(symbol);
+            let reason = match note {
+                Some(note) => format!("{}; {}", reason, note),
+                None => reason,
+            };
             dead_code.push(DeadCodeFinding {
                 symbol: symbol.clone(),
-                reason: "Not reachable from any entry point".to_string(),
+                reason,
+                confidence,
+                snippet: None,
+            });
+        }
+
+        for import in self.unused_imports.iter().filter(|i| !i.suppressed) {
+            dead_code.push(DeadCodeFinding {
+                symbol: import.clone(),
+                reason: "Imported name is never used".to_string(),
+                confidence: Confidence::Medium,
+                snippet: None,
+            });
+        }
+
+        for code in &self.unreachable_code {
+            dead_code.push(DeadCodeFinding {
+                symbol: Symbol::new(
+                    code.code.clone(),
+                    SymbolKind::Statement,
+                    code.location.clone(),
+                ),
+                reason: format!("Unreachable statement after `{}`", code.after),
                 confidence: Confidence::High,
+                snippet: None,
             });
         }
 
+        dead_code.retain(|f| f.confidence.is_at_least(self.min_confidence));
+        dead_code.sort_by(|a, b| {
+            let (a, b) = (&a.symbol, &b.symbol);
+            (
+                &a.location.file,
+                a.location.line,
+                a.location.column,
+                &a.name,
+            )
+                .cmp(&(
+                    &b.location.file,
+                    b.location.line,
+                    b.location.column,
+                    &b.name,
+                ))
+        });
         dead_code
     }
 
+    /// Group unreachable definitions that call each other in a cycle (`a` calls
+    /// `b`, `b` calls `a`) into dead clusters.
+    ///
+    /// Returns each cluster member's graph key mapped to the cluster, formatted
+    /// as a sorted list of backquoted names, so every member shares the same
+    /// reason.
+    fn dead_clusters(&self, reachable: &HashSet<String>) -> HashMap<String, String> {
+        let dead: BTreeSet<&str> = self
+            .call_graph
+            .keys()
+            .chain(self.definitions_by_name.keys())
+            .filter(|key| !reachable.contains(*key))
+            .map(String::as_str)
+            .collect();
+
+        let mut clusters = HashMap::new();
+        for cluster in strongly_connected_components(&dead, |key| self.successors(key)) {
+            // A name and its file-local definition form a cycle of one symbol
+            let names: BTreeSet<&str> = cluster.iter().map(|key| self.name_of(key)).collect();
+            if names.len() < 2 {
+                continue;
+            }
+            let names = names
+                .iter()
+                .map(|name| format!("`{}`", name))
+                .collect::<Vec<_>>()
+                .join(", ");
+            for key in cluster {
+                clusters.insert(key.to_string(), names.clone());
+            }
+        }
+        clusters
+    }
+
+    /// Name of a graph key, without the file of file-local keys
+    fn name_of<'a>(&'a self, key: &'a str) -> &'a str {
+        self.local_names.get(key).map_or(key, String::as_str)
+    }
+
+    /// Graph keys reached from `key`: its callees, the file-local definitions and
+    /// the methods of a bare name, the method name of a call on a receiver that
+    /// is not a class defining it, and, for a function sharing its name with a
+    /// method, the bare name (calls like `self.repo.save()` may resolve to either)
+    fn successors<'a>(&'a self, key: &'a str) -> Vec<&'a str> {
+        if let Some(name) = self.unresolved_receiver_call(key) {
+            return vec![name];
+        }
+
+        let mut successors: Vec<&str> = self
+            .call_graph
+            .get(key)
+            .into_iter()
+            .chain(self.definitions_by_name.get(key))
+            .chain(self.methods_by_name.get(key))
+            .flatten()
+            .map(String::as_str)
+            .collect();
+        if let Some(name) = self.local_names.get(key) {
+            if self.method_names.contains(name) {
+                successors.push(name);
+            }
+        }
+        successors
+    }
+
+    /// The method name an `obj.method()` call falls back to when `obj` is not a
+    /// class defining `method`, so any method with that name may be called
+    fn unresolved_receiver_call(&self, key: &str) -> Option<&str> {
+        if self.call_graph.contains_key(key) {
+            return None;
+        }
+        self.receiver_calls.get(key).map(String::as_str)
+    }
+
+    /// Estimate how likely an unreachable definition is really dead.
+    ///
+    /// The call graph only sees direct references, so some symbols are
+    /// downgraded:
+    ///
+    /// - Special methods (`__eq__`, `__enter__`, ...) are called implicitly by
+    ///   the runtime: Low.
+    /// - Methods of a class defining `__getattr__` or `__getattribute__` may be
+    ///   looked up by name: Low.
+    /// - Classes may be instantiated through reflection, registries, or
+    ///   serialization frameworks: Medium.
+    /// - `_private` names are often reached through `getattr` or framework
+    ///   hooks rather than direct calls: Medium.
+    ///
+    /// Anything else is High.
+    /// Returns the confidence and, for downgraded symbols, a note explaining why.
+    fn confidence(&self, symbol: &Symbol) -> (Confidence, Option<&'static str>) {
+        let name = symbol.name.as_str();
+
+        if name.len() > 4 && name.starts_with("__") && name.ends_with("__") {
+            return (
+                Confidence::Low,
+                Some("special methods may be called implicitly"),
+            );
+        }
+
+        match &symbol.kind {
+            SymbolKind::Method { class_name } if self.dynamic_classes.contains(class_name) => {
+                return (Confidence::Low, Some("the class defines __getattr__"));
+            }
+            SymbolKind::Class => {
+                return (
+                    Confidence::Medium,
+                    Some("classes may be created by reflection"),
+                );
+            }
+            _ => {}
+        }
+
+        if name.starts_with('_') {
+            return (
+                Confidence::Medium,
+                Some("private names are often accessed dynamically"),
+            );
+        }
+
+        (Confidence::High, None)
+    }
+
+    /// Snapshot of the call graph with each node's reachability.
+    ///
+    /// A function or class is shown by name, unless several files define that
+    /// name: then each definition is a `file::name` node, linked from the bare
+    /// name.
+    pub fn call_graph(&self) -> CallGraph {
+        let reachable = self.find_reachable_symbols();
+        let display = |key: &String| -> String {
+            // `obj.method()` on an unknown receiver is shown as a call of `method`
+            let key = match self.unresolved_receiver_call(key) {
+                Some(name) => name,
+                None => key.as_str(),
+            };
+            match self.local_names.get(key) {
+                Some(name) if self.definitions_by_name[name].len() == 1 => name.clone(),
+                _ => key.to_string(),
+            }
+        };
+
+        let mut keys: BTreeSet<&String> = self.call_graph.keys().collect();
+        keys.extend(self.call_graph.values().flatten());
+        keys.extend(&self.entry_points);
+        keys.extend(&self.manual_entry_points);
+
+        let mut nodes: BTreeMap<String, GraphNode> = BTreeMap::new();
+        for key in keys {
+            let name = display(key);
+            let node = nodes.entry(name.clone()).or_insert(GraphNode {
+                name,
+                entry_point: false,
+                reachable: false,
+            });
+            node.entry_point |=
+                self.entry_points.contains(key) || self.manual_entry_points.contains(key);
+            node.reachable |= reachable.contains(key);
+        }
+
+        let mut edges: BTreeSet<(String, String)> = self
+            .call_graph
+            .iter()
+            .flat_map(|(from, calls)| calls.iter().map(move |to| (display(from), display(to))))
+            .collect();
+        for (name, keys) in &self.definitions_by_name {
+            if keys.len() > 1 && nodes.contains_key(name) {
+                edges.extend(keys.iter().map(|key| (name.clone(), key.clone())));
+            }
+        }
+        // A method called by name may be any method with that name
+        for (name, keys) in &self.methods_by_name {
+            if nodes.contains_key(name) {
+                edges.extend(keys.iter().map(|key| (name.clone(), key.clone())));
+            }
+        }
+
+        CallGraph {
+            nodes: nodes.into_values().collect(),
+            edges: edges
+                .into_iter()
+                .map(|(from, to)| GraphEdge { from, to })
+                .collect(),
+        }
+    }
+
     /// Find all symbols reachable from entry points using BFS
     fn find_reachable_symbols(&self) -> HashSet<String> {
         let mut reachable = HashSet::new();
@@ -118,12 +569,10 @@ impl Analyzer {
         // BFS traversal
         while let Some(current) = queue.pop_front() {
             // Find all functions called by the current function
-            if let Some(called_functions) = self.call_graph.get(&current) {
-                for called in called_functions {
-                    // If we haven't seen this function yet, mark it as reachable
-                    if reachable.insert(called.clone()) {
-                        queue.push_back(called.clone());
-                    }
+            for called in self.successors(&current) {
+                // If we haven't seen this function yet, mark it as reachable
+                if reachable.insert(called.to_string()) {
+                    queue.push_back(called.to_string());
                 }
             }
         }
@@ -132,6 +581,140 @@ impl Analyzer {
     }
 }
 
+/// Call graph node for a function or class, qualified by the file defining it
+fn local_key(file: &str, name: &str) -> String {
+    format!("{}::{}", file, name)
+}
+
+/// Call graph node for a method resolved to its class
+fn method_key(class_name: &str, method: &str) -> String {
+    format!("{}.{}", class_name, method)
+}
+
+/// The class of the method named `scope` that encloses `line`, or `None` when
+/// the innermost enclosing definition with that name is not a method.
+///
+/// Usages are scoped by bare function name, so this tells the body of
+/// `Report.format` apart from the body of `Export.format`.
+fn enclosing_method<'a>(definitions: &'a [Symbol], scope: &str, line: usize) -> Option<&'a str> {
+    definitions
+        .iter()
+        .filter(|def| {
+            def.name == scope && def.location.line <= line && line <= def.location.end_line
+        })
+        // The innermost enclosing definition starts last
+        .max_by_key(|def| def.location.line)
+        .and_then(|def| match &def.kind {
+            SymbolKind::Method { class_name } => Some(class_name.as_str()),
+            _ => None,
+        })
+}
+
+/// Resolve `self.foo()` (or `cls.foo()`, `this.foo()`) inside a method to the
+/// enclosing class, if that class defines `foo`.
+///
+/// Methods inherited from or overridden in other classes are not resolved
+/// through the hierarchy; a self call to a method the class does not define
+/// falls back to matching any method with that name.
+fn self_call_class<'a>(definitions: &'a [Symbol], usage: &Symbol) -> Option<&'a str> {
+    if !matches!(usage.receiver.as_deref(), Some("self" | "cls" | "this")) {
+        return None;
+    }
+
+    let line = usage.location.line;
+    let class_name = definitions
+        .iter()
+        .filter_map(|def| match &def.kind {
+            SymbolKind::Method { class_name }
+                if def.location.line <= line && line <= def.location.end_line =>
+            {
+                Some((def.location.line, class_name.as_str()))
+            }
+            _ => None,
+        })
+        // The innermost enclosing method starts last
+        .max_by_key(|(start, _)| *start)
+        .map(|(_, class_name)| class_name)?;
+
+    let defines_method = definitions.iter().any(|def| {
+        def.name == usage.name
+            && matches!(&def.kind, SymbolKind::Method { class_name: c } if c == class_name)
+    });
+    defines_method.then_some(class_name)
+}
+
+/// Strongly connected components of the graph given by `edges`, restricted to
+/// `nodes` (Kosaraju's algorithm, iterative so long call chains cannot overflow
+/// the stack)
+fn strongly_connected_components<'a>(
+    nodes: &BTreeSet<&'a str>,
+    edges: impl Fn(&'a str) -> Vec<&'a str>,
+) -> Vec<Vec<&'a str>> {
+    let successors = |node: &'a str| -> Vec<&'a str> {
+        edges(node)
+            .into_iter()
+            .filter(|callee| nodes.contains(callee))
+            .collect()
+    };
+
+    // Pass 1: order nodes by DFS finishing time
+    let mut visited = HashSet::new();
+    let mut finished = Vec::new();
+    for &start in nodes {
+        if !visited.insert(start) {
+            continue;
+        }
+        let mut stack = vec![(start, successors(start))];
+        while let Some((node, callees)) = stack.last_mut() {
+            match callees.pop() {
+                Some(callee) => {
+                    if visited.insert(callee) {
+                        stack.push((callee, successors(callee)));
+                    }
+                }
+                None => {
+                    finished.push(*node);
+                    stack.pop();
+                }
+            }
+        }
+    }
+
+    // Pass 2: walk the reversed graph in reverse finishing order; each walk
+    // collects one component
+    let mut callers: HashMap<&str, Vec<&str>> = HashMap::new();
+    for &node in nodes {
+        for callee in successors(node) {
+            callers.entry(callee).or_default().push(node);
+        }
+    }
+
+    let mut assigned = HashSet::new();
+    let mut components = Vec::new();
+    for &start in finished.iter().rev() {
+        if !assigned.insert(start) {
+            continue;
+        }
+        let mut component = vec![start];
+        let mut stack = vec![start];
+        while let Some(node) = stack.pop() {
+            for &caller in callers.get(node).into_iter().flatten() {
+                if assigned.insert(caller) {
+                    component.push(caller);
+                    stack.push(caller);
+                }
+            }
+        }
+        components.push(component);
+    }
+    components
+}
+
+/// Constructors run whenever their class is instantiated
+fn is_constructor(name: &str) -> bool {
+    matches!(name, "__init__" | "__new__" | "constructor")
+}
+
 impl Default for Analyzer {
     fn default() -> Self {
         Self::new()
@@ -144,6 +727,9 @@ pub struct DeadCodeFinding {
     pub symbol: Symbol,
     pub reason: String,
     pub confidence: Confidence,
+    /// Source lines around the symbol, filled in by [`crate::add_snippets`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snippet: Option<String>,
 }
 
 impl neural_shared::report::Finding for DeadCodeFinding {
@@ -167,6 +753,21 @@ impl neural_shared::report::Finding for DeadCodeFinding {
         self.symbol.location.column
     }
 
+    fn end_line(&self) -> usize {
+        // Locations cached before end positions were recorded have no end
+        match self.symbol.location.end_line {
+            0 => self.symbol.location.line,
+            line => line,
+        }
+    }
+
+    fn end_column(&self) -> usize {
+        match self.symbol.location.end_line {
+            0 => self.symbol.location.column,
+            _ => self.symbol.location.end_column,
+        }
+    }
+
     fn reason(&self) -> String {
         self.reason.clone()
     }
@@ -174,11 +775,1082 @@ impl neural_shared::report::Finding for DeadCodeFinding {
     fn confidence(&self) -> String {
         format!("{:?}", self.confidence)
     }
+
+    fn snippet(&self) -> Option<String> {
+        self.snippet.clone()
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
 pub enum Confidence {
+    #[serde(alias = "high")]
     High,
+    #[serde(alias = "medium")]
     Medium,
+    #[serde(alias = "low")]
     Low,
 }
+
+impl Confidence {
+    fn rank(self) -> u8 {
+        match self {
+            Confidence::High => 2,
+            Confidence::Medium => 1,
+            Confidence::Low => 0,
+        }
+    }
+
+    /// Whether this confidence is `min` or higher
+    pub fn is_at_least(self, min: Confidence) -> bool {
+        self.rank() >= min.rank()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use neural_shared::parser::{GoParser, PythonParser, RustParser, TypeScriptParser};
+    use neural_shared::Parser;
+    use std::path::Path;
+
+    fn parse_python(source: &str) -> ParsedFile {
+        PythonParser::new()
+            .unwrap()
+            .parse(source, Path::new("test.py"))
+            .unwrap()
+    }
+
+    fn parse_typescript(source: &str) -> ParsedFile {
+        TypeScriptParser::new()
+            .unwrap()
+            .parse(source, Path::new("test.ts"))
+            .unwrap()
+    }
+
+    fn parse_tsx(source: &str) -> ParsedFile {
+        TypeScriptParser::new()
+            .unwrap()
+            .parse(source, Path::new("test.tsx"))
+            .unwrap()
+    }
+
+    fn parse_go(source: &str) -> ParsedFile {
+        GoParser::new()
+            .unwrap()
+            .parse(source, Path::new("main.go"))
+            .unwrap()
+    }
+
+    fn parse_rust(source: &str) -> ParsedFile {
+        RustParser::new()
+            .unwrap()
+            .parse(source, Path::new("lib.rs"))
+            .unwrap()
+    }
+
+    const MIXED_CASE_SOURCE: &str = r#"
+def main():
+    loadconfig()
+
+def LoadConfig():
+    pass
+
+if __name__ == "__main__":
+    main()
+"#;
+
+    fn finding_names(analyzer: &Analyzer) -> Vec<String> {
+        let mut names: Vec<String> = analyzer
+            .analyze()
+            .into_iter()
+            .map(|f| f.symbol.name)
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_call_graph_export() {
+        let mut analyzer = Analyzer::new();
+        analyzer.add_file(parse_python(
+            r#"
+def main():
+    helper()
+
+def helper():
+    pass
+
+def unused():
+    helper()
+
+if __name__ == "__main__":
+    main()
+"#,
+        ));
+
+        let graph = analyzer.call_graph();
+        let node = |name: &str| graph.nodes.iter().find(|n| n.name == name).unwrap();
+        assert!(node("main").entry_point && node("main").reachable);
+        assert!(!node("helper").entry_point && node("helper").reachable);
+        assert!(!node("unused").reachable);
+
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("digraph bury {"));
+        assert!(dot.contains("    \"main\" -> \"helper\";\n"));
+        assert!(dot.contains("    \"unused\" -> \"helper\";\n"));
+        assert!(dot.contains("\"main\" [fillcolor=palegreen, penwidth=2];"));
+        assert!(dot.contains("\"unused\" [fillcolor=lightcoral];"));
+    }
+
+    #[test]
+    fn test_code_after_return_is_reported() {
+        let mut analyzer = Analyzer::new();
+        analyzer.add_file(parse_python(
+            r#"
+def main(x):
+    if x:
+        return 1
+    else:
+        print("reachable")
+    return 2
+    print("unreachable")
+
+if __name__ == "__main__":
+    main(1)
+"#,
+        ));
+
+        let findings = analyzer.analyze();
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].symbol.kind, SymbolKind::Statement);
+        assert_eq!(findings[0].symbol.name, "print(\"unreachable\")");
+        assert_eq!(findings[0].symbol.location.line, 8);
+        assert_eq!(findings[0].reason, "Unreachable statement after `return`");
+        assert_eq!(findings[0].confidence, Confidence::High);
+    }
+
+    #[test]
+    fn test_function_called_only_from_dead_function_is_dead() {
+        let mut analyzer = Analyzer::new();
+        analyzer.add_file(parse_python(
+            r#"
+def main():
+    pass
+
+def unused():
+    helper()
+
+def helper():
+    pass
+
+if __name__ == "__main__":
+    main()
+"#,
+        ));
+
+        assert_eq!(finding_names(&analyzer), vec!["helper", "unused"]);
+    }
+
+    #[test]
+    fn test_calls_are_followed_through_live_functions() {
+        let mut analyzer = Analyzer::new();
+        analyzer.add_file(parse_python(
+            r#"
+def main():
+    load()
+
+def load():
+    parse()
+
+def parse():
+    pass
+
+def orphan():
+    pass
+
+if __name__ == "__main__":
+    main()
+"#,
+        ));
+
+        assert_eq!(finding_names(&analyzer), vec!["orphan"]);
+    }
+
+    #[test]
+    fn test_module_level_calls_are_roots() {
+        let mut analyzer = Analyzer::new();
+        analyzer.add_file(parse_python(
+            r#"
+def configure():
+    pass
+
+configure()
+"#,
+        ));
+
+        assert!(finding_names(&analyzer).is_empty());
+    }
+
+    fn unused_imports(analyzer: &Analyzer) -> Vec<String> {
+        analyzer
+            .analyze()
+            .into_iter()
+            .filter(|f| f.symbol.kind == SymbolKind::Import)
+            .map(|f| f.symbol.name)
+            .collect()
+    }
+
+    #[test]
+    fn test_flask_route_is_not_dead() {
+        let mut analyzer = Analyzer::new();
+        analyzer.add_file(parse_python(
+            r#"
+from flask import Flask
+
+app = Flask(__name__)
+
+@app.route("/")
+def index():
+    return render()
+
+def render():
+    return "hello"
+
+def unused():
+    pass
+"#,
+        ));
+
+        assert_eq!(finding_names(&analyzer), vec!["unused"]);
+    }
+
+    #[test]
+    fn test_dunder_all_exports_are_not_dead() {
+        let mut analyzer = Analyzer::new();
+        analyzer.add_file(parse_python(
+            r#"
+__all__ = ["public_api"]
+
+def public_api():
+    return _impl()
+
+def _impl():
+    pass
+
+def unused():
+    pass
+"#,
+        ));
+
+        assert_eq!(finding_names(&analyzer), vec!["unused"]);
+    }
+
+    fn confidences(analyzer: &Analyzer) -> HashMap<String, Confidence> {
+        analyzer
+            .analyze()
+            .into_iter()
+            .map(|f| (f.symbol.name, f.confidence))
+            .collect()
+    }
+
+    #[test]
+    fn test_confidence_heuristics() {
+        let mut analyzer = Analyzer::new();
+        analyzer.add_file(parse_python(
+            r#"
+def unreferenced():
+    pass
+
+def _private_helper():
+    pass
+
+class Plugin:
+    def __eq__(self, other):
+        pass
+
+class Proxy:
+    def __getattr__(self, name):
+        pass
+
+    def forwarded(self):
+        pass
+"#,
+        ));
+
+        let confidences = confidences(&analyzer);
+        assert_eq!(confidences["unreferenced"], Confidence::High);
+        assert_eq!(confidences["_private_helper"], Confidence::Medium);
+        assert_eq!(confidences["Plugin"], Confidence::Medium);
+        assert_eq!(confidences["__eq__"], Confidence::Low);
+        assert_eq!(confidences["forwarded"], Confidence::Low);
+    }
+
+    #[test]
+    fn test_findings_are_sorted_regardless_of_file_order() {
+        use neural_shared::report::{AnalysisStats, JsonReporter, Reporter};
+
+        let parser = PythonParser::new().unwrap();
+        let files = [
+            ("b.py", "def zeta():\n    pass\n\ndef alpha():\n    pass\n"),
+            ("a.py", "import os\n\ndef beta():\n    pass\n"),
+            ("c.py", "def gamma():\n    return 1\n    print('never')\n"),
+        ];
+
+        let report = |order: &[usize]| {
+            let mut analyzer = Analyzer::new();
+            for &i in order {
+                let (name, source) = files[i];
+                analyzer.add_file(parser.parse(source, Path::new(name)).unwrap());
+            }
+            JsonReporter
+                .report(&analyzer.analyze(), &AnalysisStats::default())
+                .unwrap()
+        };
+
+        let first = report(&[0, 1, 2]);
+        assert_eq!(first, report(&[2, 0, 1]));
+        assert_eq!(first, report(&[1, 2, 0]));
+
+        let mut analyzer = Analyzer::new();
+        for i in [2, 1, 0] {
+            analyzer.add_file(parser.parse(files[i].1, Path::new(files[i].0)).unwrap());
+        }
+        let order: Vec<String> = analyzer
+            .analyze()
+            .into_iter()
+            .map(|f| f.symbol.name)
+            .collect();
+        assert_eq!(
+            order,
+            vec!["os", "beta", "zeta", "alpha", "gamma", "print('never')"]
+        );
+    }
+
+    #[test]
+    fn test_ignore_comment_suppresses_dead_code() {
+        let mut analyzer = Analyzer::new();
+        analyzer.add_file(parse_python(
+            r#"
+import json  # bury: ignore
+
+# bury: ignore
+def loaded_by_name():
+    pass
+
+def unused():
+    pass
+"#,
+        ));
+        analyzer.add_file(parse_typescript(
+            r#"
+// bury-ignore
+function registered() {}
+
+function stale() {}
+"#,
+        ));
+
+        assert_eq!(finding_names(&analyzer), vec!["stale", "unused"]);
+    }
+
+    #[test]
+    fn test_same_name_in_different_files_is_resolved_per_file() {
+        let parser = PythonParser::new().unwrap();
+        let mut analyzer = Analyzer::new();
+        for (path, source) in [
+            ("app.py", "def helper():\n    pass\n\nhelper()\n"),
+            ("legacy.py", "def helper():\n    pass\n"),
+        ] {
+            analyzer.add_file(parser.parse(source, Path::new(path)).unwrap());
+        }
+
+        let findings = analyzer.analyze();
+        let dead: Vec<(&str, &str)> = findings
+            .iter()
+            .map(|f| (f.symbol.location.file.as_str(), f.symbol.name.as_str()))
+            .collect();
+        assert_eq!(dead, vec![("legacy.py", "helper")]);
+
+        // Both definitions get their own node, linked from the bare name
+        let graph = analyzer.call_graph();
+        let node = |name: &str| graph.nodes.iter().find(|n| n.name == name).unwrap();
+        assert!(node("app.py::helper").reachable);
+        assert!(!node("legacy.py::helper").reachable);
+    }
+
+    #[test]
+    fn test_package_reexports_are_not_dead() {
+        let parser = PythonParser::new().unwrap();
+        let mut analyzer = Analyzer::new();
+        for (path, source) in [
+            ("shop/__init__.py", "from .pricing import apply_discount\n"),
+            (
+                "shop/pricing.py",
+                "def apply_discount(price):\n    return round_price(price * 0.9)\n\ndef round_price(price):\n    return round(price, 2)\n\ndef legacy_discount(price):\n    pass\n",
+            ),
+        ] {
+            analyzer.add_file(parser.parse(source, Path::new(path)).unwrap());
+        }
+
+        assert_eq!(finding_names(&analyzer), vec!["legacy_discount"]);
+    }
+
+    #[test]
+    fn test_names_from_other_files_reach_every_definition() {
+        let parser = PythonParser::new().unwrap();
+        let mut analyzer = Analyzer::new();
+        for (path, source) in [
+            ("a.py", "def helper():\n    pass\n"),
+            ("b.py", "def helper():\n    pass\n"),
+            ("main.py", "from a import helper\n\nhelper()\n"),
+        ] {
+            analyzer.add_file(parser.parse(source, Path::new(path)).unwrap());
+        }
+
+        assert!(finding_names(&analyzer).is_empty());
+    }
+
+    #[test]
+    fn test_local_function_sharing_a_method_name_keeps_the_method_alive() {
+        let parser = PythonParser::new().unwrap();
+        let mut analyzer = Analyzer::new();
+        for (path, source) in [
+            ("repo.py", "class Repo:\n    def save(self):\n        pass\n"),
+            (
+                "app.py",
+                "from repo import Repo\n\ndef save():\n    pass\n\ndef run(app):\n    app.repo.save()\n\nRepo()\nrun(None)\n",
+            ),
+        ] {
+            analyzer.add_file(parser.parse(source, Path::new(path)).unwrap());
+        }
+
+        // `app.repo.save()` has no plain receiver, so it may call either `save`
+        assert!(finding_names(&analyzer).is_empty());
+    }
+
+    #[test]
+    fn test_closures_live_and_die_with_their_parent() {
+        let mut analyzer = Analyzer::new();
+        analyzer.add_file(parse_python(
+            r#"
+def retry(times):
+    def decorate(func):
+        def wrapper(*args):
+            return func(*args)
+        return wrapper
+    return decorate
+
+def unused_parent():
+    def helper():
+        pass
+    return helper
+
+retry(3)
+"#,
+        ));
+
+        assert_eq!(finding_names(&analyzer), vec!["helper", "unused_parent"]);
+    }
+
+    #[test]
+    fn test_mutually_recursive_dead_functions_form_a_cluster() {
+        let mut analyzer = Analyzer::new();
+        analyzer.add_file(parse_python(
+            r#"
+def a():
+    b()
+
+def b():
+    a()
+
+def c():
+    a()
+
+def main():
+    pass
+
+main()
+"#,
+        ));
+
+        let findings = analyzer.analyze();
+        let reasons: Vec<(&str, &str)> = findings
+            .iter()
+            .map(|f| (f.symbol.name.as_str(), f.reason.as_str()))
+            .collect();
+        let cluster_reason =
+            "Not reachable from any entry point; only called within the dead cluster `a`, `b`";
+        assert_eq!(
+            reasons,
+            vec![
+                ("a", cluster_reason),
+                ("b", cluster_reason),
+                ("c", "Not reachable from any entry point"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_live_cycle_is_not_reported() {
+        let mut analyzer = Analyzer::new();
+        analyzer.add_file(parse_python(
+            r#"
+def ping(n):
+    pong(n - 1)
+
+def pong(n):
+    ping(n - 1)
+
+ping(3)
+"#,
+        ));
+
+        assert!(analyzer.analyze().is_empty());
+    }
+
+    #[test]
+    fn test_ignore_patterns_treat_matching_names_as_entry_points() {
+        let mut analyzer = Analyzer::new().with_ignore_patterns(vec![
+            Regex::new("^handle_").unwrap(),
+            Regex::new("Controller$").unwrap(),
+        ]);
+        analyzer.add_file(parse_python(
+            r#"
+def handle_login():
+    validate()
+
+def handle_logout():
+    pass
+
+def validate():
+    pass
+
+class UserController:
+    def __init__(self):
+        pass
+
+def unused():
+    pass
+
+def rehandle_():
+    pass
+"#,
+        ));
+
+        assert_eq!(finding_names(&analyzer), vec!["rehandle_", "unused"]);
+    }
+
+    #[test]
+    fn test_min_confidence_drops_weaker_findings() {
+        let mut analyzer = Analyzer::new().with_min_confidence(Confidence::Medium);
+        analyzer.add_file(parse_python(
+            r#"
+def unreferenced():
+    pass
+
+def _private_helper():
+    pass
+
+class Plugin:
+    def __eq__(self, other):
+        pass
+"#,
+        ));
+
+        assert_eq!(
+            finding_names(&analyzer),
+            vec!["Plugin", "_private_helper", "unreferenced"]
+        );
+    }
+
+    #[test]
+    fn test_method_of_uninstantiated_class_is_dead() {
+        let mut analyzer = Analyzer::new();
+        analyzer.add_file(parse_python(
+            r#"
+class Draft:
+    def save(self):
+        pass
+
+class Document:
+    def __init__(self):
+        pass
+
+    def save(self):
+        pass
+
+    def archive(self):
+        pass
+
+def main():
+    doc = Document()
+    doc.save()
+
+if __name__ == "__main__":
+    main()
+"#,
+        ));
+
+        let findings = analyzer.analyze();
+        let mut dead: Vec<String> = findings
+            .iter()
+            .map(|f| match &f.symbol.kind {
+                SymbolKind::Method { class_name } => format!("{}.{}", class_name, f.symbol.name),
+                _ => f.symbol.name.clone(),
+            })
+            .collect();
+        dead.sort();
+
+        assert_eq!(dead, vec!["Document.archive", "Draft", "Draft.save"]);
+        let draft_save = findings
+            .iter()
+            .find(|f| f.symbol.kind != SymbolKind::Class && f.symbol.name == "save")
+            .unwrap();
+        assert_eq!(draft_save.reason, "Class `Draft` is never instantiated");
+    }
+
+    #[test]
+    fn test_property_read_without_call_is_not_dead() {
+        let mut analyzer = Analyzer::new();
+        analyzer.add_file(parse_python(
+            r#"
+class Temperature:
+    def __init__(self, celsius):
+        self._celsius = celsius
+
+    @property
+    def fahrenheit(self):
+        return self._celsius * 9 / 5 + 32
+
+    @property
+    def kelvin(self):
+        return self._celsius + 273.15
+
+    @staticmethod
+    def parse(text):
+        return Temperature(float(text))
+
+class Units:
+    @classmethod
+    def default(cls):
+        return cls()
+
+def main(settings):
+    print(Temperature.parse("21").fahrenheit)
+    settings.default()
+
+if __name__ == "__main__":
+    main()
+"#,
+        ));
+
+        let findings = analyzer.analyze();
+        let mut dead: Vec<String> = findings
+            .iter()
+            .map(|f| match &f.symbol.kind {
+                SymbolKind::Method { class_name } => format!("{}.{}", class_name, f.symbol.name),
+                _ => f.symbol.name.clone(),
+            })
+            .collect();
+        dead.sort();
+
+        assert_eq!(dead, vec!["Temperature.kelvin", "Units", "Units.default"]);
+        let default = findings
+            .iter()
+            .find(|f| f.symbol.name == "default")
+            .unwrap();
+        assert_eq!(default.symbol.method_kind, MethodKind::Class);
+        assert_eq!(default.reason, "Class `Units` is never used");
+    }
+
+    #[test]
+    fn test_attribute_reads_keep_properties_alive() {
+        let mut analyzer = Analyzer::new();
+        analyzer.add_file(parse_python(
+            r#"
+class Reading:
+    @property
+    def value(self):
+        return self.raw
+
+    @property
+    def raw(self):
+        return 0
+
+class Sensor:
+    @property
+    def value(self):
+        return 1
+
+def main():
+    reading = Reading()
+    print(reading.value)
+    Sensor()
+
+if __name__ == "__main__":
+    main()
+"#,
+        ));
+
+        let dead: Vec<String> = analyzer
+            .analyze()
+            .into_iter()
+            .map(|f| match f.symbol.kind {
+                SymbolKind::Method { class_name } => format!("{}.{}", class_name, f.symbol.name),
+                _ => f.symbol.name,
+            })
+            .collect();
+
+        // `reading.value` matches any `value`; `self.raw` resolves to `Reading.raw`
+        assert!(dead.is_empty(), "unexpected findings: {:?}", dead);
+    }
+
+    #[test]
+    fn test_typescript_method_of_uninstantiated_class_is_dead() {
+        let mut analyzer = Analyzer::new();
+        analyzer.add_file(parse_typescript(
+            r#"
+class Cache {
+    constructor() {}
+    get(key: string) {}
+}
+
+class Unused {
+    get(key: string) {}
+}
+
+const cache = new Cache();
+cache.get("a");
+"#,
+        ));
+
+        let mut dead: Vec<String> = analyzer
+            .analyze()
+            .into_iter()
+            .map(|f| match f.symbol.kind {
+                SymbolKind::Method { class_name } => format!("{}.{}", class_name, f.symbol.name),
+                _ => f.symbol.name,
+            })
+            .collect();
+        dead.sort();
+
+        assert_eq!(dead, vec!["Unused", "Unused.get"]);
+    }
+
+    #[test]
+    fn test_self_calls_resolve_to_enclosing_class() {
+        let mut analyzer = Analyzer::new();
+        analyzer.add_file(parse_python(
+            r#"
+class Report:
+    def render(self):
+        return self.format()
+
+    def format(self):
+        pass
+
+class Export:
+    def format(self):
+        pass
+
+def main():
+    Report().render()
+    Export()
+
+if __name__ == "__main__":
+    main()
+"#,
+        ));
+
+        let dead: Vec<String> = analyzer
+            .analyze()
+            .into_iter()
+            .map(|f| match f.symbol.kind {
+                SymbolKind::Method { class_name } => format!("{}.{}", class_name, f.symbol.name),
+                _ => f.symbol.name,
+            })
+            .collect();
+
+        assert_eq!(dead, vec!["Export.format"]);
+    }
+
+    #[test]
+    fn test_call_on_a_class_only_reaches_that_class() {
+        let mut analyzer = Analyzer::new();
+        analyzer.add_file(parse_python(
+            r#"
+def apply():
+    pass
+
+def insert():
+    pass
+
+class Migration:
+    @classmethod
+    def run(cls):
+        apply()
+
+class Seed:
+    @classmethod
+    def run(cls):
+        insert()
+
+    @classmethod
+    def describe(cls):
+        return "seed"
+
+Migration.run()
+Seed.describe()
+"#,
+        ));
+
+        let dead: Vec<String> = analyzer
+            .analyze()
+            .into_iter()
+            .map(|f| match f.symbol.kind {
+                SymbolKind::Method { class_name } => format!("{}.{}", class_name, f.symbol.name),
+                _ => f.symbol.name,
+            })
+            .collect();
+
+        // `Seed.run` is never called, so neither is `insert`
+        assert_eq!(dead, vec!["insert", "Seed.run"]);
+    }
+
+    #[test]
+    fn test_self_called_method_keeps_its_callees_alive() {
+        let mut analyzer = Analyzer::new();
+        analyzer.add_file(parse_python(
+            r#"
+def helper():
+    pass
+
+class Report:
+    def render(self):
+        return self.format()
+
+    def format(self):
+        return helper()
+
+def main():
+    Report().render()
+
+if __name__ == "__main__":
+    main()
+"#,
+        ));
+
+        assert!(finding_names(&analyzer).is_empty());
+    }
+
+    #[test]
+    fn test_unused_python_import() {
+        let mut analyzer = Analyzer::new();
+        analyzer.add_file(parse_python(
+            r#"
+import os
+import sys
+from pathlib import Path
+
+def main():
+    print(sys.argv, Path("."))
+
+if __name__ == "__main__":
+    main()
+"#,
+        ));
+
+        assert_eq!(unused_imports(&analyzer), vec!["os"]);
+    }
+
+    #[test]
+    fn test_typescript_exports_are_not_dead() {
+        let mut analyzer = Analyzer::new();
+        analyzer.add_file(parse_typescript(
+            r#"
+function foo() {
+    helper();
+}
+function helper() {}
+function bar() {}
+function unused() {}
+
+export default foo;
+export { bar };
+"#,
+        ));
+
+        assert_eq!(finding_names(&analyzer), vec!["unused"]);
+    }
+
+    #[test]
+    fn test_go_reachability() {
+        let mut analyzer = Analyzer::new();
+        analyzer.add_file(parse_go(
+            r#"
+package main
+
+type server struct{}
+
+func (s *server) start() {
+	http.HandleFunc("/", s.index)
+}
+
+func (s *server) index(w http.ResponseWriter, r *http.Request) {}
+
+func (s *server) stop() {}
+
+type unusedType struct{}
+
+func (u unusedType) run() {}
+
+func helper() {}
+
+func orphan() {}
+
+func main() {
+	s := &server{}
+	s.start()
+	helper()
+}
+
+func TestMain(t *testing.T) {}
+"#,
+        ));
+
+        assert_eq!(finding_names(&analyzer), vec!["orphan", "run", "stop"]);
+    }
+
+    #[test]
+    fn test_rust_pub_items_are_kept_and_private_unused_fn_is_dead() {
+        let mut analyzer = Analyzer::new();
+        analyzer.add_file(parse_rust(
+            r#"
+pub fn api() {
+    helper();
+}
+
+fn helper() {}
+
+fn unused() {}
+
+struct Parser;
+
+impl Parser {
+    fn new() -> Self {
+        Parser
+    }
+
+    fn parse(&self) {
+        self.validate();
+    }
+
+    fn validate(&self) {}
+
+    fn dead(&self) {}
+}
+
+fn main() {
+    let parser = Parser::new();
+    parser.parse();
+}
+
+#[test]
+fn test_api() {
+    api();
+}
+"#,
+        ));
+
+        assert_eq!(finding_names(&analyzer), vec!["dead", "unused"]);
+    }
+
+    #[test]
+    fn test_jsx_components_are_not_dead() {
+        let mut analyzer = Analyzer::new();
+        analyzer.add_file(parse_tsx(
+            r#"
+function Header() {
+    return <h1>Title</h1>;
+}
+
+function Unused() {
+    return <p />;
+}
+
+export default function Page() {
+    return <main><Header /></main>;
+}
+"#,
+        ));
+
+        assert_eq!(finding_names(&analyzer), vec!["Unused"]);
+    }
+
+    #[test]
+    fn test_parameter_does_not_keep_a_function_of_the_same_name_alive() {
+        let mut analyzer = Analyzer::new();
+        analyzer.add_file(parse_python(
+            r#"
+def parse(text):
+    return text
+
+def load(path, parse=None, *, strict=False):
+    return open(path, mode="r")
+
+load("config.toml", strict=True)
+"#,
+        ));
+        analyzer.add_file(parse_typescript(
+            r#"
+function render(node: string) {
+    return node;
+}
+
+function walk(render: (n: string) => string, ...rest: string[]) {
+    return rest.map((node) => node.length);
+}
+
+walk((n) => n);
+"#,
+        ));
+
+        assert_eq!(finding_names(&analyzer), vec!["parse", "render"]);
+    }
+
+    #[test]
+    fn test_unused_typescript_import() {
+        let mut analyzer = Analyzer::new();
+        analyzer.add_file(parse_typescript(
+            r#"
+import { foo } from 'x';
+import { Config } from 'y';
+
+export function main(config: Config) {
+    console.log(config);
+}
+"#,
+        ));
+
+        assert_eq!(unused_imports(&analyzer), vec!["foo"]);
+    }
+
+    #[test]
+    fn test_names_are_case_sensitive_by_default() {
+        let mut analyzer = Analyzer::new();
+        analyzer.add_file(parse_python(MIXED_CASE_SOURCE));
+
+        let findings = analyzer.analyze();
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].symbol.name, "LoadConfig");
+    }
+
+    #[test]
+    fn test_case_insensitive_names() {
+        let mut analyzer = Analyzer::new().with_case_insensitive_names(true);
+        analyzer.add_file(parse_python(MIXED_CASE_SOURCE));
+
+        assert!(analyzer.analyze().is_empty());
+    }
+}
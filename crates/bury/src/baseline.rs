@@ -0,0 +1,152 @@
+//! Baselines of accepted findings, so CI can fail only on new dead code
+//!
+//! A baseline is a JSON file mapping finding fingerprints to a short description
+//! of the finding. Fingerprints combine the kind, the file (relative to the
+//! analysis root) and the name, but not the line, so edits elsewhere in a file do
+//! not invalidate the baseline.
+
+use crate::{DeadCodeFinding, Result, SymbolKind};
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// A finding recorded in a baseline
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BaselineEntry {
+    pub kind: String,
+    pub name: String,
+    pub file: String,
+}
+
+/// Findings accepted at the time the baseline was written
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Baseline {
+    pub findings: BTreeMap<String, BaselineEntry>,
+}
+
+impl Baseline {
+    /// Record `findings`, with file paths made relative to `root`
+    pub fn from_findings(findings: &[DeadCodeFinding], root: &Path) -> Self {
+        let findings = findings
+            .iter()
+            .map(|finding| {
+                let entry = entry(finding, root);
+                (fingerprint(&entry), entry)
+            })
+            .collect();
+
+        Self { findings }
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read baseline {}", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse baseline {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        fs::write(path, content + "\n")
+            .with_context(|| format!("Failed to write baseline {}", path.display()))
+    }
+
+    pub fn contains(&self, finding: &DeadCodeFinding, root: &Path) -> bool {
+        self.findings
+            .contains_key(&fingerprint(&entry(finding, root)))
+    }
+
+    /// Drop findings that are already in the baseline
+    pub fn new_findings(
+        &self,
+        findings: Vec<DeadCodeFinding>,
+        root: &Path,
+    ) -> Vec<DeadCodeFinding> {
+        findings
+            .into_iter()
+            .filter(|finding| !self.contains(finding, root))
+            .collect()
+    }
+}
+
+fn entry(finding: &DeadCodeFinding, root: &Path) -> BaselineEntry {
+    let symbol = &finding.symbol;
+    let (kind, name) = match &symbol.kind {
+        SymbolKind::Function => ("function", symbol.name.clone()),
+        SymbolKind::Class => ("class", symbol.name.clone()),
+        SymbolKind::Method { class_name } => ("method", format!("{}.{}", class_name, symbol.name)),
+        SymbolKind::Variable => ("variable", symbol.name.clone()),
+        SymbolKind::Import => ("import", symbol.name.clone()),
+        SymbolKind::Statement => ("statement", symbol.name.clone()),
+    };
+
+    let path = Path::new(&symbol.location.file);
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    let relative = relative.strip_prefix(".").unwrap_or(relative);
+
+    BaselineEntry {
+        kind: kind.to_string(),
+        name,
+        file: relative.to_string_lossy().replace('\\', "/"),
+    }
+}
+
+fn fingerprint(entry: &BaselineEntry) -> String {
+    format!("{}:{}:{}", entry.kind, entry.file, entry.name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Confidence, Symbol};
+    use neural_shared::parser::Location;
+
+    fn finding(file: &str, name: &str, line: usize) -> DeadCodeFinding {
+        DeadCodeFinding {
+            symbol: Symbol::new(
+                name.to_string(),
+                SymbolKind::Method {
+                    class_name: "Service".to_string(),
+                },
+                Location {
+                    file: file.to_string(),
+                    line,
+                    column: 4,
+                    end_line: line + 1,
+                    end_column: 0,
+                },
+            ),
+            reason: "Not reachable from any entry point".to_string(),
+            confidence: Confidence::High,
+            snippet: None,
+        }
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_line_and_root() {
+        let baseline =
+            Baseline::from_findings(&[finding("/repo/src/app.py", "run", 3)], Path::new("/repo"));
+
+        assert!(baseline
+            .findings
+            .contains_key("method:src/app.py:Service.run"));
+        assert!(baseline.contains(
+            &finding("/checkout/src/app.py", "run", 40),
+            Path::new("/checkout")
+        ));
+        assert!(!baseline.contains(&finding("/repo/src/app.py", "stop", 3), Path::new("/repo")));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("baseline.json");
+        let baseline = Baseline::from_findings(&[finding("src/app.py", "run", 3)], Path::new("."));
+
+        baseline.save(&path).unwrap();
+
+        assert_eq!(Baseline::load(&path).unwrap(), baseline);
+    }
+}
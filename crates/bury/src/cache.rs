@@ -0,0 +1,195 @@
+//! Cache of parsed files keyed by content hash
+//!
+//! Parsing dominates a run on a large tree, and most files are unchanged between
+//! runs. The cache stores each file's [`ParsedFile`] with the SHA-256 of its
+//! source and reuses it while the hash matches. Entries are tied to a parser key
+//! (bury version and parser options), so results from a different configuration
+//! are never reused.
+
+use crate::{ParsedFile, Result};
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Cache location, relative to the analyzed directory
+pub const CACHE_PATH: &str = ".bury/cache.json";
+
+/// Cache file for `analysis_path`: under the directory itself, or under the
+/// directory containing it when a single file is analyzed
+pub fn cache_path(analysis_path: &Path) -> PathBuf {
+    if analysis_path.is_file() {
+        match analysis_path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.join(CACHE_PATH),
+            _ => PathBuf::from(CACHE_PATH),
+        }
+    } else {
+        analysis_path.join(CACHE_PATH)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry {
+    hash: String,
+    parsed: ParsedFile,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ParseCache {
+    key: String,
+    entries: HashMap<String, CacheEntry>,
+    #[serde(skip)]
+    seen: HashSet<String>,
+    #[serde(skip)]
+    hits: usize,
+    #[serde(skip)]
+    misses: usize,
+}
+
+impl ParseCache {
+    /// Create an empty cache for the given parser key
+    pub fn new(key: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Load the cache at `path`, starting empty if it is missing, unreadable, or
+    /// was written with a different parser key
+    pub fn load(path: &Path, key: &str) -> Self {
+        let cache = fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<ParseCache>(&content).ok());
+
+        match cache {
+            Some(cache) if cache.key == key => cache,
+            _ => Self::new(key),
+        }
+    }
+
+    /// Write the cache, dropping entries for files not looked up in this run
+    pub fn save(&mut self, path: &Path) -> Result<()> {
+        let seen = std::mem::take(&mut self.seen);
+        self.entries.retain(|file, _| seen.contains(file));
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        fs::write(path, serde_json::to_string(self)?)
+            .with_context(|| format!("Failed to write cache {}", path.display()))
+    }
+
+    /// Return the cached parse of `file` if `source` is unchanged, otherwise
+    /// call `parse` and cache its result
+    pub fn get_or_parse<E>(
+        &mut self,
+        file: &Path,
+        source: &str,
+        parse: impl FnOnce() -> std::result::Result<ParsedFile, E>,
+    ) -> std::result::Result<ParsedFile, E> {
+        let file = file.to_string_lossy().to_string();
+        let hash = format!("{:x}", Sha256::digest(source.as_bytes()));
+        self.seen.insert(file.clone());
+
+        if let Some(entry) = self.entries.get(&file) {
+            if entry.hash == hash {
+                self.hits += 1;
+                return Ok(entry.parsed.clone());
+            }
+        }
+
+        self.misses += 1;
+        let parsed = parse()?;
+        self.entries.insert(
+            file,
+            CacheEntry {
+                hash,
+                parsed: parsed.clone(),
+            },
+        );
+        Ok(parsed)
+    }
+
+    /// Drop the entries of files for which `keep` returns false
+    pub fn retain(&mut self, mut keep: impl FnMut(&Path) -> bool) {
+        self.entries.retain(|file, _| keep(Path::new(file)));
+        self.seen.retain(|file| keep(Path::new(file)));
+    }
+
+    /// Number of cached files
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    pub fn misses(&self) -> usize {
+        self.misses
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SourceParser;
+
+    fn lookup(cache: &mut ParseCache, source: &str) -> ParsedFile {
+        let parser = SourceParser::new().unwrap();
+        let path = Path::new("app.py");
+        cache
+            .get_or_parse(path, source, || parser.parse(source, path))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_cache_path_of_a_file_is_next_to_it() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("app.py");
+        fs::write(&file, "def main():\n    pass\n").unwrap();
+
+        assert_eq!(cache_path(dir.path()), dir.path().join(CACHE_PATH));
+        assert_eq!(cache_path(&file), dir.path().join(CACHE_PATH));
+    }
+
+    #[test]
+    fn test_hit_until_content_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(CACHE_PATH);
+
+        let mut cache = ParseCache::load(&path, "v1");
+        let first = lookup(&mut cache, "def main():\n    pass\n");
+        cache.save(&path).unwrap();
+
+        let mut cache = ParseCache::load(&path, "v1");
+        assert_eq!(lookup(&mut cache, "def main():\n    pass\n"), first);
+        assert_eq!((cache.hits(), cache.misses()), (1, 0));
+
+        let changed = lookup(&mut cache, "def other():\n    pass\n");
+        assert_eq!(changed.definitions[0].name, "other");
+        assert_eq!((cache.hits(), cache.misses()), (1, 1));
+    }
+
+    #[test]
+    fn test_different_key_discards_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(CACHE_PATH);
+
+        let mut cache = ParseCache::load(&path, "v1");
+        lookup(&mut cache, "def main():\n    pass\n");
+        cache.save(&path).unwrap();
+
+        let mut cache = ParseCache::load(&path, "v2");
+        lookup(&mut cache, "def main():\n    pass\n");
+        assert_eq!((cache.hits(), cache.misses()), (0, 1));
+    }
+}
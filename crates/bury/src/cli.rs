@@ -1,6 +1,8 @@
 //! CLI argument parsing and command handling
 
-use clap::{Parser, Subcommand};
+use crate::{Confidence, DeadCodeFinding};
+use clap::{Parser, Subcommand, ValueEnum};
+use std::fmt;
 use std::path::PathBuf;
 
 #[derive(Parser, Debug)]
@@ -11,9 +13,10 @@ use std::path::PathBuf;
     long_about = "Bury finds unused code in your Python and TypeScript projects using reachability analysis.\n\nBury the dead code before it haunts your codebase!"
 )]
 pub struct Cli {
-    /// Path to analyze (defaults to current directory)
+    /// Paths to analyze together (defaults to current directory). The config
+    /// file, cache and baseline paths are resolved against the first one.
     #[arg(default_value = ".")]
-    pub path: PathBuf,
+    pub paths: Vec<PathBuf>,
 
     /// Configuration file path
     #[arg(short, long, value_name = "FILE")]
@@ -23,9 +26,99 @@ pub struct Cli {
     #[arg(short, long, value_name = "FORMAT", default_value = "terminal")]
     pub format: OutputFormat,
 
-    /// Enable verbose logging
-    #[arg(short, long)]
-    pub verbose: bool,
+    /// Aggregate findings by directory, truncated to DEPTH path components below
+    /// the first path
+    #[arg(long, value_name = "DEPTH")]
+    pub group_by_dir: Option<usize>,
+
+    /// Match symbol names case-insensitively across files
+    #[arg(long)]
+    pub ignore_case: bool,
+
+    /// Treat this symbol name as reachable (repeatable, or comma-separated)
+    #[arg(long = "entry-point", value_name = "NAME", value_delimiter = ',')]
+    pub entry_points: Vec<String>,
+
+    /// Treat symbols whose name matches this regex as reachable (repeatable,
+    /// e.g. `^handle_` or `Controller$`)
+    #[arg(long = "ignore-pattern", value_name = "REGEX")]
+    pub ignore_patterns: Vec<String>,
+
+    /// Treat Python functions with this decorator as entry points (repeatable;
+    /// `*.name` matches any receiver, e.g. `*.route`)
+    #[arg(long = "entry-decorator", value_name = "PATTERN")]
+    pub entry_decorators: Vec<String>,
+
+    /// Report only findings missing from this baseline file; writes the
+    /// baseline from the current findings if the file does not exist
+    #[arg(long, value_name = "FILE")]
+    pub baseline: Option<PathBuf>,
+
+    /// Write the call graph to FILE as Graphviz DOT, or as JSON nodes and edges
+    /// if FILE ends in `.json`
+    #[arg(long, value_name = "FILE")]
+    pub export_graph: Option<PathBuf>,
+
+    /// Only report findings with at least this confidence (overrides
+    /// `min_confidence` in the config file)
+    #[arg(long, value_name = "LEVEL")]
+    pub min_confidence: Option<Confidence>,
+
+    /// Show N lines of source before and after each finding (terminal,
+    /// Markdown and HTML output)
+    #[arg(long, value_name = "N")]
+    pub context: Option<usize>,
+
+    /// Exit with status 1 when a finding has at least this confidence
+    #[arg(long, value_name = "LEVEL", default_value = "high")]
+    pub fail_on: FailOn,
+
+    /// Parse every file instead of reusing unchanged results from .bury/cache.json
+    #[arg(long)]
+    pub no_cache: bool,
+
+    /// Fail when any file cannot be read or parsed, exceeds the size limit, or
+    /// contains syntax errors, instead of skipping it (skipped files otherwise
+    /// make bury exit with status 3)
+    #[arg(long)]
+    pub strict: bool,
+
+    /// Re-run the analysis whenever analyzed files change
+    #[arg(long)]
+    pub watch: bool,
+
+    /// Also scan files ignored by .gitignore, .ignore and git excludes
+    /// (.buryignore and `exclude` still apply)
+    #[arg(long)]
+    pub no_ignore: bool,
+
+    /// Scan hidden files and directories (the default)
+    #[arg(long, overrides_with = "no_hidden")]
+    pub hidden: bool,
+
+    /// Skip hidden files and directories
+    #[arg(long, overrides_with = "hidden")]
+    pub no_hidden: bool,
+
+    /// Skip files larger than BYTES, like minified bundles (default 1 MiB; 0
+    /// for no limit)
+    #[arg(long, value_name = "BYTES")]
+    pub max_file_size: Option<u64>,
+
+    /// Analyze only the files listed on stdin, one path per line (e.g. from
+    /// `git diff --name-only`), that a scan of the first path would include.
+    /// Only references between the listed files are followed.
+    #[arg(long, conflicts_with = "watch")]
+    pub stdin: bool,
+
+    /// Show progress on stderr: `-v` for scan and cache statistics, `-vv` to
+    /// also list each parsed file
+    #[arg(short, long, action = clap::ArgAction::Count)]
+    pub verbose: u8,
+
+    /// Print only the report: no progress, warnings or status lines
+    #[arg(short, long, conflicts_with = "verbose")]
+    pub quiet: bool,
 
     #[command(subcommand)]
     pub command: Option<Commands>,
@@ -35,13 +128,22 @@ pub struct Cli {
 pub enum Commands {
     /// Analyze code for dead code
     Analyze {
-        /// Path to analyze
-        path: Option<PathBuf>,
+        /// Paths to analyze
+        paths: Vec<PathBuf>,
+    },
+
+    /// List the files that would be analyzed, without parsing them
+    Files {
+        /// Paths to scan
+        paths: Vec<PathBuf>,
     },
 
     /// Initialize a .bury.json config file
     Init,
 
+    /// Run a JSON-RPC server over stdio for editor integration
+    Server,
+
     /// Show version information
     Version,
 }
@@ -52,12 +154,139 @@ pub enum OutputFormat {
     Terminal,
     /// JSON format (machine-readable, LLM-friendly)
     Json,
+    /// JSON Lines: one finding per line, then a summary line (streamed, for
+    /// piping into `jq`)
+    JsonLines,
     /// Markdown report
     Markdown,
+    /// SARIF 2.1.0 (for GitHub code scanning)
+    Sarif,
+    /// Self-contained HTML page
+    Html,
+    /// CSV, one row per finding (for spreadsheets)
+    Csv,
+    /// GitHub Actions workflow commands (inline pull request annotations)
+    GithubActions,
+    /// LSP `Diagnostic` objects (for editor integrations)
+    Lsp,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum FailOn {
+    /// Never fail because of findings
+    None,
+    /// Fail on any finding
+    Low,
+    /// Fail on Medium or High confidence findings
+    Medium,
+    /// Fail on High confidence findings only
+    High,
+}
+
+impl FailOn {
+    /// Minimum confidence that fails the run, or `None` if findings never do
+    pub fn threshold(self) -> Option<Confidence> {
+        match self {
+            FailOn::None => None,
+            FailOn::Low => Some(Confidence::Low),
+            FailOn::Medium => Some(Confidence::Medium),
+            FailOn::High => Some(Confidence::High),
+        }
+    }
+
+    /// Count the findings that fail the run
+    pub fn failing(self, findings: &[DeadCodeFinding]) -> usize {
+        match self.threshold() {
+            Some(min) => findings
+                .iter()
+                .filter(|f| f.confidence.is_at_least(min))
+                .count(),
+            None => 0,
+        }
+    }
+}
+
+/// The value as written on the command line, e.g. `high`
+impl fmt::Display for FailOn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.to_possible_value() {
+            Some(value) => f.write_str(value.get_name()),
+            None => Ok(()),
+        }
+    }
 }
 
 impl Cli {
     pub fn parse_args() -> Self {
         Self::parse()
     }
+
+    /// Most detailed log level to print, from `--quiet` and the number of `-v`s
+    pub fn log_level(&self) -> log::LevelFilter {
+        match (self.quiet, self.verbose) {
+            (true, _) => log::LevelFilter::Error,
+            (false, 0) => log::LevelFilter::Info,
+            (false, 1) => log::LevelFilter::Debug,
+            (false, _) => log::LevelFilter::Trace,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use neural_shared::parser::Location;
+    use neural_shared::{Symbol, SymbolKind};
+
+    fn finding(confidence: Confidence) -> DeadCodeFinding {
+        DeadCodeFinding {
+            symbol: Symbol::new(
+                "unused".to_string(),
+                SymbolKind::Function,
+                Location {
+                    file: "app.py".to_string(),
+                    line: 1,
+                    column: 0,
+                    end_line: 2,
+                    end_column: 0,
+                },
+            ),
+            reason: "Not reachable from any entry point".to_string(),
+            confidence,
+            snippet: None,
+        }
+    }
+
+    #[test]
+    fn test_fail_on_threshold() {
+        let medium = [finding(Confidence::Medium)];
+        let low = [finding(Confidence::Low)];
+
+        assert_eq!(FailOn::Medium.failing(&medium), 1);
+        assert_eq!(FailOn::Medium.failing(&low), 0);
+        assert_eq!(FailOn::High.failing(&medium), 0);
+        assert_eq!(FailOn::Low.failing(&low), 1);
+        assert_eq!(FailOn::None.failing(&medium), 0);
+    }
+
+    #[test]
+    fn test_fail_on_displays_its_value_name() {
+        assert_eq!(FailOn::High.to_string(), "high");
+        assert_eq!(FailOn::None.to_string(), "none");
+    }
+
+    #[test]
+    fn test_log_level_from_flags() {
+        let level = |args: &[&str]| {
+            Cli::try_parse_from([&["bury"], args].concat())
+                .unwrap()
+                .log_level()
+        };
+
+        assert_eq!(level(&[]), log::LevelFilter::Info);
+        assert_eq!(level(&["-v"]), log::LevelFilter::Debug);
+        assert_eq!(level(&["-vv"]), log::LevelFilter::Trace);
+        assert_eq!(level(&["--quiet"]), log::LevelFilter::Error);
+        assert!(Cli::try_parse_from(["bury", "-q", "-v"]).is_err());
+    }
 }
@@ -0,0 +1,162 @@
+//! `.bury.json` configuration
+//!
+//! The config file is read from the scan root, or from the path given with
+//! `--config`. Every field is optional:
+//!
+//! - `entry_points` - symbol names to treat as reachable
+//! - `exclude` - globs (`.gitignore` syntax) of paths to skip
+//! - `extensions` - file extensions to scan, replacing the defaults
+//! - `min_confidence` - lowest confidence to report (`High`, `Medium` or `Low`)
+//! - `entry_decorators` - Python decorators that mark entry points
+//! - `ignore_case` - match symbol names case-insensitively
+//! - `ignore_patterns` - regexes; symbols with a matching name are treated as
+//!   reachable
+//! - `respect_gitignore` - skip files ignored by git (default `true`)
+//! - `include_hidden` - scan hidden files and directories (default `true`)
+//! - `max_file_size` - skip files larger than this many bytes (default 1 MiB,
+//!   0 for no limit)
+
+use crate::{Confidence, Result, Scanner};
+use anyhow::Context;
+use neural_shared::scanner::{DEFAULT_EXTENSIONS, DEFAULT_MAX_FILE_SIZE};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Name of the config file discovered at the scan root
+pub const CONFIG_FILENAME: &str = ".bury.json";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub entry_points: Vec<String>,
+    pub exclude: Vec<String>,
+    pub extensions: Vec<String>,
+    pub min_confidence: Confidence,
+    pub entry_decorators: Vec<String>,
+    pub ignore_case: bool,
+    pub ignore_patterns: Vec<String>,
+    pub respect_gitignore: bool,
+    pub include_hidden: bool,
+    pub max_file_size: u64,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            entry_points: Vec::new(),
+            exclude: Vec::new(),
+            extensions: DEFAULT_EXTENSIONS.iter().map(|e| e.to_string()).collect(),
+            min_confidence: Confidence::Low,
+            entry_decorators: Vec::new(),
+            ignore_case: false,
+            ignore_patterns: Vec::new(),
+            respect_gitignore: true,
+            include_hidden: true,
+            max_file_size: DEFAULT_MAX_FILE_SIZE,
+        }
+    }
+}
+
+impl Config {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config {}", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse config {}", path.display()))
+    }
+
+    /// Scanner for `root` with the configured extensions, excludes and ignore
+    /// settings
+    pub fn scanner(&self, root: impl AsRef<Path>) -> Scanner {
+        Scanner::new(root)
+            .with_extensions(self.extensions.clone())
+            .with_excludes(self.exclude.clone())
+            .respect_gitignore(self.respect_gitignore)
+            .include_hidden(self.include_hidden)
+            .with_max_file_size(self.max_file_size)
+    }
+
+    /// Compile `ignore_patterns`, failing on the first invalid regex
+    pub fn compile_ignore_patterns(&self) -> Result<Vec<Regex>> {
+        self.ignore_patterns
+            .iter()
+            .map(|pattern| {
+                Regex::new(pattern).with_context(|| format!("Invalid ignore pattern `{}`", pattern))
+            })
+            .collect()
+    }
+
+    /// Load `.bury.json` from `root` if it exists
+    pub fn discover(root: &Path) -> Result<Option<Self>> {
+        let path = root.join(CONFIG_FILENAME);
+        if path.is_file() {
+            Self::load(&path).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Write the default config into `dir`, refusing to overwrite an existing one
+    pub fn init(dir: &Path) -> Result<PathBuf> {
+        let path = dir.join(CONFIG_FILENAME);
+        if path.exists() {
+            anyhow::bail!("{} already exists", path.display());
+        }
+
+        let content = serde_json::to_string_pretty(&Self::default())?;
+        fs::write(&path, content + "\n")
+            .with_context(|| format!("Failed to write config {}", path.display()))?;
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_partial_config() {
+        let config: Config = serde_json::from_str(
+            r#"{ "entry_points": ["handler"], "exclude": ["migrations/"], "min_confidence": "Medium" }"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.entry_points, vec!["handler"]);
+        assert_eq!(config.exclude, vec!["migrations/"]);
+        assert_eq!(config.extensions, Config::default().extensions);
+        assert_eq!(config.min_confidence, Confidence::Medium);
+    }
+
+    #[test]
+    fn test_unknown_fields_are_rejected() {
+        assert!(serde_json::from_str::<Config>(r#"{ "entrypoints": [] }"#).is_err());
+    }
+
+    #[test]
+    fn test_invalid_ignore_pattern_is_reported() {
+        let config = Config {
+            ignore_patterns: vec!["^handle_".to_string(), "(unclosed".to_string()],
+            ..Config::default()
+        };
+
+        let error = config.compile_ignore_patterns().unwrap_err();
+
+        assert!(format!("{:#}", error).contains("Invalid ignore pattern `(unclosed`"));
+    }
+
+    #[test]
+    fn test_init_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let path = Config::init(dir.path()).unwrap();
+
+        assert_eq!(path, dir.path().join(CONFIG_FILENAME));
+        assert_eq!(
+            Config::discover(dir.path()).unwrap(),
+            Some(Config::default())
+        );
+        assert!(Config::init(dir.path()).is_err());
+    }
+}
@@ -8,17 +8,73 @@
 //! This tool uses the `neural-shared` library for parsing and scanning.
 //! The analyzer module contains bury-specific dead code detection logic.
 
+pub mod analysis;
 pub mod analyzer;
+pub mod baseline;
+pub mod cache;
 pub mod cli;
+pub mod config;
+pub mod logging;
+pub mod server;
+pub mod watch;
 
 // Re-export shared types
-pub use neural_shared::{Language, ParsedFile, Parser, Scanner, Symbol, SymbolKind};
+pub use neural_shared::{BuryError, Language, ParsedFile, Parser, Scanner, Symbol, SymbolKind};
 
 // Bury-specific exports
-pub use analyzer::{Analyzer, Confidence, DeadCodeFinding};
+pub use analysis::{
+    add_snippets, analyze_files_with_cache, analyze_path, analyze_path_with_cache, analyze_paths,
+    analyze_paths_with_cache, analyze_scanned_with_cache, read_file_list, scan_paths,
+    AnalysisResult,
+};
+pub use analyzer::{Analyzer, CallGraph, Confidence, DeadCodeFinding};
+pub use baseline::Baseline;
+pub use config::Config;
 
 /// Result type used throughout the library
 pub type Result<T> = anyhow::Result<T>;
 
 /// Version information
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Parses files with the parser matching their language.
+///
+/// Each language parser is created once and reused for every file.
+pub struct SourceParser {
+    python: neural_shared::parser::PythonParser,
+    typescript: neural_shared::parser::TypeScriptParser,
+    go: neural_shared::parser::GoParser,
+    rust: neural_shared::parser::RustParser,
+}
+
+impl SourceParser {
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            python: neural_shared::parser::PythonParser::new()?,
+            typescript: neural_shared::parser::TypeScriptParser::new()?,
+            go: neural_shared::parser::GoParser::new()?,
+            rust: neural_shared::parser::RustParser::new()?,
+        })
+    }
+
+    /// Treat Python functions with these decorators as entry points, in addition
+    /// to the defaults (see [`neural_shared::parser::DEFAULT_ENTRY_POINT_DECORATORS`])
+    pub fn with_entry_point_decorators(mut self, patterns: Vec<String>) -> Self {
+        self.python = self.python.with_entry_point_decorators(patterns);
+        self
+    }
+
+    /// Parse source code, detecting the language from the file extension
+    pub fn parse(
+        &self,
+        source: &str,
+        path: &std::path::Path,
+    ) -> neural_shared::error::Result<ParsedFile> {
+        match Language::from_path(path)? {
+            Language::Python => self.python.parse(source, path),
+            Language::TypeScript | Language::JavaScript => self.typescript.parse(source, path),
+            Language::Go => self.go.parse(source, path),
+            Language::Rust => self.rust.parse(source, path),
+        }
+    }
+}
@@ -0,0 +1,36 @@
+//! Progress and warning output on stderr
+//!
+//! Status lines go through the `log` macros instead of `println!`, so stdout only
+//! ever carries the report and JSON or SARIF output stays machine-readable.
+
+use log::{LevelFilter, Log, Metadata, Record};
+use std::io::Write;
+
+/// Writes each record's message to stderr as-is; the messages carry their own
+/// emoji markers, so no level prefix is added.
+struct StderrLogger;
+
+impl Log for StderrLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            let _ = writeln!(std::io::stderr().lock(), "{}", record.args());
+        }
+    }
+
+    fn flush(&self) {
+        let _ = std::io::stderr().flush();
+    }
+}
+
+static LOGGER: StderrLogger = StderrLogger;
+
+/// Install the stderr logger, showing records up to `level`. Calling it again
+/// only changes the level.
+pub fn init(level: LevelFilter) {
+    let _ = log::set_logger(&LOGGER);
+    log::set_max_level(level);
+}
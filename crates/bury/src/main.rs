@@ -1,10 +1,27 @@
+use anyhow::Context;
+use bury::cache::{cache_path, ParseCache};
 use bury::cli::{Cli, Commands, OutputFormat};
-use bury::{Analyzer, Parser, Scanner};
-use neural_shared::parser::{Language, PythonParser, TypeScriptParser};
-use neural_shared::report::{JsonReporter, MarkdownReporter, Reporter};
-use std::fs;
+use bury::server::Server;
+use bury::watch::ChangeWatcher;
+use bury::{
+    add_snippets, analyze_paths_with_cache, analyze_scanned_with_cache, read_file_list, scan_paths,
+    Baseline, Config,
+};
+use clap::ValueEnum;
+use neural_shared::report::{
+    group_by_directory, CsvReporter, GithubActionsReporter, HtmlReporter, JsonLinesReporter,
+    JsonReporter, LspReporter, MarkdownReporter, Reporter, SarifReporter, TerminalReporter,
+};
+use neural_shared::scanner::ScanOutcome;
+use std::collections::HashSet;
+use std::io;
+use std::path::PathBuf;
 use std::process;
 
+/// Exit status when the report is complete except for files that were skipped.
+/// Distinct from 1 (findings or a fatal error) and 2 (invalid arguments).
+const EXIT_SKIPPED_FILES: i32 = 3;
+
 fn main() {
     if let Err(e) = run() {
         eprintln!("Error: {}", e);
@@ -14,11 +31,10 @@ fn main() {
 
 fn run() -> bury::Result<()> {
     let cli = Cli::parse_args();
+    bury::logging::init(cli.log_level());
 
-    if cli.verbose {
-        println!("bury v{}", bury::VERSION);
-        println!("Analyzing: {:?}", cli.path);
-    }
+    log::debug!("bury v{}", bury::VERSION);
+    log::debug!("Analyzing: {:?}", cli.paths);
 
     match &cli.command {
         Some(Commands::Version) => {
@@ -26,104 +42,328 @@ fn run() -> bury::Result<()> {
             Ok(())
         }
         Some(Commands::Init) => {
-            println!("Creating .bury.json config file...");
-            // TODO: Implement config file generation
-            println!("✅ Created .bury.json");
+            let path = Config::init(&cli.paths[0])?;
+            println!("✅ Created {}", path.display());
+            Ok(())
+        }
+        Some(Commands::Server) => {
+            let mut server = Server::new(load_config(&cli.paths, &cli)?)?;
+            server.run(io::stdin().lock(), io::stdout().lock())
+        }
+        Some(Commands::Files { paths }) => {
+            let paths = if paths.is_empty() { &cli.paths } else { paths };
+            let scanned = scan_paths(paths, &load_config(paths, &cli)?)?;
+            for file in &scanned.files {
+                println!("{}", file.display());
+            }
+            if !scanned.too_large.is_empty() {
+                log::warn!(
+                    "📏 Skipped {} file(s) over the size limit:",
+                    scanned.too_large.len()
+                );
+                for file in &scanned.too_large {
+                    log::warn!("  - {}", file.display());
+                }
+            }
             Ok(())
         }
-        Some(Commands::Analyze { path }) => analyze(path.as_ref().unwrap_or(&cli.path), &cli),
-        None => {
+        Some(Commands::Analyze { paths }) if !paths.is_empty() => analyze(paths, &cli),
+        Some(Commands::Analyze { .. }) | None => {
             // Default behavior: analyze current directory
-            analyze(&cli.path, &cli)
+            analyze(&cli.paths, &cli)
         }
     }
 }
 
-fn analyze(analysis_path: &std::path::Path, cli: &Cli) -> bury::Result<()> {
-    if cli.verbose {
-        println!("🔍 Scanning for files...");
+/// Load the config of the first path (or `--config`) and apply the command line
+/// overrides
+fn load_config(paths: &[PathBuf], cli: &Cli) -> bury::Result<Config> {
+    let mut config = match &cli.config {
+        Some(path) => Config::load(path)?,
+        None => Config::discover(&paths[0])?.unwrap_or_default(),
+    };
+    config.entry_points.extend(cli.entry_points.iter().cloned());
+    config
+        .ignore_patterns
+        .extend(cli.ignore_patterns.iter().cloned());
+    config
+        .entry_decorators
+        .extend(cli.entry_decorators.iter().cloned());
+    config.ignore_case |= cli.ignore_case;
+    config.respect_gitignore &= !cli.no_ignore;
+    if cli.hidden || cli.no_hidden {
+        config.include_hidden = cli.hidden;
     }
+    if let Some(min_confidence) = cli.min_confidence {
+        config.min_confidence = min_confidence;
+    }
+    if let Some(max_file_size) = cli.max_file_size {
+        config.max_file_size = max_file_size;
+    }
+    Ok(config)
+}
 
-    let scanner = Scanner::new(analysis_path);
-    let files = scanner.scan()?;
+/// Analyze `paths` together. The config, cache and baseline belong to the first
+/// path, which clap guarantees exists.
+fn analyze(paths: &[PathBuf], cli: &Cli) -> bury::Result<()> {
+    let analysis_path = &paths[0];
+    log::debug!("🔍 Scanning for files...");
 
-    if cli.verbose {
-        println!("📁 Found {} files", files.len());
-    }
+    let config = load_config(paths, cli)?;
+    // Fail before scanning (or watching) rather than on the first analysis
+    config.compile_ignore_patterns()?;
+
+    let cache_path = cache_path(analysis_path);
+    let mut cache = if cli.no_cache {
+        None
+    } else {
+        let key = format!("{}:{}", bury::VERSION, config.entry_decorators.join(","));
+        Some(ParseCache::load(&cache_path, &key))
+    };
 
-    if files.is_empty() {
-        println!("No source files found to analyze.");
-        return Ok(());
+    if cli.watch {
+        return watch(paths, cli, &config, cache);
     }
 
-    // Parse all files
-    if cli.verbose {
-        println!("🔬 Parsing files...");
+    let files = if cli.stdin {
+        Some(read_file_list(io::stdin().lock(), analysis_path, &config)?)
+    } else {
+        None
+    };
+
+    let status = report(paths, files.as_ref(), cli, &config, &mut cache)?;
+    if status != 0 {
+        process::exit(status);
     }
 
-    let mut analyzer = Analyzer::new();
-    let python_parser = PythonParser::new()?;
-    let ts_parser = TypeScriptParser::new()?;
+    Ok(())
+}
+
+/// Re-run the analysis whenever analyzed files change, until interrupted
+fn watch(
+    paths: &[PathBuf],
+    cli: &Cli,
+    config: &Config,
+    mut cache: Option<ParseCache>,
+) -> bury::Result<()> {
+    // Start watching before the first run so changes made during it are seen
+    let mut watcher = ChangeWatcher::new(paths, config)?;
+    let watched: Vec<String> = paths.iter().map(|p| p.display().to_string()).collect();
 
-    for file_path in &files {
-        if cli.verbose {
-            println!("  Parsing: {}", file_path.display());
+    loop {
+        // Clear the screen and move the cursor to the top left
+        print!("\x1B[2J\x1B[1;1H");
+        if let Err(e) = report(paths, None, cli, config, &mut cache) {
+            log::error!("Error: {}", e);
         }
+        log::info!(
+            "\n👀 Watching {} for changes (Ctrl+C to stop)",
+            watched.join(", ")
+        );
 
-        let source = fs::read_to_string(file_path)?;
-        let language = Language::from_path(file_path)?;
+        if let Some(changed) = watcher.wait(None)? {
+            for path in changed {
+                log::debug!("✏️  Changed: {}", path.display());
+            }
+        }
+    }
+}
 
-        let parsed = match language {
-            Language::Python => python_parser.parse(&source, file_path)?,
-            Language::TypeScript | Language::JavaScript => ts_parser.parse(&source, file_path)?,
-        };
+/// List the files that could not be analyzed, once per run, after the report
+fn warn_skipped(skipped: &[(PathBuf, String)]) {
+    log::warn!(
+        "\n⚠️  {} file(s) could not be analyzed and were skipped:",
+        skipped.len()
+    );
+    for (file_path, reason) in skipped {
+        log::warn!("  - {}: {}", file_path.display(), reason);
+    }
+}
 
-        if cli.verbose {
-            println!(
-                "    Found {} definitions, {} usages",
-                parsed.definitions.len(),
-                parsed.usages.len()
-            );
+/// Analyze once and print the report, returning the exit status. `files` (from
+/// `--stdin`) replaces scanning `paths`.
+fn report(
+    paths: &[PathBuf],
+    files: Option<&ScanOutcome>,
+    cli: &Cli,
+    config: &Config,
+    cache: &mut Option<ParseCache>,
+) -> bury::Result<i32> {
+    let analysis_path = &paths[0];
+    let cache_path = cache_path(analysis_path);
+    let result = match files {
+        Some(files) => analyze_scanned_with_cache(files, config, cache.as_mut())?,
+        None => analyze_paths_with_cache(paths, config, cache.as_mut())?,
+    };
+
+    log::debug!("📁 Found {} files", result.stats.total_files_scanned);
+
+    // Files that were all skipped still have to be reported
+    if result.stats.total_files_scanned == 0 && result.skipped.is_empty() {
+        log::warn!("No source files found to analyze.");
+        return Ok(0);
+    }
+
+    for error in &result.parse_errors {
+        log::warn!(
+            "⚠️  Syntax error in {}:{}:{}, results for this file may be incomplete",
+            error.file,
+            error.line,
+            error.column + 1
+        );
+    }
+
+    if let Some(cache) = cache {
+        log::debug!("💾 Cache: {} hits, {} misses", cache.hits(), cache.misses());
+        if let Err(e) = cache.save(&cache_path) {
+            log::warn!("⚠️  Could not save cache: {}", e);
         }
+    }
+
+    if !result.skipped.is_empty() && cli.strict {
+        warn_skipped(&result.skipped);
+        return Err(anyhow::anyhow!(
+            "{} file(s) could not be analyzed (--strict)",
+            result.skipped.len()
+        ));
+    }
 
-        analyzer.add_file(parsed);
+    let with_syntax_errors: HashSet<&str> = result
+        .parse_errors
+        .iter()
+        .map(|e| e.file.as_str())
+        .collect();
+    if !with_syntax_errors.is_empty() && cli.strict {
+        return Err(anyhow::anyhow!(
+            "{} file(s) contain syntax errors (--strict)",
+            with_syntax_errors.len()
+        ));
     }
 
-    // Run analysis
-    if cli.verbose {
-        println!("🔍 Running reachability analysis...");
+    if let Some(graph_path) = &cli.export_graph {
+        let graph = &result.call_graph;
+        let contents = match graph_path.extension().and_then(|e| e.to_str()) {
+            Some("json") => serde_json::to_string_pretty(graph)?,
+            _ => graph.to_dot(),
+        };
+        std::fs::write(graph_path, contents)
+            .with_context(|| format!("Failed to write {}", graph_path.display()))?;
+        log::info!(
+            "📈 Wrote call graph with {} nodes to {}",
+            graph.nodes.len(),
+            graph_path.display()
+        );
     }
 
-    let findings = analyzer.analyze();
+    let stats = result.stats;
+    let mut findings = result.findings;
+
+    if let Some(baseline_path) = &cli.baseline {
+        if baseline_path.exists() {
+            let baseline = Baseline::load(baseline_path)?;
+            findings = baseline.new_findings(findings, analysis_path);
+        } else {
+            Baseline::from_findings(&findings, analysis_path).save(baseline_path)?;
+            log::info!(
+                "📝 Wrote baseline with {} findings to {}",
+                findings.len(),
+                baseline_path.display()
+            );
+            findings.clear();
+        }
+    }
 
-    if cli.verbose {
-        println!("✅ Analysis complete!");
-        println!();
+    if let Some(context) = cli.context {
+        add_snippets(&mut findings, context);
     }
 
+    log::debug!("✅ Analysis complete!");
+
     // Generate report
-    let output = match cli.format {
-        OutputFormat::Json => {
-            let reporter = JsonReporter;
-            reporter.report(&findings)?
-        }
-        OutputFormat::Markdown => {
-            let reporter = MarkdownReporter;
-            reporter.report(&findings)?
+    let output = if let Some(depth) = cli.group_by_dir {
+        let groups = group_by_directory(&findings, analysis_path, depth);
+        match cli.format {
+            OutputFormat::Json => JsonReporter.report_directories(&groups)?,
+            OutputFormat::JsonLines
+            | OutputFormat::Sarif
+            | OutputFormat::Html
+            | OutputFormat::Csv
+            | OutputFormat::GithubActions
+            | OutputFormat::Lsp => {
+                let format = cli.format.to_possible_value().expect("no skipped formats");
+                return Err(anyhow::anyhow!(
+                    "--group-by-dir cannot be combined with --format {}",
+                    format.get_name()
+                ));
+            }
+            OutputFormat::Markdown | OutputFormat::Terminal => {
+                MarkdownReporter.report_directories(&groups)?
+            }
         }
-        OutputFormat::Terminal => {
-            // For now, use Markdown for terminal
-            let reporter = MarkdownReporter;
-            reporter.report(&findings)?
+    } else {
+        match cli.format {
+            OutputFormat::Json => {
+                let reporter = JsonReporter;
+                reporter.report(&findings, &stats)?
+            }
+            OutputFormat::JsonLines => {
+                // Streamed instead of built up in memory, for huge result sets
+                JsonLinesReporter.write(&findings, &stats, io::stdout().lock())?;
+                String::new()
+            }
+            OutputFormat::Markdown => {
+                let reporter = MarkdownReporter;
+                reporter.report(&findings, &stats)?
+            }
+            OutputFormat::Sarif => {
+                let reporter = SarifReporter::new("bury", bury::VERSION);
+                reporter.report(&findings, &stats)?
+            }
+            OutputFormat::Html => {
+                let reporter = HtmlReporter;
+                reporter.report(&findings, &stats)?
+            }
+            OutputFormat::Csv => {
+                let reporter = CsvReporter;
+                reporter.report(&findings, &stats)?
+            }
+            OutputFormat::GithubActions => {
+                let reporter = GithubActionsReporter;
+                reporter.report(&findings, &stats)?
+            }
+            OutputFormat::Lsp => {
+                let reporter = LspReporter::new("bury");
+                reporter.report(&findings, &stats)?
+            }
+            OutputFormat::Terminal => {
+                let reporter = TerminalReporter::new();
+                reporter.report(&findings, &stats)?
+            }
         }
     };
 
-    println!("{}", output);
+    // JSON Lines output has already been written
+    if !output.is_empty() {
+        println!("{}", output);
+    }
+
+    if !result.skipped.is_empty() {
+        warn_skipped(&result.skipped);
+    }
 
-    if !findings.is_empty() && matches!(cli.format, OutputFormat::Terminal) {
-        eprintln!("\n⚠️  Found {} dead code items", findings.len());
-        process::exit(1);
+    let failing = cli.fail_on.failing(&findings);
+    if failing > 0 {
+        log::warn!(
+            "\n⚠️  Found {} dead code items at or above --fail-on {}",
+            failing,
+            cli.fail_on
+        );
+        return Ok(1);
     }
 
-    Ok(())
+    if !result.skipped.is_empty() {
+        return Ok(EXIT_SKIPPED_FILES);
+    }
+
+    Ok(0)
 }
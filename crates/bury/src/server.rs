@@ -0,0 +1,494 @@
+//! Long-running analysis server speaking JSON-RPC 2.0 over stdio
+//!
+//! Each request and response is a single line of JSON. Supported methods:
+//!
+//! - `analyze` `{ "path": "..." }` - scan and parse a directory, replacing the current state
+//! - `didChange` `{ "file": "...", "text": "..." }` - reparse one file from in-memory text
+//! - `findings` - return the current dead code findings
+//! - `shutdown` - stop the server
+//!
+//! The server scans, parses and analyzes with a [`Config`], as the CLI does, so
+//! excludes, size limits, entry points, ignore patterns and the minimum
+//! confidence apply. Requests without an `id` are notifications: they are
+//! handled, but not answered. Parsed files are kept in a [`ParseCache`], so `didChange` and a
+//! repeated `analyze` only reparse files whose text changed. Text sent with
+//! `didChange` is kept and used instead of the file on disk by later `analyze`
+//! requests, so unsaved edits are not lost. Cache entries for files that a new
+//! `analyze` no longer finds are dropped.
+
+use crate::analysis::too_large_reason;
+use crate::cache::ParseCache;
+use crate::{Analyzer, BuryError, Config, DeadCodeFinding, ParsedFile, Result, SourceParser};
+use regex::Regex;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const INTERNAL_ERROR: i64 = -32603;
+
+/// JSON-RPC request
+#[derive(Debug, Deserialize)]
+pub struct RpcRequest {
+    /// `None` for a notification, which gets no response
+    #[serde(default, deserialize_with = "present")]
+    pub id: Option<Value>,
+    pub method: String,
+    #[serde(default)]
+    pub params: Value,
+}
+
+/// JSON-RPC response
+#[derive(Debug, Serialize)]
+pub struct RpcResponse {
+    pub jsonrpc: &'static str,
+    pub id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<RpcError>,
+}
+
+/// JSON-RPC error object
+#[derive(Debug, Serialize)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+impl RpcResponse {
+    fn success(id: Value, result: Value) -> Self {
+        Self {
+            jsonrpc: "2.0",
+            id,
+            result: Some(result),
+            error: None,
+        }
+    }
+
+    fn failure(id: Value, code: i64, message: impl Into<String>) -> Self {
+        Self {
+            jsonrpc: "2.0",
+            id,
+            result: None,
+            error: Some(RpcError {
+                code,
+                message: message.into(),
+            }),
+        }
+    }
+}
+
+/// Deserialize a field that is present, even as `null`, to `Some`
+fn present<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Option<Value>, D::Error> {
+    Value::deserialize(deserializer).map(Some)
+}
+
+#[derive(Deserialize)]
+struct AnalyzeParams {
+    path: PathBuf,
+}
+
+#[derive(Deserialize)]
+struct DidChangeParams {
+    file: PathBuf,
+    text: String,
+}
+
+/// Analysis server state
+pub struct Server {
+    config: Config,
+    ignore_patterns: Vec<Regex>,
+    parser: SourceParser,
+    cache: ParseCache,
+    /// Parsed files, keyed by canonical path
+    files: BTreeMap<PathBuf, ParsedFile>,
+    /// Text from `didChange`, used instead of the file on disk
+    changed: BTreeMap<PathBuf, String>,
+    running: bool,
+}
+
+impl Server {
+    /// Create a server that scans, parses and analyzes with `config`
+    pub fn new(config: Config) -> Result<Self> {
+        Ok(Self {
+            ignore_patterns: config.compile_ignore_patterns()?,
+            parser: SourceParser::new()?
+                .with_entry_point_decorators(config.entry_decorators.clone()),
+            config,
+            cache: ParseCache::new(crate::VERSION),
+            files: BTreeMap::new(),
+            changed: BTreeMap::new(),
+            running: true,
+        })
+    }
+
+    /// Serve requests line by line until EOF or `shutdown`
+    pub fn run<R: BufRead, W: Write>(&mut self, input: R, mut output: W) -> Result<()> {
+        for line in input.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let response = match serde_json::from_str::<RpcRequest>(&line) {
+                Ok(request) if request.id.is_none() => {
+                    self.handle(request);
+                    None
+                }
+                Ok(request) => Some(self.handle(request)),
+                Err(e) => Some(RpcResponse::failure(
+                    Value::Null,
+                    PARSE_ERROR,
+                    e.to_string(),
+                )),
+            };
+
+            if let Some(response) = response {
+                writeln!(output, "{}", serde_json::to_string(&response)?)?;
+                output.flush()?;
+            }
+
+            if !self.running {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Handle a single request
+    pub fn handle(&mut self, request: RpcRequest) -> RpcResponse {
+        let id = request.id.clone().unwrap_or(Value::Null);
+
+        let result = match request.method.as_str() {
+            "analyze" => match serde_json::from_value::<AnalyzeParams>(request.params) {
+                Ok(params) => self.analyze(&params.path),
+                Err(e) => return RpcResponse::failure(id, INVALID_PARAMS, e.to_string()),
+            },
+            "didChange" => match serde_json::from_value::<DidChangeParams>(request.params) {
+                Ok(params) => self.did_change(&params.file, &params.text),
+                Err(e) => return RpcResponse::failure(id, INVALID_PARAMS, e.to_string()),
+            },
+            "findings" => Ok(self.findings_result()),
+            "shutdown" => {
+                self.running = false;
+                Ok(Value::Null)
+            }
+            method => {
+                return RpcResponse::failure(
+                    id,
+                    METHOD_NOT_FOUND,
+                    format!("Unknown method: {}", method),
+                )
+            }
+        };
+
+        match result {
+            Ok(value) => RpcResponse::success(id, value),
+            Err(e) => RpcResponse::failure(id, INTERNAL_ERROR, e.to_string()),
+        }
+    }
+
+    /// Scan and parse a directory, replacing all analyzed files
+    fn analyze(&mut self, path: &Path) -> Result<Value> {
+        let scanned = self.config.scanner(canonical(path)).scan_detailed()?;
+        let mut errors: Vec<Value> = scanned
+            .too_large
+            .iter()
+            .map(|file| {
+                json!({
+                    "file": file.display().to_string(),
+                    "message": too_large_reason(&self.config),
+                })
+            })
+            .collect();
+
+        self.files.clear();
+        for file in scanned.files {
+            let source = match self.changed.get(&file) {
+                Some(text) => Ok(text.clone()),
+                None => fs::read_to_string(&file).map_err(BuryError::from),
+            };
+            let parsed = source.and_then(|source| {
+                self.cache
+                    .get_or_parse(&file, &source, || self.parser.parse(&source, &file))
+            });
+
+            match parsed {
+                Ok(parsed) => {
+                    self.files.insert(file, parsed);
+                }
+                Err(e) => errors.push(json!({
+                    "file": file.display().to_string(),
+                    "message": e.to_string(),
+                })),
+            }
+        }
+        self.cache.retain(|file| self.files.contains_key(file));
+
+        let mut result = self.findings_result();
+        result["errors"] = Value::Array(errors);
+        Ok(result)
+    }
+
+    /// Reparse a single file from its in-memory contents
+    fn did_change(&mut self, file: &Path, text: &str) -> Result<Value> {
+        let file = canonical(file);
+        let parsed = self
+            .cache
+            .get_or_parse(&file, text, || self.parser.parse(text, &file))?;
+        self.changed.insert(file.clone(), text.to_string());
+        self.files.insert(file, parsed);
+
+        Ok(self.findings_result())
+    }
+
+    /// Run reachability analysis over the cached files
+    pub fn findings(&self) -> Vec<DeadCodeFinding> {
+        let mut analyzer = Analyzer::new()
+            .with_case_insensitive_names(self.config.ignore_case)
+            .with_ignore_patterns(self.ignore_patterns.clone())
+            .with_min_confidence(self.config.min_confidence);
+        analyzer.add_entry_points(self.config.entry_points.clone());
+        for parsed in self.files.values() {
+            analyzer.add_file(parsed.clone());
+        }
+        analyzer.analyze()
+    }
+
+    fn findings_result(&self) -> Value {
+        json!({
+            "files": self.files.len(),
+            "findings": self.findings(),
+        })
+    }
+}
+
+/// Canonicalize a path when it exists, so scanned and changed files share keys
+fn canonical(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn run_session(server: &mut Server, requests: &[Value]) -> Vec<Value> {
+        let input: String = requests.iter().map(|r| format!("{}\n", r)).collect();
+        let mut output = Vec::new();
+
+        server.run(Cursor::new(input), &mut output).unwrap();
+
+        String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    fn finding_names(response: &Value) -> Vec<String> {
+        response["result"]["findings"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|f| f["symbol"]["name"].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_analyze_and_did_change() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("app.py");
+        fs::write(
+            &file,
+            "def main():\n    pass\n\ndef helper():\n    pass\n\nif __name__ == \"__main__\":\n    main()\n",
+        )
+        .unwrap();
+
+        let mut server = Server::new(Config::default()).unwrap();
+        let responses = run_session(
+            &mut server,
+            &[
+                json!({"jsonrpc": "2.0", "id": 1, "method": "analyze", "params": {"path": dir.path()}}),
+                json!({"jsonrpc": "2.0", "id": 2, "method": "didChange", "params": {
+                    "file": file,
+                    "text": "def main():\n    helper()\n\ndef helper():\n    pass\n\nif __name__ == \"__main__\":\n    main()\n",
+                }}),
+                json!({"jsonrpc": "2.0", "id": 3, "method": "findings"}),
+            ],
+        );
+
+        assert_eq!(responses.len(), 3);
+        assert_eq!(finding_names(&responses[0]), vec!["helper"]);
+        assert!(finding_names(&responses[1]).is_empty());
+        assert_eq!(responses[2]["id"], 3);
+        assert_eq!(responses[2]["result"]["files"], 1);
+    }
+
+    #[test]
+    fn test_changed_text_survives_reanalysis_without_reparsing() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("app.py");
+        fs::write(
+            &file,
+            "def main():\n    pass\n\ndef helper():\n    pass\n\nmain()\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("util.py"),
+            "def util():\n    pass\n\nutil()\n",
+        )
+        .unwrap();
+
+        let mut server = Server::new(Config::default()).unwrap();
+        let responses = run_session(
+            &mut server,
+            &[
+                json!({"jsonrpc": "2.0", "id": 1, "method": "analyze", "params": {"path": dir.path()}}),
+                json!({"jsonrpc": "2.0", "method": "didChange", "params": {
+                    "file": file,
+                    "text": "def main():\n    helper()\n\ndef helper():\n    pass\n\nmain()\n",
+                }}),
+                json!({"jsonrpc": "2.0", "id": 2, "method": "analyze", "params": {"path": dir.path()}}),
+            ],
+        );
+
+        // The notification is not answered
+        assert_eq!(responses.len(), 2);
+        assert_eq!(responses[1]["id"], 2);
+        assert_eq!(finding_names(&responses[0]), vec!["helper"]);
+        assert!(finding_names(&responses[1]).is_empty());
+        // Both files came from the cache on the second analysis
+        assert_eq!(server.cache.misses(), 3);
+        assert_eq!(server.cache.hits(), 2);
+    }
+
+    #[test]
+    fn test_analyze_applies_config() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("app.py"),
+            "def handler():\n    pass\n\ndef _private():\n    pass\n\ndef unused():\n    pass\n",
+        )
+        .unwrap();
+        fs::create_dir(dir.path().join("vendor")).unwrap();
+        fs::write(
+            dir.path().join("vendor/lib.py"),
+            "def vendored():\n    pass\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("big.py"),
+            format!("def big():\n    pass\n{}", "#\n".repeat(100)),
+        )
+        .unwrap();
+
+        let config = Config {
+            entry_points: vec!["handler".to_string()],
+            exclude: vec!["vendor/".to_string()],
+            ignore_patterns: vec!["^_".to_string()],
+            max_file_size: 100,
+            ..Default::default()
+        };
+        let mut server = Server::new(config).unwrap();
+        let responses = run_session(
+            &mut server,
+            &[
+                json!({"jsonrpc": "2.0", "id": 1, "method": "analyze", "params": {"path": dir.path()}}),
+            ],
+        );
+
+        assert_eq!(finding_names(&responses[0]), vec!["unused"]);
+        assert_eq!(responses[0]["result"]["files"], 1);
+        let errors = responses[0]["result"]["errors"].as_array().unwrap();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0]["file"].as_str().unwrap().ends_with("big.py"));
+    }
+
+    #[test]
+    fn test_invalid_ignore_pattern_is_rejected() {
+        let config = Config {
+            ignore_patterns: vec!["(".to_string()],
+            ..Default::default()
+        };
+        assert!(Server::new(config).is_err());
+    }
+
+    #[test]
+    fn test_reanalysis_drops_cache_entries_of_removed_files() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("app.py"),
+            "def main():\n    pass\n\nmain()\n",
+        )
+        .unwrap();
+        let old = dir.path().join("old.py");
+        fs::write(&old, "def old():\n    pass\n").unwrap();
+
+        let mut server = Server::new(Config::default()).unwrap();
+        let analyze =
+            json!({"jsonrpc": "2.0", "id": 1, "method": "analyze", "params": {"path": dir.path()}});
+        run_session(&mut server, std::slice::from_ref(&analyze));
+        assert_eq!(server.cache.len(), 2);
+
+        fs::remove_file(&old).unwrap();
+        let responses = run_session(&mut server, &[analyze]);
+        assert_eq!(responses[0]["result"]["files"], 1);
+        assert_eq!(server.cache.len(), 1);
+    }
+
+    #[test]
+    fn test_null_id_is_a_request() {
+        let mut server = Server::new(Config::default()).unwrap();
+        let responses = run_session(
+            &mut server,
+            &[
+                json!({"jsonrpc": "2.0", "method": "findings"}),
+                json!({"jsonrpc": "2.0", "id": null, "method": "findings"}),
+                json!({"jsonrpc": "2.0", "method": "shutdown"}),
+                json!({"jsonrpc": "2.0", "id": 1, "method": "findings"}),
+            ],
+        );
+
+        assert_eq!(responses.len(), 1);
+        assert!(responses[0]["id"].is_null());
+        assert_eq!(responses[0]["result"]["files"], 0);
+    }
+
+    #[test]
+    fn test_unknown_method_and_shutdown() {
+        let mut server = Server::new(Config::default()).unwrap();
+        let responses = run_session(
+            &mut server,
+            &[
+                json!({"jsonrpc": "2.0", "id": 1, "method": "bogus"}),
+                json!({"jsonrpc": "2.0", "id": 2, "method": "shutdown"}),
+                json!({"jsonrpc": "2.0", "id": 3, "method": "findings"}),
+            ],
+        );
+
+        assert_eq!(responses.len(), 2);
+        assert_eq!(responses[0]["error"]["code"], METHOD_NOT_FOUND);
+        assert!(responses[1]["error"].is_null());
+    }
+
+    #[test]
+    fn test_invalid_json_is_a_parse_error() {
+        let mut server = Server::new(Config::default()).unwrap();
+        let mut output = Vec::new();
+
+        server.run(Cursor::new("not json\n"), &mut output).unwrap();
+
+        let response: Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(response["error"]["code"], PARSE_ERROR);
+    }
+}
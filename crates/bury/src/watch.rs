@@ -0,0 +1,217 @@
+//! Watch directories and report when analyzed files change
+//!
+//! File system events arrive in bursts (an editor save is often a write, a
+//! rename and a metadata change), so events are debounced: a change is reported
+//! once no further event has arrived for the debounce interval. Only files the
+//! scanner would analyze count, so `.git/` churn, the parse cache, and files
+//! excluded by `.gitignore`, `.buryignore` or the config never trigger a rerun.
+
+use crate::{Config, Result, Scanner};
+use anyhow::Context;
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::{BTreeSet, HashSet};
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+/// Quiet period after the last event before a change is reported
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Watches scan roots for changes to analyzable files
+pub struct ChangeWatcher {
+    scanners: Vec<Scanner>,
+    extensions: Vec<String>,
+    /// Files found by the last scan, so deleted files are still recognized
+    known: HashSet<PathBuf>,
+    events: Receiver<notify::Result<Event>>,
+    debounce: Duration,
+    // Dropping the watcher stops the events
+    _watcher: RecommendedWatcher,
+}
+
+impl ChangeWatcher {
+    /// Start watching each of `roots` recursively, filtering with the scanner
+    /// settings from `config`
+    pub fn new(roots: &[PathBuf], config: &Config) -> Result<Self> {
+        let (sender, events) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(sender)?;
+
+        let mut scanners = Vec::new();
+        for root in roots {
+            // Event paths are absolute, so scan from the same absolute root
+            let root = root
+                .canonicalize()
+                .with_context(|| format!("Failed to watch {}", root.display()))?;
+            watcher.watch(&root, RecursiveMode::Recursive)?;
+            scanners.push(config.scanner(&root));
+        }
+        let known = scan(&scanners)?;
+        let extensions = if config.extensions.is_empty() {
+            neural_shared::scanner::DEFAULT_EXTENSIONS
+                .iter()
+                .map(|e| e.to_string())
+                .collect()
+        } else {
+            config
+                .extensions
+                .iter()
+                .map(|e| e.trim_start_matches('.').to_string())
+                .collect()
+        };
+
+        Ok(Self {
+            scanners,
+            extensions,
+            known,
+            events,
+            debounce: DEFAULT_DEBOUNCE,
+            _watcher: watcher,
+        })
+    }
+
+    /// Set the quiet period after the last event before a change is reported
+    pub fn with_debounce(mut self, debounce: Duration) -> Self {
+        self.debounce = debounce;
+        self
+    }
+
+    /// Block until analyzable files change and the debounce interval passes
+    /// without further events, returning the changed files.
+    ///
+    /// Returns `None` if `timeout` elapses first; without a timeout, waits
+    /// indefinitely.
+    pub fn wait(&mut self, timeout: Option<Duration>) -> Result<Option<Vec<PathBuf>>> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+
+        loop {
+            let mut candidates = BTreeSet::new();
+
+            // Wait for the first candidate event, then until events stop arriving
+            while candidates.is_empty() {
+                let event = match deadline {
+                    Some(deadline) => {
+                        let remaining = deadline.saturating_duration_since(Instant::now());
+                        match self.events.recv_timeout(remaining) {
+                            Ok(event) => event,
+                            Err(RecvTimeoutError::Timeout) => return Ok(None),
+                            Err(RecvTimeoutError::Disconnected) => break,
+                        }
+                    }
+                    None => match self.events.recv() {
+                        Ok(event) => event,
+                        Err(_) => break,
+                    },
+                };
+                self.collect(event?, &mut candidates);
+            }
+            loop {
+                match self.events.recv_timeout(self.debounce) {
+                    Ok(event) => self.collect(event?, &mut candidates),
+                    Err(RecvTimeoutError::Timeout) => break,
+                    Err(RecvTimeoutError::Disconnected) => {
+                        if candidates.is_empty() {
+                            return Err(anyhow::anyhow!("File watcher stopped"));
+                        }
+                        break;
+                    }
+                }
+            }
+
+            // Rescan to apply the ignore rules; a file counts if it is analyzed
+            // now or was before (deleted or newly ignored)
+            let current = scan(&self.scanners)?;
+            let changed: Vec<PathBuf> = candidates
+                .into_iter()
+                .filter(|path| current.contains(path) || self.known.contains(path))
+                .collect();
+            self.known = current;
+
+            if !changed.is_empty() {
+                return Ok(Some(changed));
+            }
+        }
+    }
+
+    /// Add the paths of an event that could be analyzed files
+    fn collect(&self, event: Event, candidates: &mut BTreeSet<PathBuf>) {
+        if event.kind.is_access() {
+            return;
+        }
+        for path in event.paths {
+            let analyzable = path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| self.extensions.iter().any(|e| e == ext));
+            if analyzable {
+                candidates.insert(path);
+            }
+        }
+    }
+}
+
+/// Files analyzed from any of the roots
+fn scan(scanners: &[Scanner]) -> Result<HashSet<PathBuf>> {
+    let mut files = HashSet::new();
+    for scanner in scanners {
+        files.extend(scanner.scan()?);
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::Path;
+    use std::thread;
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    fn watcher(dir: &Path) -> ChangeWatcher {
+        ChangeWatcher::new(&[dir.to_path_buf()], &Config::default())
+            .unwrap()
+            .with_debounce(Duration::from_millis(100))
+    }
+
+    #[test]
+    fn test_burst_of_writes_is_reported_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("app.py");
+        fs::write(&file, "def main():\n    pass\n").unwrap();
+        let mut watcher = watcher(dir.path());
+
+        for i in 0..3 {
+            fs::write(&file, format!("def main():\n    return {}\n", i)).unwrap();
+            thread::sleep(Duration::from_millis(20));
+        }
+
+        let changed = watcher.wait(Some(TIMEOUT)).unwrap();
+        assert_eq!(changed, Some(vec![file.canonicalize().unwrap()]));
+        assert_eq!(
+            watcher.wait(Some(Duration::from_millis(500))).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_ignored_files_do_not_trigger() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join(".buryignore"), "generated/\n").unwrap();
+        fs::create_dir(dir.path().join("generated")).unwrap();
+        fs::create_dir(dir.path().join(".git")).unwrap();
+        let mut watcher = watcher(dir.path());
+
+        fs::write(dir.path().join("generated/models.py"), "x = 1\n").unwrap();
+        fs::write(dir.path().join(".git/index"), "churn").unwrap();
+        fs::write(dir.path().join("notes.txt"), "todo").unwrap();
+        assert_eq!(
+            watcher.wait(Some(Duration::from_millis(500))).unwrap(),
+            None
+        );
+
+        let added = dir.path().join("app.py");
+        fs::write(&added, "def main():\n    pass\n").unwrap();
+        let changed = watcher.wait(Some(TIMEOUT)).unwrap();
+        assert_eq!(changed, Some(vec![added.canonicalize().unwrap()]));
+    }
+}
@@ -0,0 +1,80 @@
+//! Tests for the `bury::analyze_path` library API
+
+use bury::{analyze_path, analyze_paths, Config};
+use std::fs;
+
+#[test]
+fn test_analyze_path_returns_findings_and_stats() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("app.py"),
+        "def main():\n    helper()\n\ndef helper():\n    pass\n\ndef unused():\n    pass\n\nif __name__ == \"__main__\":\n    main()\n",
+    )
+    .unwrap();
+    fs::write(dir.path().join("broken.py"), "x = = 1\n").unwrap();
+    fs::write(dir.path().join("bad.py"), [0xff, 0xfe, 0x00]).unwrap();
+
+    let result = analyze_path(dir.path(), &Config::default()).unwrap();
+
+    let names: Vec<_> = result
+        .findings
+        .iter()
+        .map(|f| f.symbol.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["unused"]);
+    assert_eq!(result.stats.total_files_scanned, 3);
+    assert_eq!(result.stats.languages["python"], 2);
+    assert_eq!(result.skipped.len(), 1);
+    assert!(result.skipped[0].0.ends_with("bad.py"));
+    assert!(!result.parse_errors.is_empty());
+    assert!(result.parse_errors[0].file.ends_with("broken.py"));
+}
+
+#[test]
+fn test_analyze_path_applies_config() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("app.py"),
+        "def handler():\n    pass\n\ndef unused():\n    pass\n",
+    )
+    .unwrap();
+    fs::create_dir(dir.path().join("migrations")).unwrap();
+    fs::write(
+        dir.path().join("migrations/0001.py"),
+        "def forwards():\n    pass\n",
+    )
+    .unwrap();
+
+    let config = Config {
+        entry_points: vec!["handler".to_string()],
+        exclude: vec!["migrations/".to_string()],
+        ..Default::default()
+    };
+    let result = analyze_path(dir.path(), &config).unwrap();
+
+    let names: Vec<_> = result
+        .findings
+        .iter()
+        .map(|f| f.symbol.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["unused"]);
+    assert_eq!(result.stats.total_files_scanned, 1);
+}
+
+#[test]
+fn test_analyze_paths_deduplicates_overlapping_paths() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir(dir.path().join("pkg")).unwrap();
+    fs::write(dir.path().join("app.py"), "def unused():\n    pass\n").unwrap();
+    fs::write(dir.path().join("pkg/util.py"), "def helper():\n    pass\n").unwrap();
+
+    let paths = vec![
+        dir.path().to_path_buf(),
+        dir.path().join("pkg"),
+        dir.path().join("pkg/../pkg"),
+    ];
+    let result = analyze_paths(&paths, &Config::default()).unwrap();
+
+    assert_eq!(result.stats.total_files_scanned, 2);
+    assert_eq!(result.findings.len(), 2);
+}
@@ -0,0 +1,773 @@
+//! End-to-end tests for the `bury` binary
+
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+
+fn bury(args: &[&str], dir: &Path) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_bury"))
+        .args(args)
+        .arg(dir)
+        .output()
+        .expect("failed to run bury")
+}
+
+#[test]
+fn test_unreadable_file_is_skipped_with_warning() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("good.py"), "def main():\n    pass\n").unwrap();
+    fs::write(dir.path().join("bad.py"), [0xff, 0xfe, 0x00]).unwrap();
+
+    let output = bury(&["--format", "json", "--fail-on", "none"], dir.path());
+
+    assert_eq!(output.status.code(), Some(3));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("1 file(s) could not be analyzed and were skipped:"));
+    assert_eq!(stderr.matches("bad.py").count(), 1);
+}
+
+#[test]
+fn test_broken_file_does_not_stop_analysis_of_the_rest() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("app.py"),
+        "from helpers import used\n\ndef main():\n    used()\n\nif __name__ == \"__main__\":\n    main()\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("helpers.py"),
+        "def used():\n    pass\n\ndef unused():\n    pass\n",
+    )
+    .unwrap();
+    fs::write(dir.path().join("bad.py"), [0xff, 0xfe, 0x00]).unwrap();
+    fs::write(dir.path().join("other.py"), "def orphan():\n    pass\n").unwrap();
+
+    let output = bury(&["--format", "json", "--fail-on", "none"], dir.path());
+
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(finding_names(&output), vec!["orphan", "unused"]);
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let summary = stderr
+        .split("1 file(s) could not be analyzed and were skipped:")
+        .nth(1)
+        .expect("missing skipped-files summary");
+    assert!(summary.contains("bad.py"));
+}
+
+#[test]
+fn test_strict_fails_on_unreadable_file() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("good.py"), "def main():\n    pass\n").unwrap();
+    fs::write(dir.path().join("bad.py"), [0xff, 0xfe, 0x00]).unwrap();
+
+    let output = bury(&["--format", "json", "--strict"], dir.path());
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("could not be analyzed (--strict)"));
+    assert_eq!(stderr.matches("bad.py").count(), 1);
+}
+
+#[test]
+fn test_syntax_error_is_reported_as_warning() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("broken.py"), "def broken(:\n    pass\n").unwrap();
+
+    let output = bury(&["--format", "json", "--fail-on", "none"], dir.path());
+
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Syntax error in"));
+    assert!(stderr.contains("broken.py:1:"));
+}
+
+#[test]
+fn test_strict_fails_on_syntax_error() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("broken.py"), "def broken(:\n    pass\n").unwrap();
+
+    let output = bury(&["--format", "json", "--strict"], dir.path());
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("contain syntax errors"));
+}
+
+#[test]
+fn test_json_summary_counts_files_and_definitions() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("app.py"),
+        "def main():\n    pass\n\ndef helper():\n    pass\n",
+    )
+    .unwrap();
+    fs::write(dir.path().join("util.ts"), "export function format() {}\n").unwrap();
+
+    let output = bury(&["--format", "json"], dir.path());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let summary = &json["summary"];
+    assert_eq!(summary["total_files_scanned"], 2);
+    assert_eq!(summary["total_definitions"], 3);
+    assert_eq!(summary["languages"]["python"], 1);
+    assert_eq!(summary["languages"]["typescript"], 1);
+}
+
+#[test]
+fn test_unused_function_is_reported() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("app.py"),
+        "def main():\n    pass\n\ndef unused():\n    pass\n\nif __name__ == \"__main__\":\n    main()\n",
+    )
+    .unwrap();
+
+    let output = bury(&["--format", "json"], dir.path());
+
+    // High confidence findings fail the run by default, whatever the format
+    assert_eq!(output.status.code(), Some(1));
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let names: Vec<&str> = json["findings"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|f| f["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["unused"]);
+}
+
+#[test]
+fn test_sarif_output() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("app.py"),
+        "def main():\n    pass\n\ndef unused():\n    pass\n\nif __name__ == \"__main__\":\n    main()\n",
+    )
+    .unwrap();
+
+    let output = bury(&["--format", "sarif", "--fail-on", "none"], dir.path());
+
+    assert!(output.status.success());
+    let sarif: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(sarif["version"], "2.1.0");
+    let results = sarif["runs"][0]["results"].as_array().unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0]["ruleId"], "function");
+    assert_eq!(
+        results[0]["locations"][0]["physicalLocation"]["region"]["startLine"],
+        4
+    );
+}
+
+#[test]
+fn test_lsp_output_ranges_span_the_symbol() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("app.py"),
+        "def main():\n    pass\n\ndef unused():\n    x = 1\n    return x\n\nif __name__ == \"__main__\":\n    main()\n",
+    )
+    .unwrap();
+
+    let output = bury(&["--format", "lsp", "--fail-on", "none"], dir.path());
+
+    assert!(output.status.success());
+    let diagnostics: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let diagnostics = diagnostics.as_array().unwrap();
+    assert_eq!(diagnostics.len(), 1);
+    let range = &diagnostics[0]["range"];
+    assert_eq!(range["start"]["line"], 3);
+    assert_eq!(range["end"]["line"], 5);
+    assert_ne!(range["start"], range["end"]);
+    assert_eq!(diagnostics[0]["source"], "bury");
+    assert!(diagnostics[0]["data"]["file"]
+        .as_str()
+        .unwrap()
+        .ends_with("app.py"));
+}
+
+#[test]
+fn test_multiple_paths_are_analyzed_together() {
+    let dir = tempfile::tempdir().unwrap();
+    let lib = dir.path().join("lib");
+    let app = dir.path().join("app");
+    fs::create_dir(&lib).unwrap();
+    fs::create_dir(&app).unwrap();
+    fs::write(
+        lib.join("helpers.py"),
+        "def shared_helper():\n    pass\n\ndef unused_helper():\n    pass\n",
+    )
+    .unwrap();
+    fs::write(
+        app.join("main.py"),
+        "from helpers import shared_helper\n\nshared_helper()\n",
+    )
+    .unwrap();
+
+    let names = |output: &Output| -> Vec<String> {
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        json["findings"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|f| f["name"].as_str().unwrap().to_string())
+            .collect()
+    };
+
+    let alone = bury(&["--format", "json", "--fail-on", "none"], &lib);
+    assert!(names(&alone).contains(&"shared_helper".to_string()));
+
+    let together = bury(
+        &[
+            "--format",
+            "json",
+            "--fail-on",
+            "none",
+            lib.to_str().unwrap(),
+        ],
+        &app,
+    );
+    assert!(together.status.success());
+    assert_eq!(names(&together), vec!["unused_helper"]);
+}
+
+#[test]
+fn test_json_lines_output_has_one_object_per_line() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("app.py"),
+        "def unused():\n    pass\n\ndef stale():\n    pass\n",
+    )
+    .unwrap();
+
+    let output = bury(&["--format", "json-lines", "--fail-on", "none"], dir.path());
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<serde_json::Value> = stdout
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[0]["name"], "unused");
+    assert_eq!(lines[1]["name"], "stale");
+    assert_eq!(lines[2]["summary"]["total_findings"], 2);
+}
+
+#[test]
+fn test_context_shows_source_lines() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("app.py"),
+        "def main():\n    pass\n\ndef unused_helper(x):\n    return x\n",
+    )
+    .unwrap();
+
+    let plain = bury(&["--fail-on", "none"], dir.path());
+    assert!(!String::from_utf8_lossy(&plain.stdout).contains("| def unused_helper(x):"));
+
+    for format in ["terminal", "markdown", "html"] {
+        let output = bury(
+            &["--format", format, "--fail-on", "none", "--context", "1"],
+            dir.path(),
+        );
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(
+            stdout.contains(" 4 | def unused_helper(x):"),
+            "{format}: {stdout}"
+        );
+    }
+}
+
+#[test]
+fn test_min_confidence_hides_weaker_findings() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("app.py"),
+        "def unreferenced():\n    pass\n\ndef _private_helper():\n    pass\n\nclass Plugin:\n    def __eq__(self, other):\n        pass\n",
+    )
+    .unwrap();
+
+    let output = bury(
+        &[
+            "--format",
+            "json",
+            "--fail-on",
+            "none",
+            "--min-confidence",
+            "high",
+        ],
+        dir.path(),
+    );
+    assert!(output.status.success());
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let findings = report["findings"].as_array().unwrap();
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0]["name"], "unreferenced");
+    assert!(findings.iter().all(|f| f["confidence"] == "High"));
+}
+
+#[test]
+fn test_export_graph_as_dot_and_json() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("app.py"),
+        "def main():\n    helper()\n\ndef helper():\n    pass\n\ndef unused():\n    pass\n\nif __name__ == \"__main__\":\n    main()\n",
+    )
+    .unwrap();
+    let out = tempfile::tempdir().unwrap();
+    let dot_path = out.path().join("graph.dot");
+    let json_path = out.path().join("graph.json");
+
+    for path in [&dot_path, &json_path] {
+        let output = bury(
+            &[
+                "--format",
+                "json",
+                "--fail-on",
+                "none",
+                "--export-graph",
+                path.to_str().unwrap(),
+            ],
+            dir.path(),
+        );
+        assert!(output.status.success());
+    }
+
+    let dot = fs::read_to_string(&dot_path).unwrap();
+    assert!(dot.contains("\"main\" -> \"helper\";"));
+
+    let graph: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&json_path).unwrap()).unwrap();
+    assert!(graph["edges"]
+        .as_array()
+        .unwrap()
+        .contains(&serde_json::json!({"from": "main", "to": "helper"})));
+    let unused = graph["nodes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|n| n["name"] == "unused")
+        .unwrap();
+    assert_eq!(unused["reachable"], false);
+}
+
+#[test]
+fn test_files_lists_scanned_files_without_analyzing() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir_all(dir.path().join("src")).unwrap();
+    fs::create_dir_all(dir.path().join("generated")).unwrap();
+    fs::write(dir.path().join("app.py"), "def broken(:\n").unwrap();
+    fs::write(dir.path().join("src/util.ts"), "export function f() {}\n").unwrap();
+    fs::write(dir.path().join("generated/models.py"), "").unwrap();
+    fs::write(dir.path().join("README.md"), "").unwrap();
+    fs::write(dir.path().join(".buryignore"), "generated/\n").unwrap();
+
+    let output = bury(&["files"], dir.path());
+    assert!(output.status.success());
+
+    let mut listed: Vec<String> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::to_string)
+        .collect();
+    listed.sort();
+    let mut scanned: Vec<String> = bury::Scanner::new(dir.path())
+        .scan()
+        .unwrap()
+        .iter()
+        .map(|p| p.display().to_string())
+        .collect();
+    scanned.sort();
+
+    assert_eq!(listed, scanned);
+    assert_eq!(listed.len(), 2);
+}
+
+#[test]
+fn test_no_ignore_includes_gitignored_files() {
+    let dir = tempfile::tempdir().unwrap();
+    // .gitignore only applies inside a git repository
+    fs::create_dir(dir.path().join(".git")).unwrap();
+    fs::create_dir(dir.path().join("vendor")).unwrap();
+    fs::write(dir.path().join(".gitignore"), "vendor/\n").unwrap();
+    fs::write(
+        dir.path().join("vendor/lib.py"),
+        "def vendored():\n    pass\n",
+    )
+    .unwrap();
+    fs::write(dir.path().join("app.py"), "def unused():\n    pass\n").unwrap();
+
+    let output = bury(&["--format", "json"], dir.path());
+    assert_eq!(finding_names(&output), vec!["unused"]);
+
+    let output = bury(&["--format", "json", "--no-ignore"], dir.path());
+    assert_eq!(finding_names(&output), vec!["unused", "vendored"]);
+}
+
+#[test]
+fn test_max_file_size_skips_large_files() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("small.py"), "x = 1\n").unwrap();
+    fs::write(dir.path().join("bundle.js"), "x".repeat(200)).unwrap();
+
+    let output = bury(
+        &["--max-file-size", "100", "--verbose", "files"],
+        dir.path(),
+    );
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("small.py"));
+    assert!(!stdout.contains("bundle.js"));
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("Skipped 1 file(s) over the size limit")
+    );
+
+    let output = bury(&["--max-file-size", "0", "files"], dir.path());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("bundle.js"));
+}
+
+#[test]
+fn test_oversized_file_is_reported_as_skipped() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("app.py"),
+        "def main():\n    pass\n\nmain()\n",
+    )
+    .unwrap();
+    fs::write(dir.path().join("bundle.js"), "x".repeat(200)).unwrap();
+
+    let output = bury(&["--format", "json", "--max-file-size", "100"], dir.path());
+    assert_eq!(output.status.code(), Some(3));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(stderr.matches("bundle.js").count(), 1);
+    assert!(stderr.contains("size limit"));
+
+    let output = bury(
+        &["--format", "json", "--max-file-size", "100", "--strict"],
+        dir.path(),
+    );
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("could not be analyzed (--strict)"));
+}
+
+/// Run bury with `--stdin`, writing `input` to its standard input
+fn bury_stdin(args: &[&str], dir: &Path, input: &str) -> Output {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_bury"))
+        .args(args)
+        .arg("--stdin")
+        .arg(dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn test_stdin_analyzes_only_listed_files() {
+    let dir = tempfile::tempdir().unwrap();
+    for name in ["a.py", "b.py", "c.py"] {
+        fs::write(dir.path().join(name), "def unused():\n    pass\n").unwrap();
+    }
+
+    let input = format!(
+        "{}\n{}\n",
+        dir.path().join("a.py").display(),
+        dir.path().join("c.py").display()
+    );
+    let output = bury_stdin(&["--format", "json"], dir.path(), &input);
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["summary"]["total_files_scanned"], 2);
+    let mut files: Vec<&str> = json["findings"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|f| f["file"].as_str().unwrap())
+        .collect();
+    files.sort();
+    assert_eq!(
+        files,
+        vec![
+            dir.path().join("a.py").to_str().unwrap(),
+            dir.path().join("c.py").to_str().unwrap()
+        ]
+    );
+}
+
+#[test]
+fn test_stdin_respects_ignore_files_and_size_limit() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir(dir.path().join("generated")).unwrap();
+    fs::write(dir.path().join(".buryignore"), "generated/\n").unwrap();
+    fs::write(
+        dir.path().join("generated/schema.py"),
+        "def unused():\n    pass\n",
+    )
+    .unwrap();
+    fs::write(dir.path().join("app.py"), "def unused():\n    pass\n").unwrap();
+    fs::write(dir.path().join("bundle.js"), "x".repeat(200)).unwrap();
+
+    let input: String = ["generated/schema.py", "app.py", "bundle.js"]
+        .iter()
+        .map(|name| format!("{}\n", dir.path().join(name).display()))
+        .collect();
+    let output = bury_stdin(
+        &[
+            "--format",
+            "json",
+            "--fail-on",
+            "none",
+            "--max-file-size",
+            "100",
+        ],
+        dir.path(),
+        &input,
+    );
+
+    assert_eq!(output.status.code(), Some(3));
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["summary"]["total_files_scanned"], 1);
+    assert_eq!(finding_names(&output), vec!["unused"]);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("bundle.js"));
+}
+
+#[test]
+fn test_terminal_output_has_no_color_when_piped() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("app.py"), "def unused():\n    pass\n").unwrap();
+
+    let output = bury(&[], dir.path());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("app.py:1:5"));
+    assert!(!stdout.contains('\x1b'));
+}
+
+fn finding_names(output: &Output) -> Vec<String> {
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let mut names: Vec<String> = json["findings"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|f| f["name"].as_str().unwrap().to_string())
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn test_baseline_suppresses_existing_findings() {
+    let dir = tempfile::tempdir().unwrap();
+    let src = dir.path().join("src");
+    fs::create_dir(&src).unwrap();
+    fs::write(src.join("app.py"), "def legacy():\n    pass\n").unwrap();
+    let baseline = dir.path().join("bury-baseline.json");
+    let baseline_arg = baseline.to_str().unwrap();
+
+    // First run writes the baseline and reports nothing
+    let output = bury(&["--format", "json", "--baseline", baseline_arg], &src);
+    assert!(output.status.success());
+    assert!(finding_names(&output).is_empty());
+    let written: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&baseline).unwrap()).unwrap();
+    assert!(written["findings"]["function:app.py:legacy"].is_object());
+
+    // Unchanged code reports no new findings, even after lines move
+    fs::write(src.join("app.py"), "\n\ndef legacy():\n    pass\n").unwrap();
+    let output = bury(&["--format", "json", "--baseline", baseline_arg], &src);
+    assert!(finding_names(&output).is_empty());
+
+    // A newly added dead function is reported
+    fs::write(
+        src.join("app.py"),
+        "def legacy():\n    pass\n\ndef fresh():\n    pass\n",
+    )
+    .unwrap();
+    let output = bury(&["--format", "json", "--baseline", baseline_arg], &src);
+    assert_eq!(finding_names(&output), vec!["fresh"]);
+}
+
+#[test]
+fn test_init_writes_config_that_is_loaded() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_bury"))
+        .arg("init")
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let config: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(dir.path().join(".bury.json")).unwrap()).unwrap();
+    assert_eq!(config["min_confidence"], "Low");
+
+    fs::write(dir.path().join("app.py"), "def unused():\n    pass\n").unwrap();
+    let output = bury(&["--format", "json"], dir.path());
+    assert_eq!(finding_names(&output), vec!["unused"]);
+}
+
+#[test]
+fn test_config_entry_points_and_excludes() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir(dir.path().join("generated")).unwrap();
+    fs::write(
+        dir.path().join("generated/schema.py"),
+        "def generated():\n    pass\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("app.py"),
+        "def handler():\n    pass\n\ndef unused():\n    pass\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join(".bury.json"),
+        r#"{ "entry_points": ["handler"], "exclude": ["generated/"] }"#,
+    )
+    .unwrap();
+
+    let output = bury(&["--format", "json"], dir.path());
+
+    assert_eq!(finding_names(&output), vec!["unused"]);
+}
+
+#[test]
+fn test_entry_point_flag_marks_function_reachable() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("handler.py"),
+        "def lambda_handler(event, context):\n    pass\n\ndef warmup():\n    pass\n\ndef unused():\n    pass\n",
+    )
+    .unwrap();
+
+    let output = bury(
+        &["--format", "json", "--entry-point", "lambda_handler"],
+        dir.path(),
+    );
+    assert_eq!(finding_names(&output), vec!["unused", "warmup"]);
+
+    let output = bury(
+        &["--format", "json", "--entry-point", "lambda_handler,warmup"],
+        dir.path(),
+    );
+    assert_eq!(finding_names(&output), vec!["unused"]);
+}
+
+#[test]
+fn test_ignore_pattern_flag_marks_matching_functions_reachable() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("handlers.py"),
+        "def handle_get():\n    pass\n\ndef handle_post():\n    pass\n\ndef on_event():\n    pass\n\ndef unused():\n    pass\n",
+    )
+    .unwrap();
+
+    let output = bury(
+        &[
+            "--format",
+            "json",
+            "--ignore-pattern",
+            "^handle_",
+            "--ignore-pattern",
+            "^on_",
+        ],
+        dir.path(),
+    );
+    assert_eq!(finding_names(&output), vec!["unused"]);
+
+    let output = bury(
+        &["--format", "json", "--ignore-pattern", "handle_("],
+        dir.path(),
+    );
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Invalid ignore pattern `handle_(`"));
+}
+
+#[test]
+fn test_fail_on_threshold() {
+    let dir = tempfile::tempdir().unwrap();
+    // An unused import is a Medium confidence finding
+    fs::write(dir.path().join("app.py"), "import os\n").unwrap();
+
+    let output = bury(&["--format", "json", "--fail-on", "medium"], dir.path());
+    assert_eq!(output.status.code(), Some(1));
+
+    let output = bury(&["--format", "json"], dir.path());
+    assert!(output.status.success());
+
+    let output = bury(&["--format", "json", "--fail-on", "low"], dir.path());
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn test_unchanged_files_are_cached() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("app.py"),
+        "def main():\n    pass\n\nmain()\n",
+    )
+    .unwrap();
+    fs::write(dir.path().join("util.ts"), "export function format() {}\n").unwrap();
+
+    let first = bury(&["--verbose"], dir.path());
+    assert!(String::from_utf8_lossy(&first.stderr).contains("Cache: 0 hits, 2 misses"));
+    assert!(dir.path().join(".bury/cache.json").is_file());
+
+    let second = bury(&["--verbose"], dir.path());
+    assert!(String::from_utf8_lossy(&second.stderr).contains("Cache: 2 hits, 0 misses"));
+
+    let uncached = bury(&["--verbose", "--no-cache"], dir.path());
+    assert!(!String::from_utf8_lossy(&uncached.stderr).contains("Cache:"));
+}
+
+#[test]
+fn test_single_file_is_cached_in_its_directory() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("app.py");
+    fs::write(&file, "def main():\n    pass\n\nmain()\n").unwrap();
+
+    let first = bury(&["--verbose"], &file);
+    assert!(String::from_utf8_lossy(&first.stderr).contains("Cache: 0 hits, 1 misses"));
+    assert!(dir.path().join(".bury/cache.json").is_file());
+
+    let second = bury(&["--verbose"], &file);
+    assert!(String::from_utf8_lossy(&second.stderr).contains("Cache: 1 hits, 0 misses"));
+}
+
+#[test]
+fn test_progress_output_stays_off_stdout() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("app.py"),
+        "def main():\n    pass\n\ndef unused():\n    pass\n\nmain()\n",
+    )
+    .unwrap();
+    fs::write(dir.path().join("broken.py"), "def broken(:\n").unwrap();
+
+    let output = bury(&["-vv", "--format", "json"], dir.path());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    serde_json::from_str::<serde_json::Value>(&stdout).expect("stdout is only the JSON report");
+    for progress in ["🔍", "📁", "💾", "✅", "⚠️", "Scanning", "Cache:"] {
+        assert!(!stdout.contains(progress), "{} on stdout", progress);
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Scanning for files"));
+    assert!(stderr.contains("Syntax error in"));
+
+    let quiet = bury(&["--quiet", "--format", "json"], dir.path());
+    assert_eq!(quiet.stdout, output.stdout);
+    assert!(quiet.stderr.is_empty());
+}
@@ -0,0 +1,376 @@
+//! Per-function complexity metrics computed from tree-sitter nodes
+
+use neural_shared::Language;
+use std::collections::HashSet;
+use tree_sitter::Node;
+
+/// How a syntax node contributes to cognitive complexity
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Construct {
+    /// Control flow that breaks linear flow and nests (if, loops, catch, ternary):
+    /// +1, plus the current nesting level, and increases nesting for its children
+    Structural,
+    /// Continuation of a structure (else, elif, else if): +1, no nesting penalty
+    Hybrid,
+    /// Anonymous function or lambda: no increment, but increases nesting
+    Nesting,
+    /// First operator in a sequence of like boolean operators: +1
+    BooleanSequence,
+    /// No contribution
+    Other,
+}
+
+/// Check whether a node defines a named function that is measured on its own.
+///
+/// These nodes are skipped when measuring an enclosing function.
+pub(crate) fn is_named_function(node: Node, language: Language) -> bool {
+    match language {
+        Language::Python => node.kind() == "function_definition",
+        Language::TypeScript | Language::JavaScript => match node.kind() {
+            "function_declaration" | "generator_function_declaration" | "method_definition" => true,
+            "variable_declarator" => node
+                .child_by_field_name("value")
+                .map(|v| matches!(v.kind(), "arrow_function" | "function"))
+                .unwrap_or(false),
+            _ => false,
+        },
+        Language::Go => matches!(node.kind(), "function_declaration" | "method_declaration"),
+        Language::Rust => node.kind() == "function_item",
+    }
+}
+
+/// Cyclomatic complexity: 1 + the number of decision points in the function body
+pub(crate) fn cyclomatic(body: Node, language: Language, source: &[u8]) -> u32 {
+    1 + count_decisions(body, language, source)
+}
+
+fn count_decisions(node: Node, language: Language, source: &[u8]) -> u32 {
+    let mut count = 0;
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if is_named_function(child, language) {
+            continue;
+        }
+        if is_decision(child, language, source) {
+            count += 1;
+        }
+        count += count_decisions(child, language, source);
+    }
+
+    count
+}
+
+fn is_decision(node: Node, language: Language, source: &[u8]) -> bool {
+    match language {
+        Language::Python => matches!(
+            node.kind(),
+            "if_statement"
+                | "elif_clause"
+                | "for_statement"
+                | "while_statement"
+                | "except_clause"
+                | "conditional_expression"
+                | "boolean_operator"
+                | "if_clause"
+                | "case_clause"
+        ),
+        Language::TypeScript | Language::JavaScript => match node.kind() {
+            "if_statement" | "for_statement" | "for_in_statement" | "while_statement"
+            | "do_statement" | "catch_clause" | "ternary_expression" | "switch_case" => true,
+            "binary_expression" => is_logical_operator(operator(node, source).as_deref()),
+            _ => false,
+        },
+        Language::Go => match node.kind() {
+            "if_statement" | "for_statement" | "expression_case" | "type_case"
+            | "communication_case" => true,
+            "binary_expression" => is_logical_operator(operator(node, source).as_deref()),
+            _ => false,
+        },
+        Language::Rust => match node.kind() {
+            "if_expression" | "for_expression" | "while_expression" | "match_arm" => true,
+            "binary_expression" => is_logical_operator(operator(node, source).as_deref()),
+            _ => false,
+        },
+    }
+}
+
+/// Halstead volume: `N * log2(n)`, where `N` is the total and `n` the distinct
+/// number of operators and operands in the function.
+///
+/// Named leaf tokens (identifiers, literals) are operands and anonymous tokens
+/// (keywords, operators, punctuation) are operators. String literals count as a
+/// single operand and comments are ignored.
+pub(crate) fn halstead_volume(body: Node, language: Language, source: &[u8]) -> f64 {
+    let mut counts = HalsteadCounts::default();
+    count_tokens(body, language, source, &mut counts);
+
+    let total = (counts.total_operators + counts.total_operands) as f64;
+    let distinct = (counts.operators.len() + counts.operands.len()) as f64;
+    if distinct < 2.0 {
+        return 0.0;
+    }
+    total * distinct.log2()
+}
+
+#[derive(Default)]
+struct HalsteadCounts<'a> {
+    operators: HashSet<&'a str>,
+    operands: HashSet<&'a str>,
+    total_operators: usize,
+    total_operands: usize,
+}
+
+fn count_tokens<'a>(
+    node: Node,
+    language: Language,
+    source: &'a [u8],
+    counts: &mut HalsteadCounts<'a>,
+) {
+    if node.is_extra() || node.kind().contains("comment") {
+        return;
+    }
+
+    let is_string = matches!(
+        node.kind(),
+        "string"
+            | "template_string"
+            | "string_literal"
+            | "raw_string_literal"
+            | "char_literal"
+            | "interpreted_string_literal"
+            | "rune_literal"
+    );
+    if node.child_count() == 0 || is_string {
+        let token = node.utf8_text(source).unwrap_or_default();
+        if node.is_named() {
+            counts.operands.insert(token);
+            counts.total_operands += 1;
+        } else {
+            counts.operators.insert(token);
+            counts.total_operators += 1;
+        }
+        return;
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if is_named_function(child, language) {
+            continue;
+        }
+        count_tokens(child, language, source, counts);
+    }
+}
+
+/// Maintainability index on a 0–100 scale, from Halstead volume, cyclomatic
+/// complexity and lines of code:
+///
+/// `max(0, (171 - 5.2 ln V - 0.23 CC - 16.2 ln LOC) * 100 / 171)`
+///
+/// Higher is easier to maintain.
+pub(crate) fn maintainability_index(halstead_volume: f64, cyclomatic: u32, lines: u32) -> f64 {
+    let raw = 171.0
+        - 5.2 * halstead_volume.max(1.0).ln()
+        - 0.23 * cyclomatic as f64
+        - 16.2 * (lines.max(1) as f64).ln();
+    (raw * 100.0 / 171.0).clamp(0.0, 100.0)
+}
+
+/// Maximum nesting of control-flow blocks inside the function body.
+///
+/// `elif`/`else`/`else if` branches sit at the same depth as their `if`, and
+/// `catch`/`finally` at the same depth as their `try`.
+pub(crate) fn nesting_depth(body: Node, language: Language) -> u32 {
+    max_depth(body, 0, language)
+}
+
+fn max_depth(node: Node, depth: u32, language: Language) -> u32 {
+    let mut max = depth;
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if is_named_function(child, language) {
+            continue;
+        }
+        let child_depth = if opens_block(child, language) {
+            depth + 1
+        } else {
+            depth
+        };
+        max = max.max(max_depth(child, child_depth, language));
+    }
+
+    max
+}
+
+fn opens_block(node: Node, language: Language) -> bool {
+    match language {
+        Language::Python => matches!(
+            node.kind(),
+            "if_statement"
+                | "for_statement"
+                | "while_statement"
+                | "try_statement"
+                | "with_statement"
+                | "match_statement"
+        ),
+        Language::TypeScript | Language::JavaScript => match node.kind() {
+            "if_statement" => parent_kind(node) != Some("else_clause"),
+            "for_statement" | "for_in_statement" | "while_statement" | "do_statement"
+            | "try_statement" | "switch_statement" => true,
+            _ => false,
+        },
+        Language::Go => match node.kind() {
+            "if_statement" => !is_go_else_branch(node),
+            "for_statement"
+            | "expression_switch_statement"
+            | "type_switch_statement"
+            | "select_statement" => true,
+            _ => false,
+        },
+        Language::Rust => match node.kind() {
+            "if_expression" => parent_kind(node) != Some("else_clause"),
+            "for_expression" | "while_expression" | "loop_expression" | "match_expression" => true,
+            _ => false,
+        },
+    }
+}
+
+/// Cognitive complexity following the SonarSource rules.
+///
+/// Each control-flow structure adds 1, plus 1 for every level of nesting it sits
+/// in. `else`/`elif` branches add 1 without a nesting penalty, and each sequence
+/// of like boolean operators (`a && b && c`) adds 1.
+pub(crate) fn cognitive(body: Node, language: Language, source: &[u8]) -> u32 {
+    score_children(body, 0, language, source)
+}
+
+fn score_children(node: Node, nesting: u32, language: Language, source: &[u8]) -> u32 {
+    let mut score = 0;
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if is_named_function(child, language) {
+            continue;
+        }
+        score += score_node(child, nesting, language, source);
+    }
+
+    score
+}
+
+fn score_node(node: Node, nesting: u32, language: Language, source: &[u8]) -> u32 {
+    let (increment, child_nesting) = match classify(node, language, source) {
+        Construct::Structural => (1 + nesting, nesting + 1),
+        Construct::Hybrid | Construct::BooleanSequence => (1, nesting),
+        Construct::Nesting => (0, nesting + 1),
+        Construct::Other => (0, nesting),
+    };
+
+    increment + score_children(node, child_nesting, language, source)
+}
+
+fn classify(node: Node, language: Language, source: &[u8]) -> Construct {
+    match language {
+        Language::Python => match node.kind() {
+            "if_statement"
+            | "for_statement"
+            | "while_statement"
+            | "except_clause"
+            | "conditional_expression"
+            | "match_statement" => Construct::Structural,
+            "elif_clause" | "else_clause" => Construct::Hybrid,
+            "lambda" => Construct::Nesting,
+            "boolean_operator" => boolean_sequence(node, source),
+            _ => Construct::Other,
+        },
+        Language::TypeScript | Language::JavaScript => match node.kind() {
+            // `else if` is a continuation of the enclosing `if`, not a nested one
+            "if_statement" if parent_kind(node) == Some("else_clause") => Construct::Hybrid,
+            "if_statement" | "for_statement" | "for_in_statement" | "while_statement"
+            | "do_statement" | "catch_clause" | "ternary_expression" | "switch_statement" => {
+                Construct::Structural
+            }
+            "else_clause" if first_named_child_kind(node) == Some("if_statement") => {
+                Construct::Other
+            }
+            "else_clause" => Construct::Hybrid,
+            "arrow_function" | "function" | "generator_function" => Construct::Nesting,
+            "binary_expression" if is_logical_operator(operator(node, source).as_deref()) => {
+                boolean_sequence(node, source)
+            }
+            _ => Construct::Other,
+        },
+        Language::Go => match node.kind() {
+            // Go has no else clause: the `alternative` of an `if` is its else block,
+            // or the `if` of an `else if`
+            "if_statement" | "block" if is_go_else_branch(node) => Construct::Hybrid,
+            "if_statement"
+            | "for_statement"
+            | "expression_switch_statement"
+            | "type_switch_statement"
+            | "select_statement" => Construct::Structural,
+            "func_literal" => Construct::Nesting,
+            "binary_expression" if is_logical_operator(operator(node, source).as_deref()) => {
+                boolean_sequence(node, source)
+            }
+            _ => Construct::Other,
+        },
+        Language::Rust => match node.kind() {
+            "if_expression" if parent_kind(node) == Some("else_clause") => Construct::Hybrid,
+            "if_expression" | "for_expression" | "while_expression" | "loop_expression"
+            | "match_expression" => Construct::Structural,
+            "else_clause" if first_named_child_kind(node) == Some("if_expression") => {
+                Construct::Other
+            }
+            "else_clause" => Construct::Hybrid,
+            "closure_expression" => Construct::Nesting,
+            "binary_expression" if is_logical_operator(operator(node, source).as_deref()) => {
+                boolean_sequence(node, source)
+            }
+            _ => Construct::Other,
+        },
+    }
+}
+
+/// Whether a Go node is the `else` branch of an `if` statement
+fn is_go_else_branch(node: Node) -> bool {
+    node.parent()
+        .filter(|p| p.kind() == "if_statement")
+        .and_then(|p| p.child_by_field_name("alternative"))
+        == Some(node)
+}
+
+/// A boolean operator only starts a new sequence when its parent is not the same operator
+fn boolean_sequence(node: Node, source: &[u8]) -> Construct {
+    let op = operator(node, source);
+    let continues_sequence = node
+        .parent()
+        .filter(|p| p.kind() == node.kind())
+        .map(|p| operator(p, source) == op)
+        .unwrap_or(false);
+
+    if continues_sequence {
+        Construct::Other
+    } else {
+        Construct::BooleanSequence
+    }
+}
+
+fn operator(node: Node, source: &[u8]) -> Option<String> {
+    node.child_by_field_name("operator")
+        .and_then(|op| op.utf8_text(source).ok())
+        .map(|op| op.to_string())
+}
+
+fn is_logical_operator(op: Option<&str>) -> bool {
+    matches!(op, Some("&&") | Some("||") | Some("??"))
+}
+
+fn parent_kind(node: Node) -> Option<&'static str> {
+    node.parent().map(|p| p.kind())
+}
+
+fn first_named_child_kind(node: Node) -> Option<&'static str> {
+    node.named_child(0).map(|c| c.kind())
+}
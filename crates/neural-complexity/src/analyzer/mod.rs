@@ -1,21 +1,45 @@
 //! Complexity analysis module
 
-use neural_shared::ParsedFile;
+mod metrics;
+
+use crate::Result;
+use anyhow::anyhow;
+use neural_shared::parser::{self, Grammar, Location};
+use neural_shared::{Language, Symbol, SymbolKind};
 use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tree_sitter::Node;
 
 /// Complexity analyzer
-pub struct ComplexityAnalyzer {
-    // Placeholder for now
-}
+///
+/// Parses source files with tree-sitter and measures every named function and
+/// method. Parsers come from the shared per-thread pool, which also picks the
+/// TSX grammar for `.tsx` files and the JavaScript grammar for `.js` files.
+pub struct ComplexityAnalyzer;
 
 impl ComplexityAnalyzer {
     pub fn new() -> Self {
-        Self {}
+        Self
     }
 
-    pub fn analyze(&self, _file: &ParsedFile) -> ComplexityMetrics {
-        // TODO: Implement actual complexity analysis
-        ComplexityMetrics::default()
+    /// Measure the complexity of every function in a source file
+    pub fn analyze(&self, source: &str, file_path: &Path) -> Result<Vec<FunctionComplexity>> {
+        let language = Language::from_path(file_path)?;
+
+        let tree = parser::with_parser(Grammar::for_path(file_path)?, |parser| {
+            parser.parse(source, None)
+        })?
+        .ok_or_else(|| anyhow!("Failed to parse {}", file_path.display()))?;
+
+        let mut functions = Vec::new();
+        let context = FileContext {
+            source: source.as_bytes(),
+            file: file_path.to_string_lossy().to_string(),
+            language,
+        };
+        collect_functions(tree.root_node(), &context, None, &mut functions);
+
+        Ok(functions)
     }
 }
 
@@ -25,6 +49,215 @@ impl Default for ComplexityAnalyzer {
     }
 }
 
+struct FileContext<'a> {
+    source: &'a [u8],
+    file: String,
+    language: Language,
+}
+
+/// Walk the tree, measuring each named function and tracking the enclosing class
+fn collect_functions(
+    node: Node,
+    context: &FileContext,
+    current_class: Option<&str>,
+    functions: &mut Vec<FunctionComplexity>,
+) {
+    let is_function = metrics::is_named_function(node, context.language);
+    if is_function {
+        if let Some(function) = measure_function(node, context, current_class) {
+            functions.push(function);
+        }
+    }
+
+    // Functions nested inside a function body are not methods of the outer class
+    let child_class = match node.kind() {
+        "class_definition" | "class_declaration" | "class" => node
+            .child_by_field_name("name")
+            .and_then(|n| n.utf8_text(context.source).ok()),
+        _ if is_function => None,
+        _ => current_class,
+    };
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_functions(child, context, child_class, functions);
+    }
+}
+
+fn measure_function(
+    node: Node,
+    context: &FileContext,
+    current_class: Option<&str>,
+) -> Option<FunctionComplexity> {
+    let name_node = node.child_by_field_name("name")?;
+    let name = name_node.utf8_text(context.source).ok()?.to_string();
+
+    // For `const f = () => {}` the function itself is the declarator's value
+    let body = if node.kind() == "variable_declarator" {
+        node.child_by_field_name("value")?
+    } else {
+        node
+    };
+
+    let kind = match current_class {
+        Some(class_name) if node.kind() != "variable_declarator" => SymbolKind::Method {
+            class_name: class_name.to_string(),
+        },
+        _ => SymbolKind::Function,
+    };
+
+    let pos = name_node.start_position();
+    let symbol = Symbol::new(
+        name,
+        kind,
+        Location {
+            file: context.file.clone(),
+            line: pos.row + 1,
+            column: pos.column,
+            end_line: node.end_position().row + 1,
+            end_column: node.end_position().column,
+        },
+    );
+
+    // Span of the whole definition, not just its name
+    let lines_of_code = (node.end_position().row - node.start_position().row + 1) as u32;
+
+    let cyclomatic = metrics::cyclomatic(body, context.language, context.source);
+    let halstead_volume = metrics::halstead_volume(body, context.language, context.source);
+    let metrics = ComplexityMetrics {
+        cyclomatic,
+        cognitive: metrics::cognitive(body, context.language, context.source),
+        lines_of_code,
+        nesting_depth: metrics::nesting_depth(body, context.language),
+        halstead_volume: round(halstead_volume),
+        maintainability_index: round(metrics::maintainability_index(
+            halstead_volume,
+            cyclomatic,
+            lines_of_code,
+        )),
+    };
+
+    Some(FunctionComplexity { symbol, metrics })
+}
+
+/// Round to two decimals
+pub(crate) fn round(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+/// Complexity metrics for a single function or method
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionComplexity {
+    pub symbol: Symbol,
+    pub metrics: ComplexityMetrics,
+}
+
+/// A function whose cyclomatic complexity exceeds the threshold, or whose
+/// maintainability index is below the minimum, in the shape the shared
+/// reporters expect
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComplexityFinding {
+    pub symbol: Symbol,
+    pub metrics: ComplexityMetrics,
+    pub reason: String,
+    /// Cyclomatic complexity the function was measured against
+    pub threshold: u32,
+}
+
+impl ComplexityFinding {
+    /// Report `function` if its cyclomatic complexity exceeds `threshold`
+    pub fn new(function: FunctionComplexity, threshold: u32) -> Option<Self> {
+        Self::check(function, threshold, None)
+    }
+
+    /// Report `function` if its cyclomatic complexity exceeds `threshold` or its
+    /// maintainability index is below `min_maintainability`
+    pub fn check(
+        function: FunctionComplexity,
+        threshold: u32,
+        min_maintainability: Option<f64>,
+    ) -> Option<Self> {
+        let metrics = &function.metrics;
+        let mut reasons = Vec::new();
+        if metrics.cyclomatic > threshold {
+            reasons.push(format!(
+                "Cyclomatic complexity {} exceeds {} (cognitive complexity {})",
+                metrics.cyclomatic, threshold, metrics.cognitive
+            ));
+        }
+        if let Some(min) = min_maintainability {
+            if metrics.maintainability_index < min {
+                reasons.push(format!(
+                    "Maintainability index {:.2} is below {}",
+                    metrics.maintainability_index, min
+                ));
+            }
+        }
+        if reasons.is_empty() {
+            return None;
+        }
+
+        let reason = reasons.join("; ");
+        Some(Self {
+            symbol: function.symbol,
+            metrics: function.metrics,
+            reason,
+            threshold,
+        })
+    }
+}
+
+impl neural_shared::report::Finding for ComplexityFinding {
+    fn kind(&self) -> String {
+        format!("{:?}", self.symbol.kind)
+    }
+
+    fn name(&self) -> String {
+        self.symbol.name.clone()
+    }
+
+    fn file(&self) -> String {
+        self.symbol.location.file.clone()
+    }
+
+    fn line(&self) -> usize {
+        self.symbol.location.line
+    }
+
+    fn column(&self) -> usize {
+        self.symbol.location.column
+    }
+
+    fn end_line(&self) -> usize {
+        self.symbol.location.end_line
+    }
+
+    fn end_column(&self) -> usize {
+        self.symbol.location.end_column
+    }
+
+    fn reason(&self) -> String {
+        self.reason.clone()
+    }
+
+    /// How far past the threshold the function is: more than twice the
+    /// threshold is High, more than one and a half times Medium, anything
+    /// else (including a low maintainability index alone) Low
+    fn confidence(&self) -> String {
+        // Compare in u64 so large thresholds cannot overflow
+        let cyclomatic = u64::from(self.metrics.cyclomatic);
+        let threshold = u64::from(self.threshold);
+        let band = if cyclomatic > threshold * 2 {
+            "High"
+        } else if cyclomatic * 2 > threshold * 3 {
+            "Medium"
+        } else {
+            "Low"
+        };
+        band.to_string()
+    }
+}
+
 /// Complexity metrics for a symbol
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ComplexityMetrics {
@@ -32,4 +265,497 @@ pub struct ComplexityMetrics {
     pub cognitive: u32,
     pub lines_of_code: u32,
     pub nesting_depth: u32,
+    /// Halstead volume of the function's operators and operands
+    pub halstead_volume: f64,
+    /// Maintainability index, 0 (hard to maintain) to 100
+    pub maintainability_index: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn analyze_python(source: &str) -> Vec<FunctionComplexity> {
+        ComplexityAnalyzer::new()
+            .analyze(source, Path::new("test.py"))
+            .unwrap()
+    }
+
+    fn analyze_typescript(source: &str) -> Vec<FunctionComplexity> {
+        ComplexityAnalyzer::new()
+            .analyze(source, Path::new("test.ts"))
+            .unwrap()
+    }
+
+    fn analyze_go(source: &str) -> Vec<FunctionComplexity> {
+        ComplexityAnalyzer::new()
+            .analyze(source, Path::new("test.go"))
+            .unwrap()
+    }
+
+    fn analyze_rust(source: &str) -> Vec<FunctionComplexity> {
+        ComplexityAnalyzer::new()
+            .analyze(source, Path::new("test.rs"))
+            .unwrap()
+    }
+
+    fn analyze_tsx(source: &str) -> Vec<FunctionComplexity> {
+        ComplexityAnalyzer::new()
+            .analyze(source, Path::new("test.tsx"))
+            .unwrap()
+    }
+
+    fn analyze_javascript(source: &str) -> Vec<FunctionComplexity> {
+        ComplexityAnalyzer::new()
+            .analyze(source, Path::new("test.js"))
+            .unwrap()
+    }
+
+    fn metrics_for<'a>(functions: &'a [FunctionComplexity], name: &str) -> &'a ComplexityMetrics {
+        &functions
+            .iter()
+            .find(|f| f.symbol.name == name)
+            .unwrap()
+            .metrics
+    }
+
+    #[test]
+    fn test_nested_if_scores_higher_than_sibling_ifs() {
+        let functions = analyze_python(
+            r#"
+def nested(items):
+    for item in items:
+        if item:
+            print(item)
+
+def siblings(a, b):
+    if a:
+        print(a)
+    if b:
+        print(b)
+"#,
+        );
+
+        let nested = metrics_for(&functions, "nested");
+        let siblings = metrics_for(&functions, "siblings");
+
+        assert_eq!(nested.cyclomatic, 3);
+        assert_eq!(siblings.cyclomatic, 3);
+        assert_eq!(nested.cognitive, 3); // for (+1), if nested once (+2)
+        assert_eq!(siblings.cognitive, 2); // two top-level ifs (+1 each)
+        assert!(nested.cognitive > siblings.cognitive);
+    }
+
+    #[test]
+    fn test_python_else_branches_and_boolean_sequences() {
+        let functions = analyze_python(
+            r#"
+def classify(x, y, z):
+    if x and y and z:
+        return 1
+    elif x or y:
+        return 2
+    else:
+        return 3
+"#,
+        );
+
+        let metrics = metrics_for(&functions, "classify");
+
+        // if (+1), `and` sequence (+1), elif (+1), `or` sequence (+1), else (+1)
+        assert_eq!(metrics.cognitive, 5);
+        // 1 + if + elif + two `and` + one `or`
+        assert_eq!(metrics.cyclomatic, 6);
+    }
+
+    #[test]
+    fn test_typescript_else_if_is_not_nested() {
+        let functions = analyze_typescript(
+            r#"
+function route(kind: string) {
+    if (kind === "a") {
+        return 1;
+    } else if (kind === "b") {
+        return 2;
+    } else {
+        return 3;
+    }
+}
+"#,
+        );
+
+        let metrics = metrics_for(&functions, "route");
+
+        // if (+1), else if (+1), else (+1)
+        assert_eq!(metrics.cognitive, 3);
+        assert_eq!(metrics.cyclomatic, 3);
+    }
+
+    #[test]
+    fn test_go_else_if_and_nested_loops() {
+        let functions = analyze_go(
+            r#"
+package main
+
+func route(kind string, ok bool) int {
+	if kind == "a" && ok {
+		return 1
+	} else if kind == "b" {
+		return 2
+	} else {
+		return 3
+	}
+}
+
+func (p *printer) each(items []int) {
+	for _, item := range items {
+		if item > 0 {
+			println(item)
+		}
+	}
+}
+"#,
+        );
+
+        let route = metrics_for(&functions, "route");
+        // if (+1), && (+1), else if (+1), else (+1)
+        assert_eq!(route.cognitive, 4);
+        assert_eq!(route.cyclomatic, 4);
+        assert_eq!(route.nesting_depth, 1);
+
+        let each = metrics_for(&functions, "each");
+        // for (+1), if nested once (+2)
+        assert_eq!(each.cognitive, 3);
+        assert_eq!(each.cyclomatic, 3);
+        assert_eq!(each.nesting_depth, 2);
+    }
+
+    #[test]
+    fn test_rust_else_if_and_match() {
+        let functions = analyze_rust(
+            r#"
+fn route(kind: &str, ok: bool) -> u32 {
+    if kind == "a" && ok {
+        1
+    } else if kind == "b" {
+        2
+    } else {
+        3
+    }
+}
+
+fn each(items: &[Option<u32>]) {
+    for item in items {
+        match item {
+            Some(value) => println!("{}", value),
+            None => {}
+        }
+    }
+}
+"#,
+        );
+
+        let route = metrics_for(&functions, "route");
+        // if (+1), && (+1), else if (+1), else (+1)
+        assert_eq!(route.cognitive, 4);
+        assert_eq!(route.cyclomatic, 4);
+        assert_eq!(route.nesting_depth, 1);
+
+        let each = metrics_for(&functions, "each");
+        // for (+1), match nested once (+2); each arm is a decision
+        assert_eq!(each.cognitive, 3);
+        assert_eq!(each.cyclomatic, 4);
+        assert_eq!(each.nesting_depth, 2);
+    }
+
+    #[test]
+    fn test_nested_functions_are_measured_separately() {
+        let functions = analyze_python(
+            r#"
+class Service:
+    def run(self, items):
+        def check(item):
+            if item:
+                return True
+            return False
+        return [i for i in items if check(i)]
+"#,
+        );
+
+        assert_eq!(functions.len(), 2);
+        assert!(matches!(
+            functions[0].symbol.kind,
+            SymbolKind::Method { .. }
+        ));
+        assert_eq!(functions[1].symbol.kind, SymbolKind::Function);
+        assert_eq!(metrics_for(&functions, "check").cognitive, 1);
+        assert_eq!(metrics_for(&functions, "run").cognitive, 0);
+    }
+
+    #[test]
+    fn test_one_line_function() {
+        let functions = analyze_python("def one(): return 1\n");
+
+        let metrics = metrics_for(&functions, "one");
+        assert_eq!(metrics.lines_of_code, 1);
+        assert_eq!(metrics.nesting_depth, 0);
+    }
+
+    #[test]
+    fn test_three_nested_blocks() {
+        let functions = analyze_python(
+            r#"
+def deep(rows):
+    for row in rows:
+        if row:
+            while row.pending():
+                row.step()
+        else:
+            pass
+    return rows
+"#,
+        );
+
+        let metrics = metrics_for(&functions, "deep");
+        assert_eq!(metrics.nesting_depth, 3);
+        assert_eq!(metrics.lines_of_code, 8);
+    }
+
+    #[test]
+    fn test_typescript_arrow_function_span_and_depth() {
+        let functions = analyze_typescript(
+            r#"
+const load = (items: string[]) => {
+    try {
+        for (const item of items) {
+            if (item) {
+                return item;
+            } else if (item === "") {
+                return null;
+            }
+        }
+    } catch (e) {
+        return undefined;
+    }
+};
+"#,
+        );
+
+        let metrics = metrics_for(&functions, "load");
+        assert_eq!(metrics.nesting_depth, 3);
+        assert_eq!(metrics.lines_of_code, 13);
+    }
+
+    #[test]
+    fn test_tsx_ternary_in_jsx_is_counted() {
+        let functions = analyze_tsx(
+            r#"
+function Badge({ user }: { user?: User }) {
+    if (!user) {
+        return null;
+    }
+    return <span>{user.admin ? <b>Admin</b> : <i>Member</i>}</span>;
+}
+"#,
+        );
+
+        assert_eq!(metrics_for(&functions, "Badge").cyclomatic, 3);
+    }
+
+    #[test]
+    fn test_javascript_with_jsx_is_measured() {
+        let functions = analyze_javascript(
+            r#"
+function Badge({ user }) {
+    if (!user) {
+        return null;
+    }
+    return <span>{user.admin ? <b>Admin</b> : <i>Member</i>}</span>;
+}
+"#,
+        );
+
+        assert_eq!(metrics_for(&functions, "Badge").cyclomatic, 3);
+    }
+
+    #[test]
+    fn test_complexity_finding_reports_through_json_reporter() {
+        use neural_shared::report::{AnalysisStats, JsonReporter, Reporter};
+
+        let functions = analyze_python(
+            r#"
+def simple():
+    return 1
+
+def branchy(a, b, c):
+    if a:
+        return 1
+    if b:
+        return 2
+    if c:
+        return 3
+    return 4
+"#,
+        );
+        let findings: Vec<ComplexityFinding> = functions
+            .into_iter()
+            .filter_map(|f| ComplexityFinding::new(f, 2))
+            .collect();
+
+        let output = JsonReporter
+            .report(&findings, &AnalysisStats::default())
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+
+        assert_eq!(json["summary"]["total_findings"], 1);
+        let finding = &json["findings"][0];
+        assert_eq!(finding["name"], "branchy");
+        assert_eq!(finding["line"], 5);
+        assert_eq!(finding["kind"], "Function");
+        assert_eq!(
+            finding["reason"],
+            "Cyclomatic complexity 4 exceeds 2 (cognitive complexity 3)"
+        );
+        assert_eq!(finding["confidence"], "Medium");
+    }
+
+    #[test]
+    fn test_complexity_finding_confidence_bands() {
+        let function = |cyclomatic| FunctionComplexity {
+            symbol: Symbol::new(
+                "f".to_string(),
+                SymbolKind::Function,
+                Location {
+                    file: "f.py".to_string(),
+                    line: 1,
+                    column: 4,
+                    end_line: 2,
+                    end_column: 0,
+                },
+            ),
+            metrics: ComplexityMetrics {
+                cyclomatic,
+                ..Default::default()
+            },
+        };
+        let band = |cyclomatic| {
+            use neural_shared::report::Finding;
+            ComplexityFinding::new(function(cyclomatic), 10).map(|f| f.confidence())
+        };
+
+        assert_eq!(band(10), None);
+        assert_eq!(band(11).as_deref(), Some("Low"));
+        assert_eq!(band(15).as_deref(), Some("Low"));
+        assert_eq!(band(16).as_deref(), Some("Medium"));
+        assert_eq!(band(21).as_deref(), Some("High"));
+
+        let huge = |cyclomatic, threshold| {
+            use neural_shared::report::Finding;
+            ComplexityFinding::new(function(cyclomatic), threshold).map(|f| f.confidence())
+        };
+        assert_eq!(huge(u32::MAX, 3_000_000_000).as_deref(), Some("Low"));
+        assert_eq!(huge(u32::MAX, 2_500_000_000).as_deref(), Some("Medium"));
+        assert_eq!(huge(u32::MAX, 2_000_000_000).as_deref(), Some("High"));
+    }
+
+    #[test]
+    fn test_halstead_volume() {
+        let functions = analyze_python("def add(a, b):\n    return a + b\n");
+
+        // Operators: def ( , ) : return + (7 distinct, 7 total)
+        // Operands: add a b a b (3 distinct, 5 total)
+        // Volume: 12 * log2(10)
+        assert_eq!(metrics_for(&functions, "add").halstead_volume, 39.86);
+    }
+
+    #[test]
+    fn test_maintainability_decreases_with_complexity_and_length() {
+        let functions = analyze_python(
+            r#"
+def simple(a):
+    return a
+
+def branchy(a, b, c):
+    if a and b:
+        return 1
+    elif c or a:
+        return 2
+    return 3
+
+def long_branchy(a, b, c):
+    total = 0
+    if a and b:
+        total += 1
+    elif c or a:
+        total += 2
+    for item in a:
+        if item > b:
+            total += item * c
+        else:
+            total -= item
+    while total > 100:
+        total = total // 2
+    return total
+"#,
+        );
+
+        let simple = metrics_for(&functions, "simple");
+        let branchy = metrics_for(&functions, "branchy");
+        let long_branchy = metrics_for(&functions, "long_branchy");
+
+        assert!(simple.maintainability_index <= 100.0);
+        assert!(simple.maintainability_index > branchy.maintainability_index);
+        assert!(branchy.maintainability_index > long_branchy.maintainability_index);
+        assert!(long_branchy.maintainability_index > 0.0);
+    }
+
+    #[test]
+    fn test_maintainability_index_formula() {
+        // Same volume and length: more decision points lower the index
+        assert!(
+            metrics::maintainability_index(100.0, 1, 10)
+                > metrics::maintainability_index(100.0, 20, 10)
+        );
+        // Same complexity: more code lowers the index
+        assert!(
+            metrics::maintainability_index(100.0, 5, 10)
+                > metrics::maintainability_index(1000.0, 5, 100)
+        );
+        // A huge function bottoms out at 0
+        assert_eq!(metrics::maintainability_index(1e12, 500, 100_000), 0.0);
+    }
+
+    #[test]
+    fn test_low_maintainability_is_reported() {
+        let functions = analyze_python(
+            r#"
+def simple(a):
+    return a
+
+def long_function(items, limit):
+    total = 0
+    for item in items:
+        if item > limit:
+            total += item * 2
+        else:
+            total -= item
+    while total > 100:
+        total = total // 2
+    return total
+"#,
+        );
+        let min = metrics_for(&functions, "long_function").maintainability_index + 1.0;
+
+        let findings: Vec<ComplexityFinding> = functions
+            .into_iter()
+            .filter_map(|f| ComplexityFinding::check(f, 10, Some(min)))
+            .collect();
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].symbol.name, "long_function");
+        assert!(findings[0].reason.starts_with("Maintainability index "));
+        assert!(findings[0].reason.ends_with(&format!("is below {}", min)));
+        use neural_shared::report::Finding;
+        assert_eq!(findings[0].confidence(), "Low");
+    }
 }
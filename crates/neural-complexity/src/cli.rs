@@ -0,0 +1,59 @@
+//! CLI argument parsing
+
+use clap::Parser;
+use std::path::PathBuf;
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "neural-complexity",
+    version,
+    about = "Code complexity analyzer",
+    long_about = "Neural Complexity measures cyclomatic and cognitive complexity of functions in your Python and TypeScript projects."
+)]
+pub struct Cli {
+    /// Path to analyze (defaults to current directory)
+    #[arg(default_value = ".")]
+    pub path: PathBuf,
+
+    /// Output format
+    #[arg(short, long, value_name = "FORMAT", default_value = "terminal")]
+    pub format: OutputFormat,
+
+    /// Only report functions whose cyclomatic complexity exceeds this value
+    #[arg(short, long, value_name = "N", default_value_t = 10)]
+    pub threshold: u32,
+
+    /// Also report functions whose maintainability index (0-100) is below this value
+    #[arg(long, value_name = "MI", value_parser = maintainability_index)]
+    pub min_maintainability: Option<f64>,
+
+    /// Enable verbose logging
+    #[arg(short, long)]
+    pub verbose: bool,
+}
+
+#[derive(Debug, Clone, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// Pretty terminal output
+    Terminal,
+    /// JSON format (machine-readable, LLM-friendly)
+    Json,
+    /// Markdown report
+    Markdown,
+}
+
+impl Cli {
+    pub fn parse_args() -> Self {
+        Self::parse()
+    }
+}
+
+/// Parse a maintainability index, which must be a number from 0 to 100
+fn maintainability_index(value: &str) -> Result<f64, String> {
+    let index: f64 = value.parse().map_err(|e| format!("{}", e))?;
+    if (0.0..=100.0).contains(&index) {
+        Ok(index)
+    } else {
+        Err(format!("{} is not between 0 and 100", value))
+    }
+}
@@ -5,12 +5,15 @@
 //! Part of the Neural Garage toolkit.
 
 pub mod analyzer;
+pub mod cli;
+pub mod summary;
 
 // Re-export shared types
 pub use neural_shared::{Language, ParsedFile, Parser, Scanner, Symbol, SymbolKind};
 
 // Complexity-specific exports
-pub use analyzer::{ComplexityAnalyzer, ComplexityMetrics};
+pub use analyzer::{ComplexityAnalyzer, ComplexityFinding, ComplexityMetrics, FunctionComplexity};
+pub use summary::ComplexitySummary;
 
 /// Result type
 pub type Result<T> = anyhow::Result<T>;
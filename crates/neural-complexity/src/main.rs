@@ -1,9 +1,112 @@
-use neural_complexity::VERSION;
+use neural_complexity::cli::{Cli, OutputFormat};
+use neural_complexity::{
+    ComplexityAnalyzer, ComplexityFinding, ComplexitySummary, FunctionComplexity, Language, Scanner,
+};
+use neural_shared::report::{
+    AnalysisStats, JsonReporter, MarkdownReporter, Reporter, TerminalReporter,
+};
+use std::fs;
+use std::process;
+
+/// Exit status when a function is reported
+const EXIT_FINDINGS: i32 = 1;
+
+/// Exit status when the analysis could not run. Distinct from findings (1), and
+/// the same status clap uses for invalid arguments.
+const EXIT_ERROR: i32 = 2;
 
 fn main() {
-    println!("neural-complexity v{}", VERSION);
-    println!("Code complexity analyzer - coming soon!");
-    println!();
-    println!("Part of Neural Garage 🧠🔧");
-    println!("https://github.com/neural-garage/tools");
+    match run() {
+        Ok(true) => process::exit(EXIT_FINDINGS),
+        Ok(false) => {}
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            process::exit(EXIT_ERROR);
+        }
+    }
+}
+
+/// Run the analysis, returning whether any function was reported
+fn run() -> neural_complexity::Result<bool> {
+    let cli = Cli::parse_args();
+
+    if cli.verbose {
+        println!("neural-complexity v{}", neural_complexity::VERSION);
+        println!("Analyzing: {:?}", cli.path);
+    }
+
+    let files = Scanner::new(&cli.path).scan_parallel()?;
+
+    if cli.verbose {
+        println!("📁 Found {} files", files.len());
+    }
+
+    let analyzer = ComplexityAnalyzer::new();
+    let mut measured: Vec<FunctionComplexity> = Vec::new();
+    let mut stats = AnalysisStats {
+        total_files_scanned: files.len(),
+        ..Default::default()
+    };
+
+    for file_path in &files {
+        if cli.verbose {
+            println!("  Analyzing: {}", file_path.display());
+        }
+
+        let functions = match fs::read_to_string(file_path)
+            .map_err(anyhow::Error::from)
+            .and_then(|source| analyzer.analyze(&source, file_path))
+        {
+            Ok(functions) => functions,
+            Err(e) => {
+                eprintln!("⚠️  Skipping {}: {}", file_path.display(), e);
+                continue;
+            }
+        };
+
+        if let Ok(language) = Language::from_path(file_path) {
+            stats.record_file(language, functions.len());
+        }
+
+        measured.extend(functions);
+    }
+
+    let summary = ComplexitySummary::new(&measured, &cli.path);
+    let findings: Vec<ComplexityFinding> = measured
+        .into_iter()
+        .filter_map(|f| ComplexityFinding::check(f, cli.threshold, cli.min_maintainability))
+        .collect();
+
+    let output = match cli.format {
+        OutputFormat::Json => {
+            JsonReporter.report_with_section(&findings, &stats, "complexity", &summary)?
+        }
+        OutputFormat::Markdown => {
+            let mut report = MarkdownReporter.report(&findings, &stats)?;
+            report.push('\n');
+            report.push_str(&summary.to_markdown());
+            report
+        }
+        OutputFormat::Terminal => TerminalReporter::new().report(&findings, &stats)?,
+    };
+
+    println!("{}", output);
+
+    if !findings.is_empty() {
+        match cli.min_maintainability {
+            Some(min) => eprintln!(
+                "\n⚠️  Found {} functions above cyclomatic complexity {} or below maintainability index {}",
+                findings.len(),
+                cli.threshold,
+                min
+            ),
+            None => eprintln!(
+                "\n⚠️  Found {} functions above cyclomatic complexity {}",
+                findings.len(),
+                cli.threshold
+            ),
+        }
+    }
+
+    Ok(!findings.is_empty())
 }
@@ -0,0 +1,297 @@
+//! Complexity rolled up per file and per top-level directory
+//!
+//! Reviewers often want to know which parts of a codebase are hardest to work
+//! in, not just which single functions cross the threshold. Every measured
+//! function counts here, whether or not it is reported as a finding.
+
+use crate::analyzer::round;
+use crate::FunctionComplexity;
+use neural_shared::report::relative_path;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Number of files listed as hotspots
+pub const HOTSPOT_COUNT: usize = 10;
+
+/// Sums and averages over a set of functions
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ComplexityTotals {
+    pub functions: usize,
+    pub total_cyclomatic: u32,
+    pub total_cognitive: u32,
+    pub average_cyclomatic: f64,
+    pub average_cognitive: f64,
+    pub max_cyclomatic: u32,
+    /// Mean maintainability index of the functions
+    pub average_maintainability: f64,
+}
+
+/// Sums collected while rolling up; the averages are taken once at the end
+#[derive(Debug, Default)]
+struct RunningTotals {
+    functions: usize,
+    cyclomatic: u32,
+    cognitive: u32,
+    max_cyclomatic: u32,
+    maintainability: f64,
+}
+
+impl RunningTotals {
+    fn add(&mut self, function: &FunctionComplexity) {
+        self.functions += 1;
+        self.cyclomatic += function.metrics.cyclomatic;
+        self.cognitive += function.metrics.cognitive;
+        self.max_cyclomatic = self.max_cyclomatic.max(function.metrics.cyclomatic);
+        self.maintainability += function.metrics.maintainability_index;
+    }
+
+    fn finish(self) -> ComplexityTotals {
+        // Every rollup has at least one function
+        let count = self.functions as f64;
+        ComplexityTotals {
+            functions: self.functions,
+            total_cyclomatic: self.cyclomatic,
+            total_cognitive: self.cognitive,
+            average_cyclomatic: round(f64::from(self.cyclomatic) / count),
+            average_cognitive: round(f64::from(self.cognitive) / count),
+            max_cyclomatic: self.max_cyclomatic,
+            average_maintainability: round(self.maintainability / count),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileSummary {
+    /// Path relative to the analyzed root
+    pub file: String,
+    #[serde(flatten)]
+    pub totals: ComplexityTotals,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DirectorySummary {
+    /// Top-level directory under the analyzed root ("." for files in the root)
+    pub directory: String,
+    pub files: usize,
+    #[serde(flatten)]
+    pub totals: ComplexityTotals,
+}
+
+/// Per-file and per-directory rollups, sorted by path, and the worst files
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ComplexitySummary {
+    pub files: Vec<FileSummary>,
+    pub directories: Vec<DirectorySummary>,
+    /// Up to [`HOTSPOT_COUNT`] files with the highest total cyclomatic
+    /// complexity (ties broken by cognitive complexity)
+    pub hotspots: Vec<FileSummary>,
+}
+
+impl ComplexitySummary {
+    /// Roll up `functions`, with paths taken relative to `root`
+    pub fn new(functions: &[FunctionComplexity], root: &Path) -> Self {
+        let mut files: BTreeMap<String, RunningTotals> = BTreeMap::new();
+        let mut directories: BTreeMap<String, RunningTotals> = BTreeMap::new();
+        for function in functions {
+            let file = relative_path(&function.symbol.location.file, root);
+            directories
+                .entry(top_level_directory(&file))
+                .or_default()
+                .add(function);
+            files.entry(file).or_default().add(function);
+        }
+
+        let mut files_per_directory: BTreeMap<String, usize> = BTreeMap::new();
+        for file in files.keys() {
+            *files_per_directory
+                .entry(top_level_directory(file))
+                .or_default() += 1;
+        }
+
+        let directories = directories
+            .into_iter()
+            .map(|(directory, totals)| DirectorySummary {
+                files: files_per_directory[&directory],
+                directory,
+                totals: totals.finish(),
+            })
+            .collect();
+        let files: Vec<FileSummary> = files
+            .into_iter()
+            .map(|(file, totals)| FileSummary {
+                file,
+                totals: totals.finish(),
+            })
+            .collect();
+
+        let mut hotspots = files.clone();
+        hotspots.sort_by(|a, b| {
+            b.totals
+                .total_cyclomatic
+                .cmp(&a.totals.total_cyclomatic)
+                .then_with(|| b.totals.total_cognitive.cmp(&a.totals.total_cognitive))
+                .then_with(|| a.file.cmp(&b.file))
+        });
+        hotspots.truncate(HOTSPOT_COUNT);
+
+        Self {
+            files,
+            directories,
+            hotspots,
+        }
+    }
+
+    /// Markdown section with the hotspots and per-directory table
+    pub fn to_markdown(&self) -> String {
+        let mut output = String::new();
+
+        output.push_str("## Complexity Hotspots\n\n");
+        output.push_str("| File | Functions | Cyclomatic | Avg | Max | Cognitive | MI |\n");
+        output.push_str("|------|-----------|------------|-----|-----|-----------|----|\n");
+        for hotspot in &self.hotspots {
+            let t = &hotspot.totals;
+            output.push_str(&format!(
+                "| `{}` | {} | {} | {:.2} | {} | {} | {:.2} |\n",
+                hotspot.file,
+                t.functions,
+                t.total_cyclomatic,
+                t.average_cyclomatic,
+                t.max_cyclomatic,
+                t.total_cognitive,
+                t.average_maintainability
+            ));
+        }
+        output.push('\n');
+
+        output.push_str("## Complexity by Directory\n\n");
+        output.push_str("| Directory | Files | Functions | Cyclomatic | Avg | Cognitive | MI |\n");
+        output.push_str("|-----------|-------|-----------|------------|-----|-----------|----|\n");
+        for directory in &self.directories {
+            let t = &directory.totals;
+            output.push_str(&format!(
+                "| `{}` | {} | {} | {} | {:.2} | {} | {:.2} |\n",
+                directory.directory,
+                directory.files,
+                t.functions,
+                t.total_cyclomatic,
+                t.average_cyclomatic,
+                t.total_cognitive,
+                t.average_maintainability
+            ));
+        }
+
+        output
+    }
+}
+
+/// First directory of a relative path, or "." for a file in the root
+fn top_level_directory(file: &str) -> String {
+    match file.split_once('/') {
+        Some((directory, _)) => directory.to_string(),
+        None => ".".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ComplexityAnalyzer;
+
+    const ROUTES: &str = r#"
+def route(kind):
+    if kind == "a":
+        return 1
+    elif kind == "b":
+        return 2
+    return 3
+
+def handle(items):
+    for item in items:
+        if item:
+            print(item)
+"#;
+
+    const MAIN: &str = r#"
+def main():
+    return 0
+"#;
+
+    fn analyze(files: &[(&str, &str)]) -> Vec<FunctionComplexity> {
+        let analyzer = ComplexityAnalyzer::new();
+        files
+            .iter()
+            .flat_map(|(path, source)| analyzer.analyze(source, Path::new(path)).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_file_total_is_sum_of_its_functions() {
+        let functions = analyze(&[
+            ("project/src/api/routes.py", ROUTES),
+            ("project/main.py", MAIN),
+        ]);
+        let summary = ComplexitySummary::new(&functions, Path::new("project"));
+
+        let routes = summary
+            .files
+            .iter()
+            .find(|f| f.file == "src/api/routes.py")
+            .unwrap();
+        let expected: u32 = functions
+            .iter()
+            .filter(|f| f.symbol.location.file.ends_with("routes.py"))
+            .map(|f| f.metrics.cyclomatic)
+            .sum();
+        assert_eq!(routes.totals.total_cyclomatic, expected);
+        assert_eq!(routes.totals.functions, 2);
+        assert_eq!(routes.totals.total_cyclomatic, 6);
+        assert_eq!(routes.totals.average_cyclomatic, 3.0);
+        assert_eq!(routes.totals.max_cyclomatic, 3);
+
+        let mean = functions
+            .iter()
+            .filter(|f| f.symbol.location.file.ends_with("routes.py"))
+            .map(|f| f.metrics.maintainability_index)
+            .sum::<f64>()
+            / 2.0;
+        assert!((routes.totals.average_maintainability - mean).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_directories_and_hotspots() {
+        let functions = analyze(&[
+            ("./src/api/routes.py", ROUTES),
+            ("./src/util.py", MAIN),
+            ("./main.py", MAIN),
+        ]);
+        let summary = ComplexitySummary::new(&functions, Path::new("."));
+
+        let directories: Vec<_> = summary
+            .directories
+            .iter()
+            .map(|d| (d.directory.as_str(), d.files, d.totals.total_cyclomatic))
+            .collect();
+        assert_eq!(directories, vec![(".", 1, 1), ("src", 2, 7)]);
+
+        let hotspots: Vec<_> = summary.hotspots.iter().map(|f| f.file.as_str()).collect();
+        assert_eq!(
+            hotspots,
+            vec!["src/api/routes.py", "main.py", "src/util.py"]
+        );
+
+        let markdown = summary.to_markdown();
+        assert!(markdown.contains("| `src/api/routes.py` | 2 | 6 | 3.00 | 3 |"));
+        assert!(markdown.contains("| `src` | 2 | 3 | 7 |"));
+    }
+
+    #[test]
+    fn test_single_file_root_uses_the_file_name() {
+        let functions = analyze(&[("./src/api/routes.py", ROUTES)]);
+        let summary = ComplexitySummary::new(&functions, Path::new("./src/api/routes.py"));
+
+        assert_eq!(summary.files[0].file, "routes.py");
+        assert_eq!(summary.directories[0].directory, ".");
+        assert_eq!(summary.hotspots[0].file, "routes.py");
+    }
+}
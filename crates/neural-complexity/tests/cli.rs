@@ -0,0 +1,120 @@
+//! End-to-end tests for the `neural-complexity` binary
+
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+
+const SOURCE: &str = r#"
+def simple():
+    return 1
+
+def branchy(a, b, c):
+    if a:
+        return 1
+    if b:
+        return 2
+    if c:
+        return 3
+    return 4
+"#;
+
+fn neural_complexity(args: &[&str], dir: &Path) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_neural-complexity"))
+        .args(args)
+        .arg(dir)
+        .output()
+        .expect("failed to run neural-complexity")
+}
+
+#[test]
+fn test_reports_functions_above_threshold() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("app.py"), SOURCE).unwrap();
+
+    let output = neural_complexity(&["--format", "json", "--threshold", "2"], dir.path());
+
+    assert_eq!(output.status.code(), Some(1));
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let findings = report["findings"].as_array().unwrap();
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0]["name"], "branchy");
+}
+
+#[test]
+fn test_exits_zero_below_threshold() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("app.py"), SOURCE).unwrap();
+
+    let output = neural_complexity(&["--format", "json", "--threshold", "4"], dir.path());
+
+    assert!(output.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["summary"]["total_findings"], 0);
+}
+
+#[test]
+fn test_json_report_includes_complexity_rollup() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir(dir.path().join("src")).unwrap();
+    fs::write(dir.path().join("src/app.py"), SOURCE).unwrap();
+
+    let output = neural_complexity(&["--format", "json", "--threshold", "10"], dir.path());
+
+    assert!(output.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let complexity = &report["complexity"];
+    // simple (1) + branchy (4), even though neither is a finding
+    assert_eq!(complexity["files"][0]["file"], "src/app.py");
+    assert_eq!(complexity["files"][0]["total_cyclomatic"], 5);
+    assert_eq!(complexity["directories"][0]["directory"], "src");
+    assert_eq!(complexity["hotspots"][0]["file"], "src/app.py");
+}
+
+#[test]
+fn test_min_maintainability_reports_hard_to_maintain_functions() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("app.py"), SOURCE).unwrap();
+
+    // Nothing is above the cyclomatic threshold, but every function is below MI 100
+    let output = neural_complexity(
+        &["--format", "json", "--min-maintainability", "100"],
+        dir.path(),
+    );
+
+    assert_eq!(output.status.code(), Some(1));
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let findings = report["findings"].as_array().unwrap();
+    assert_eq!(findings.len(), 2);
+    assert!(findings[0]["reason"]
+        .as_str()
+        .unwrap()
+        .starts_with("Maintainability index"));
+    let file = &report["complexity"]["files"][0];
+    assert!(file["average_maintainability"].as_f64().unwrap() > 0.0);
+}
+
+#[test]
+fn test_error_exit_code_differs_from_findings() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let output = neural_complexity(&[], &dir.path().join("missing"));
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Error:"));
+}
+
+#[test]
+fn test_min_maintainability_must_be_between_0_and_100() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("app.py"), SOURCE).unwrap();
+
+    for value in ["NaN", "-1", "100.5", "abc"] {
+        let arg = format!("--min-maintainability={}", value);
+        let output = neural_complexity(&[&arg], dir.path());
+        assert_eq!(output.status.code(), Some(2), "accepted {}", value);
+        assert!(String::from_utf8_lossy(&output.stderr).contains("--min-maintainability"));
+    }
+
+    let output = neural_complexity(&["--min-maintainability", "100"], dir.path());
+    assert_eq!(output.status.code(), Some(1));
+}
@@ -1,15 +1,29 @@
 //! CLI commands for GitHub Copilot integration
 
 use anyhow::Result;
+use chrono::{DateTime, Local, TimeZone};
 use clap::{Parser, Subcommand};
+use std::fmt;
 use std::time::{SystemTime, UNIX_EPOCH};
 
-use crate::copilot::{CopilotProvider, DeviceFlowAuth, StoredAuth, TokenStorage};
+use crate::copilot::{
+    default_credential_store, ChatRepl, CopilotProvider, DeviceFlowAuth, ModelInfo,
+    ProfileRegistry, StoredAuth, TokenStorage, UsageLedger,
+};
 
 #[derive(Parser, Debug)]
 #[command(name = "copilot")]
 #[command(about = "GitHub Copilot integration commands")]
 pub struct CopilotCli {
+    /// Account profile to use (defaults to the one set with `profiles --set-default`)
+    #[arg(long, global = true, value_name = "NAME")]
+    pub profile: Option<String>,
+
+    /// Send GitHub and Copilot requests through this HTTP(S) proxy (HTTPS_PROXY,
+    /// HTTP_PROXY and NO_PROXY are honored without it)
+    #[arg(long, global = true, value_name = "URL")]
+    pub proxy: Option<String>,
+
     #[command(subcommand)]
     pub command: CopilotCommand,
 }
@@ -17,7 +31,11 @@ pub struct CopilotCli {
 #[derive(Subcommand, Debug)]
 pub enum CopilotCommand {
     /// Authenticate with GitHub Copilot
-    Login,
+    Login {
+        /// Don't open the verification page in a browser, only print its URL
+        #[arg(long)]
+        no_browser: bool,
+    },
 
     /// Show authentication status
     Status,
@@ -31,6 +49,31 @@ pub enum CopilotCommand {
         /// Test message to send
         #[arg(short, long, default_value = "Say hello!")]
         message: String,
+
+        /// Send the request even if the model is not in the model list (for
+        /// newly released models)
+        #[arg(long)]
+        skip_model_check: bool,
+    },
+
+    /// Chat with a model interactively (`/model <name>`, `/clear`, `/quit`)
+    Chat {
+        /// Model to start the conversation with
+        #[arg(short, long, default_value = "gpt-4o")]
+        model: String,
+    },
+
+    /// List available models with their tier and premium-request multiplier
+    Models,
+
+    /// Show premium-request usage for the current billing period
+    Usage,
+
+    /// List account profiles
+    Profiles {
+        /// Make this profile the default
+        #[arg(long, value_name = "NAME")]
+        set_default: Option<String>,
     },
 
     /// Logout and clear stored credentials
@@ -39,18 +82,49 @@ pub enum CopilotCommand {
 
 impl CopilotCli {
     pub async fn execute(self) -> Result<()> {
+        let registry = ProfileRegistry::load(&ProfileRegistry::default_path()?)?;
+        let profile = registry.resolve(self.profile.as_deref());
+        let profile = profile.as_deref();
+        let proxy = self.proxy.as_deref();
+
         match self.command {
-            CopilotCommand::Login => Self::login().await,
-            CopilotCommand::Status => Self::status().await,
-            CopilotCommand::Test { model, message } => Self::test(&model, &message).await,
-            CopilotCommand::Logout => Self::logout().await,
+            CopilotCommand::Login { no_browser } => Self::login(profile, proxy, no_browser).await,
+            CopilotCommand::Status => Self::status(profile).await,
+            CopilotCommand::Test {
+                model,
+                message,
+                skip_model_check,
+            } => Self::test(profile, proxy, &model, &message, skip_model_check).await,
+            CopilotCommand::Chat { model } => Self::chat(profile, proxy, &model).await,
+            CopilotCommand::Models => Self::models(profile, proxy).await,
+            CopilotCommand::Usage => Self::usage(profile).await,
+            CopilotCommand::Profiles { set_default } => {
+                Self::profiles(registry, set_default.as_deref()).await
+            }
+            CopilotCommand::Logout => Self::logout(profile).await,
+        }
+    }
+
+    /// Load the provider for `profile` from stored credentials, sending requests
+    /// through `proxy` if given
+    fn provider(profile: Option<&str>, proxy: Option<&str>) -> Result<CopilotProvider> {
+        let provider = CopilotProvider::from_storage(profile)?;
+        match proxy {
+            Some(proxy) => provider.with_proxy(proxy),
+            None => Ok(provider),
         }
     }
 
-    async fn login() -> Result<()> {
+    async fn login(profile: Option<&str>, proxy: Option<&str>, no_browser: bool) -> Result<()> {
         println!("🚀 Starting GitHub Copilot authentication...\n");
 
-        let auth = DeviceFlowAuth::new();
+        let mut auth = match proxy {
+            Some(proxy) => DeviceFlowAuth::new().with_proxy(proxy)?,
+            None => DeviceFlowAuth::new(),
+        };
+        if no_browser {
+            auth = auth.without_browser();
+        }
         let (github_token, copilot_token) = auth.complete_flow().await?;
 
         let now = SystemTime::now()
@@ -66,19 +140,29 @@ impl CopilotCli {
             updated_at: now,
         };
 
-        let storage = TokenStorage::new()?;
+        let storage = default_credential_store(profile)?;
         storage.save(&stored)?;
 
+        if let Some(name) = profile {
+            let registry_path = ProfileRegistry::default_path()?;
+            let mut registry = ProfileRegistry::load(&registry_path)?;
+            registry.add(name)?;
+            registry.save(&registry_path)?;
+        }
+
         println!("✅ Authentication successful!");
-        println!("📁 Credentials saved to: {}", storage.path().display());
-        println!("⏰ Session expires at: {}", copilot_token.expires_at);
+        println!("📁 Credentials saved to: {}", storage.location());
+        println!(
+            "⏰ Session expires at: {}",
+            format_timestamp(copilot_token.expires_at, &Local)
+        );
         println!("\nYou can now use GitHub Copilot models in Neural Conductor! 🎉\n");
 
         Ok(())
     }
 
-    async fn status() -> Result<()> {
-        let storage = TokenStorage::new()?;
+    async fn status(profile: Option<&str>) -> Result<()> {
+        let storage = default_credential_store(profile)?;
 
         if !storage.exists() {
             println!("❌ Not authenticated");
@@ -109,11 +193,15 @@ impl CopilotCli {
         println!("GitHub Copilot Status");
         println!("═══════════════════════════════════════");
         println!("✅ Authenticated");
-        println!("📁 Config: {}", storage.path().display());
+        if let Some(name) = profile {
+            println!("👤 Profile: {}", name);
+        }
+        println!("📁 Config: {}", storage.location());
         println!("🔑 GitHub Token: {}...", &stored.github_token[..12]);
         println!(
             "⏰ Session expires: {} ({})",
-            stored.expires_at, time_until_expiry
+            format_timestamp(stored.expires_at, &Local),
+            time_until_expiry
         );
 
         if is_expired {
@@ -126,12 +214,23 @@ impl CopilotCli {
         Ok(())
     }
 
-    async fn test(model: &str, message: &str) -> Result<()> {
+    async fn test(
+        profile: Option<&str>,
+        proxy: Option<&str>,
+        model: &str,
+        message: &str,
+        skip_model_check: bool,
+    ) -> Result<()> {
         println!("🧪 Testing Copilot API connection...\n");
         println!("Model: {}", model);
         println!("Message: {}\n", message);
 
-        let mut provider = CopilotProvider::from_storage()?;
+        let mut provider = Self::provider(profile, proxy)?;
+
+        if !skip_model_check {
+            let models = provider.known_models().await;
+            ModelInfo::check_known(model, &models)?;
+        }
 
         println!("📡 Sending request...");
 
@@ -140,11 +239,15 @@ impl CopilotCli {
             messages: vec![crate::copilot::provider::ChatMessage {
                 role: "user".to_string(),
                 content: message.to_string(),
+                tool_calls: None,
+                tool_call_id: None,
             }],
             temperature: Some(0.7),
             stream: Some(false),
             n: Some(1),
             prompt_cache_key: None,
+            tools: None,
+            tool_choice: None,
         };
 
         let response = provider.chat_completion(request).await?;
@@ -173,8 +276,128 @@ impl CopilotCli {
         Ok(())
     }
 
-    async fn logout() -> Result<()> {
-        let storage = TokenStorage::new()?;
+    async fn chat(profile: Option<&str>, proxy: Option<&str>, model: &str) -> Result<()> {
+        let mut provider = Self::provider(profile, proxy)?;
+
+        println!(
+            "💬 Chatting with {} (/model <name>, /clear, /quit)\n",
+            model
+        );
+
+        ChatRepl::new(model)
+            .run(&mut provider, std::io::stdin().lock(), std::io::stdout())
+            .await
+    }
+
+    async fn models(profile: Option<&str>, proxy: Option<&str>) -> Result<()> {
+        let live = match Self::provider(profile, proxy) {
+            Ok(mut provider) => provider.list_models().await,
+            Err(e) => Err(e),
+        };
+        let models = match live {
+            Ok(models) => models,
+            Err(e) => {
+                println!("⚠️  Could not fetch models from the Copilot API: {}", e);
+                println!("Showing the built-in model list instead.\n");
+                ModelInfo::available_models()
+            }
+        };
+
+        println!(
+            "{:<24} {:<10} {:>10} {:>12}",
+            "Model", "Tier", "Multiplier", "Context"
+        );
+        println!("═══════════════════════════════════════════════════════════");
+        for model in models {
+            let context = if model.context_window > 0 {
+                model.context_window.to_string()
+            } else {
+                "-".to_string()
+            };
+            println!(
+                "{:<24} {:<10} {:>9}x {:>12}",
+                model.name,
+                format!("{:?}", model.tier),
+                model.multiplier,
+                context
+            );
+        }
+        println!();
+
+        Ok(())
+    }
+
+    async fn usage(profile: Option<&str>) -> Result<()> {
+        let storage = TokenStorage::for_profile(profile)?;
+        let mut ledger = UsageLedger::load(&storage.usage_path())?;
+        ledger.roll_over(chrono::Utc::now());
+
+        println!("Copilot Usage ({})", ledger.period);
+        println!("═══════════════════════════════════════");
+
+        if ledger.models.is_empty() {
+            println!("No requests recorded this billing period.\n");
+            return Ok(());
+        }
+
+        println!("{:<24} {:>8} {:>10}", "Model", "Requests", "Premium");
+        for (model, usage) in &ledger.models {
+            println!(
+                "{:<24} {:>8} {:>10.2}",
+                model, usage.requests, usage.premium_requests
+            );
+        }
+        println!("───────────────────────────────────────");
+        println!(
+            "{:<24} {:>8} {:>10.2}\n",
+            "Total",
+            ledger.total_requests(),
+            ledger.total_premium_requests()
+        );
+
+        Ok(())
+    }
+
+    async fn profiles(mut registry: ProfileRegistry, set_default: Option<&str>) -> Result<()> {
+        if let Some(name) = set_default {
+            registry.set_default(name)?;
+            registry.save(&ProfileRegistry::default_path()?)?;
+            println!("✅ Default profile set to '{}'\n", name);
+            return Ok(());
+        }
+
+        if registry.profiles.is_empty() {
+            println!("No named profiles. Log in with --profile <name> to create one.\n");
+            return Ok(());
+        }
+
+        println!("Copilot Profiles");
+        println!("═══════════════════════════════════════");
+        for name in &registry.profiles {
+            let marker = if registry.default.as_deref() == Some(name.as_str()) {
+                "*"
+            } else {
+                " "
+            };
+            println!("{} {}", marker, name);
+        }
+        if registry.default.is_none() {
+            println!("\nNo default profile; the unnamed profile is used without --profile.");
+        }
+        println!();
+
+        Ok(())
+    }
+
+    async fn logout(profile: Option<&str>) -> Result<()> {
+        let storage = default_credential_store(profile)?;
+
+        if let Some(name) = profile {
+            let registry_path = ProfileRegistry::default_path()?;
+            let mut registry = ProfileRegistry::load(&registry_path)?;
+            registry.remove(name);
+            registry.save(&registry_path)?;
+        }
 
         if !storage.exists() {
             println!("ℹ️  No stored credentials found.\n");
@@ -183,8 +406,43 @@ impl CopilotCli {
 
         storage.delete()?;
         println!("✅ Logged out successfully");
-        println!("📁 Removed: {}\n", storage.path().display());
+        println!("📁 Removed: {}\n", storage.location());
 
         Ok(())
     }
 }
+
+/// Format a Unix timestamp as a date and time in `tz`, e.g.
+/// `2025-01-15 14:32:00 UTC`. Out-of-range timestamps are printed as-is.
+fn format_timestamp<Tz: TimeZone>(secs: u64, tz: &Tz) -> String
+where
+    Tz::Offset: fmt::Display,
+{
+    match i64::try_from(secs)
+        .ok()
+        .and_then(|secs| DateTime::from_timestamp(secs, 0))
+    {
+        Some(time) => time
+            .with_timezone(tz)
+            .format("%Y-%m-%d %H:%M:%S %Z")
+            .to_string(),
+        None => secs.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(
+            format_timestamp(1_736_951_520, &chrono::Utc),
+            "2025-01-15 14:32:00 UTC"
+        );
+        assert_eq!(
+            format_timestamp(u64::MAX, &chrono::Utc),
+            u64::MAX.to_string()
+        );
+    }
+}
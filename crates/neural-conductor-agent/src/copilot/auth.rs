@@ -7,6 +7,8 @@ use anyhow::{anyhow, Context, Result};
 use serde::Deserialize;
 use std::time::Duration;
 
+use super::http::client_builder;
+
 const DEVICE_CODE_URL: &str = "https://github.com/login/device/code";
 const ACCESS_TOKEN_URL: &str = "https://github.com/login/oauth/access_token";
 const COPILOT_TOKEN_URL: &str = "https://api.github.com/copilot_internal/v2/token";
@@ -49,18 +51,55 @@ pub struct CopilotTokenResponse {
     pub refresh_in: Option<u64>,
 }
 
+/// Opens the verification URL for the user during the device flow
+pub trait UrlOpener: Send + Sync {
+    fn open(&self, url: &str) -> Result<()>;
+}
+
+/// Opens URLs in the default browser
+pub struct BrowserOpener;
+
+impl UrlOpener for BrowserOpener {
+    fn open(&self, url: &str) -> Result<()> {
+        open::that(url).with_context(|| format!("Failed to open {}", url))
+    }
+}
+
 /// OAuth Device Flow handler
 pub struct DeviceFlowAuth {
     client: reqwest::Client,
+    /// Opens the verification URL; `None` leaves it to the user
+    opener: Option<Box<dyn UrlOpener>>,
 }
 
 impl DeviceFlowAuth {
     pub fn new() -> Self {
         Self {
             client: reqwest::Client::new(),
+            opener: Some(Box::new(BrowserOpener)),
         }
     }
 
+    /// Open the verification URL with a custom opener instead of the browser
+    pub fn with_opener(mut self, opener: impl UrlOpener + 'static) -> Self {
+        self.opener = Some(Box::new(opener));
+        self
+    }
+
+    /// Only print the verification URL, for headless environments
+    pub fn without_browser(mut self) -> Self {
+        self.opener = None;
+        self
+    }
+
+    /// Send device-code and token requests through an HTTP(S) proxy
+    pub fn with_proxy(mut self, proxy: &str) -> Result<Self> {
+        self.client = client_builder(Some(proxy))?
+            .build()
+            .context("Failed to create HTTP client")?;
+        Ok(self)
+    }
+
     /// Step 1: Request a device code from GitHub
     pub async fn request_device_code(&self) -> Result<DeviceCodeResponse> {
         let response = self
@@ -206,12 +245,7 @@ impl DeviceFlowAuth {
     pub async fn complete_flow(&self) -> Result<(String, CopilotTokenResponse)> {
         // Step 1: Get device code
         let device_code = self.request_device_code().await?;
-
-        println!("\n🔑 GitHub Copilot Authentication");
-        println!("═══════════════════════════════════════");
-        println!("\nPlease visit: {}", device_code.verification_uri);
-        println!("\nEnter code: {}\n", device_code.user_code);
-        println!("Waiting for authorization...\n");
+        self.show_verification(&device_code);
 
         // Step 2: Poll for OAuth token
         let github_token = self
@@ -230,6 +264,24 @@ impl DeviceFlowAuth {
 
         Ok((github_token, copilot_token))
     }
+
+    /// Print the verification URL and code, and open the URL if an opener is set.
+    /// The printed URL is the fallback when no browser can be opened.
+    fn show_verification(&self, device_code: &DeviceCodeResponse) {
+        println!("\n🔑 GitHub Copilot Authentication");
+        println!("═══════════════════════════════════════");
+        println!("\nPlease visit: {}", device_code.verification_uri);
+        println!("\nEnter code: {}\n", device_code.user_code);
+
+        if let Some(opener) = &self.opener {
+            match opener.open(&device_code.verification_uri) {
+                Ok(()) => println!("🌐 Opened the verification page in your browser"),
+                Err(e) => println!("⚠️  Could not open a browser ({}), visit the URL above", e),
+            }
+        }
+
+        println!("Waiting for authorization...\n");
+    }
 }
 
 impl Default for DeviceFlowAuth {
@@ -252,4 +304,51 @@ mod tests {
     fn test_scope_is_correct() {
         assert_eq!(SCOPE, "read:user");
     }
+
+    #[derive(Clone, Default)]
+    struct RecordingOpener {
+        opened: std::sync::Arc<std::sync::Mutex<Vec<String>>>,
+    }
+
+    impl UrlOpener for RecordingOpener {
+        fn open(&self, url: &str) -> Result<()> {
+            self.opened.lock().unwrap().push(url.to_string());
+            Ok(())
+        }
+    }
+
+    fn device_code() -> DeviceCodeResponse {
+        DeviceCodeResponse {
+            device_code: "dc-1".to_string(),
+            user_code: "ABCD-1234".to_string(),
+            verification_uri: "https://github.com/login/device".to_string(),
+            expires_in: 900,
+            interval: 5,
+        }
+    }
+
+    #[test]
+    fn test_verification_url_is_opened() {
+        let opener = RecordingOpener::default();
+        let auth = DeviceFlowAuth::new().with_opener(opener.clone());
+
+        auth.show_verification(&device_code());
+
+        assert_eq!(
+            *opener.opened.lock().unwrap(),
+            vec!["https://github.com/login/device"]
+        );
+    }
+
+    #[test]
+    fn test_no_browser_only_prints_url() {
+        let opener = RecordingOpener::default();
+        let auth = DeviceFlowAuth::new()
+            .with_opener(opener.clone())
+            .without_browser();
+
+        auth.show_verification(&device_code());
+
+        assert!(opener.opened.lock().unwrap().is_empty());
+    }
 }
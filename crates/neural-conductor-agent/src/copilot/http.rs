@@ -0,0 +1,33 @@
+//! HTTP client construction shared by authentication and API requests
+
+use anyhow::{Context, Result};
+
+/// Start building an HTTP client, sending every request through `proxy` if given.
+///
+/// Without an explicit proxy, reqwest honors `HTTPS_PROXY`, `HTTP_PROXY`,
+/// `ALL_PROXY` and `NO_PROXY`. `NO_PROXY` also applies to an explicit proxy.
+pub(crate) fn client_builder(proxy: Option<&str>) -> Result<reqwest::ClientBuilder> {
+    let builder = reqwest::Client::builder();
+    match proxy {
+        Some(url) => {
+            let proxy = reqwest::Proxy::all(url)
+                .with_context(|| format!("Invalid proxy URL: {}", url))?
+                .no_proxy(reqwest::NoProxy::from_env());
+            Ok(builder.proxy(proxy))
+        }
+        None => Ok(builder),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invalid_proxy_url_is_rejected() {
+        assert!(client_builder(Some("http://proxy.internal:3128")).is_ok());
+
+        let err = client_builder(Some("not a url")).unwrap_err();
+        assert!(err.to_string().contains("Invalid proxy URL"));
+    }
+}
@@ -4,9 +4,21 @@
 //! for LLM-powered code analysis and generation.
 
 pub mod auth;
+mod http;
+pub mod profiles;
 pub mod provider;
+pub mod repl;
 pub mod storage;
+pub mod stream;
+pub mod usage;
 
-pub use auth::DeviceFlowAuth;
-pub use provider::{CopilotProvider, ModelInfo};
-pub use storage::{StoredAuth, TokenStorage};
+pub use auth::{BrowserOpener, DeviceFlowAuth, UrlOpener};
+pub use profiles::ProfileRegistry;
+pub use provider::{Cancelled, CopilotProvider, ModelInfo, RequestTimeout, UnknownModel};
+pub use repl::ChatRepl;
+pub use storage::{
+    default_credential_store, CredentialStore, KeyringStorage, StoredAuth, TokenStorage,
+    CREDENTIAL_STORE_ENV,
+};
+pub use stream::ChatStream;
+pub use usage::{ModelUsage, UsageLedger};
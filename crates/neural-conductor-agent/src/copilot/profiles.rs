@@ -0,0 +1,129 @@
+//! Named Copilot account profiles
+//!
+//! Each profile keeps its own credentials (see [`TokenStorage::for_profile`]), so
+//! personal and enterprise accounts can be used side by side. The registry file
+//! `copilot-profiles.json` lists the named profiles and which one commands use
+//! when `--profile` is not given. Without a default, the unnamed profile is used.
+//!
+//! [`TokenStorage::for_profile`]: super::TokenStorage::for_profile
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::storage::{config_dir, validate_profile_name};
+
+/// File name of the profile registry in the config directory
+const REGISTRY_FILE: &str = "copilot-profiles.json";
+
+/// Known profiles and the default-profile pointer
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProfileRegistry {
+    /// Profile used when none is given explicitly
+    #[serde(default)]
+    pub default: Option<String>,
+
+    /// Named profiles that have been logged in
+    #[serde(default)]
+    pub profiles: BTreeSet<String>,
+}
+
+impl ProfileRegistry {
+    /// Path of the registry in the default config directory
+    pub fn default_path() -> Result<PathBuf> {
+        Ok(config_dir()?.join(REGISTRY_FILE))
+    }
+
+    /// Load the registry, starting empty if the file does not exist
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&contents).context("Failed to parse profile registry")
+    }
+
+    /// Save the registry to disk
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+        }
+
+        let json = serde_json::to_string_pretty(self).context("Failed to serialize profiles")?;
+        fs::write(path, json).with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Register a named profile
+    pub fn add(&mut self, name: &str) -> Result<()> {
+        validate_profile_name(name)?;
+        self.profiles.insert(name.to_string());
+        Ok(())
+    }
+
+    /// Forget a named profile, clearing the default if it pointed there
+    pub fn remove(&mut self, name: &str) {
+        self.profiles.remove(name);
+        if self.default.as_deref() == Some(name) {
+            self.default = None;
+        }
+    }
+
+    /// Make `name` the default profile
+    pub fn set_default(&mut self, name: &str) -> Result<()> {
+        if !self.profiles.contains(name) {
+            return Err(anyhow!(
+                "Unknown profile '{}'. Log in with --profile {} first.",
+                name,
+                name
+            ));
+        }
+        self.default = Some(name.to_string());
+        Ok(())
+    }
+
+    /// Profile to use: the explicit one if given, otherwise the default
+    pub fn resolve(&self, explicit: Option<&str>) -> Option<String> {
+        explicit
+            .map(str::to_string)
+            .or_else(|| self.default.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_pointer() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(REGISTRY_FILE);
+
+        let mut registry = ProfileRegistry::load(&path).unwrap();
+        assert_eq!(registry.resolve(None), None);
+        assert!(registry.set_default("work").is_err());
+
+        registry.add("personal").unwrap();
+        registry.add("work").unwrap();
+        registry.set_default("work").unwrap();
+        registry.save(&path).unwrap();
+
+        let mut registry = ProfileRegistry::load(&path).unwrap();
+        assert_eq!(registry.resolve(None).as_deref(), Some("work"));
+        assert_eq!(
+            registry.resolve(Some("personal")).as_deref(),
+            Some("personal")
+        );
+
+        registry.remove("work");
+        assert_eq!(registry.resolve(None), None);
+        assert_eq!(
+            registry.profiles.iter().collect::<Vec<_>>(),
+            vec!["personal"]
+        );
+    }
+}
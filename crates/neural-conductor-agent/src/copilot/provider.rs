@@ -5,9 +5,16 @@
 
 use anyhow::{anyhow, Context, Result};
 use serde::{Deserialize, Serialize};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::fmt;
+use std::future::Future;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio_util::sync::CancellationToken;
 
-use super::storage::{StoredAuth, TokenStorage};
+use super::http::client_builder;
+use super::storage::{default_credential_store, CredentialStore, StoredAuth, TokenStorage};
+use super::stream::{chat_stream_until, ChatStream};
+use super::usage::UsageLedger;
+use std::path::PathBuf;
 
 /// GitHub Copilot authentication information
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,6 +28,10 @@ pub struct CopilotAuth {
     /// Session token expiration timestamp (Unix timestamp in seconds)
     pub expires_at: u64,
 
+    /// Seconds before `expires_at` at which the session token should be refreshed
+    #[serde(default)]
+    pub refresh_in: Option<u64>,
+
     /// Optional enterprise URL
     pub enterprise_url: Option<String>,
 }
@@ -36,6 +47,24 @@ impl CopilotAuth {
         now >= self.expires_at
     }
 
+    /// Check if the session token is expired or inside its refresh window.
+    ///
+    /// With a `refresh_in` margin the token is refreshed once
+    /// `now >= expires_at - refresh_in`, before it actually expires.
+    pub fn needs_refresh(&self) -> bool {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        let refresh_at = self
+            .refresh_in
+            .map(|margin| self.expires_at.saturating_sub(margin))
+            .unwrap_or(self.expires_at);
+
+        now >= refresh_at
+    }
+
     /// Get the API base URL based on whether this is enterprise or public GitHub
     pub fn base_url(&self) -> String {
         if let Some(enterprise_url) = &self.enterprise_url {
@@ -65,43 +94,224 @@ impl CopilotAuth {
     }
 }
 
+/// Default number of attempts for requests that hit 429 or 5xx responses
+const DEFAULT_MAX_ATTEMPTS: u32 = 4;
+
+/// Default delay before the first retry; doubled on each subsequent retry
+const DEFAULT_RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+
+/// Upper bound on any single retry delay, including `Retry-After`
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// Default limit on how long a single HTTP request may take
+const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Error returned when a Copilot HTTP request does not complete within the
+/// provider's timeout
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestTimeout {
+    pub url: String,
+    pub timeout: Duration,
+}
+
+impl fmt::Display for RequestTimeout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Request to {} timed out after {:?}",
+            self.url, self.timeout
+        )
+    }
+}
+
+impl std::error::Error for RequestTimeout {}
+
+/// Error returned when a request is cancelled through its [`CancellationToken`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Cancelled {
+    /// Content streamed before the cancellation (empty for non-streamed requests)
+    pub partial: String,
+}
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Request was cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+/// Error returned when a model name is not among the available models
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownModel {
+    pub model: String,
+    /// Available models with similar names, closest first
+    pub suggestions: Vec<String>,
+}
+
+impl fmt::Display for UnknownModel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Unknown model `{}`", self.model)?;
+        if self.suggestions.is_empty() {
+            write!(f, " (run `copilot models` to list available models)")
+        } else {
+            let suggestions: Vec<String> = self
+                .suggestions
+                .iter()
+                .map(|s| format!("`{}`", s))
+                .collect();
+            write!(f, ", did you mean {}?", suggestions.join(", "))
+        }
+    }
+}
+
+impl std::error::Error for UnknownModel {}
+
 /// GitHub Copilot provider
 pub struct CopilotProvider {
     auth: CopilotAuth,
     http_client: reqwest::Client,
-    storage: TokenStorage,
+    storage: Box<dyn CredentialStore>,
+    /// Where premium-request usage is recorded
+    usage_path: PathBuf,
+    /// Overrides the API base URL derived from `auth` (useful for proxies and tests)
+    base_url: Option<String>,
+    /// Overrides the token refresh URL derived from `auth`
+    refresh_url: Option<String>,
+    /// Maximum attempts per request when the API is rate limiting or failing
+    max_attempts: u32,
+    /// Backoff delay before the first retry
+    retry_base_delay: Duration,
+    /// Limit on each HTTP request, from sending until the response headers arrive
+    timeout: Duration,
 }
 
 impl CopilotProvider {
     /// Create a new Copilot provider with existing authentication
     pub fn new(auth: CopilotAuth) -> Result<Self> {
-        let http_client = reqwest::Client::builder()
-            .user_agent("GitHubCopilotChat/0.32.4")
-            .build()
-            .context("Failed to create HTTP client")?;
+        let http_client = Self::http_client(None)?;
 
         let storage = TokenStorage::new()?;
+        let usage_path = storage.usage_path();
 
         Ok(Self {
             auth,
             http_client,
-            storage,
+            storage: Box::new(storage),
+            usage_path,
+            base_url: None,
+            refresh_url: None,
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            retry_base_delay: DEFAULT_RETRY_BASE_DELAY,
+            timeout: DEFAULT_REQUEST_TIMEOUT,
         })
     }
 
-    /// Load provider from stored credentials
-    pub fn from_storage() -> Result<Self> {
-        let storage = TokenStorage::new()?;
+    fn http_client(proxy: Option<&str>) -> Result<reqwest::Client> {
+        client_builder(proxy)?
+            .user_agent("GitHubCopilotChat/0.32.4")
+            .build()
+            .context("Failed to create HTTP client")
+    }
+
+    /// Send token refresh and API requests through an HTTP(S) proxy
+    pub fn with_proxy(mut self, proxy: &str) -> Result<Self> {
+        self.http_client = Self::http_client(Some(proxy))?;
+        Ok(self)
+    }
+
+    /// Use a custom credential store instead of the default file
+    pub fn with_storage(mut self, storage: impl CredentialStore + 'static) -> Self {
+        self.storage = Box::new(storage);
+        self
+    }
+
+    /// Record premium-request usage in a custom file
+    pub fn with_usage_path(mut self, path: PathBuf) -> Self {
+        self.usage_path = path;
+        self
+    }
+
+    /// Send API requests to a custom base URL
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = Some(base_url.into());
+        self
+    }
+
+    /// Refresh session tokens from a custom URL
+    pub fn with_refresh_url(mut self, refresh_url: impl Into<String>) -> Self {
+        self.refresh_url = Some(refresh_url.into());
+        self
+    }
+
+    /// Set the maximum number of attempts for requests that fail with 429 or 5xx
+    /// (at least 1; 1 disables retries)
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Set the backoff delay before the first retry (doubled on each retry)
+    pub fn with_retry_base_delay(mut self, delay: Duration) -> Self {
+        self.retry_base_delay = delay;
+        self
+    }
+
+    /// Fail requests that take longer than `timeout` with a [`RequestTimeout`]
+    /// error (default 120s)
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Turn a send error into a [`RequestTimeout`] if the request timed out
+    fn send_error(&self, url: &str, error: reqwest::Error) -> anyhow::Error {
+        if error.is_timeout() {
+            RequestTimeout {
+                url: url.to_string(),
+                timeout: self.timeout,
+            }
+            .into()
+        } else {
+            anyhow::Error::new(error)
+        }
+    }
+
+    /// Get the API base URL in effect
+    fn base_url(&self) -> String {
+        self.base_url
+            .clone()
+            .unwrap_or_else(|| self.auth.base_url())
+    }
+
+    /// Get the token refresh URL in effect
+    fn refresh_url(&self) -> String {
+        self.refresh_url
+            .clone()
+            .unwrap_or_else(|| self.auth.refresh_url())
+    }
+
+    /// Load provider from the stored credentials of `profile` (`None` for the
+    /// unnamed profile).
+    ///
+    /// The credential backend is chosen by
+    /// [`CREDENTIAL_STORE_ENV`](super::storage::CREDENTIAL_STORE_ENV).
+    pub fn from_storage(profile: Option<&str>) -> Result<Self> {
+        let storage = default_credential_store(profile)?;
         let stored = storage.load()?;
 
         let auth = CopilotAuth {
             refresh_token: stored.github_token,
             session_token: stored.copilot_token,
             expires_at: stored.expires_at,
+            refresh_in: stored.refresh_in,
             enterprise_url: None, // TODO: Store this in StoredAuth
         };
 
-        Self::new(auth)
+        let mut provider = Self::new(auth)?;
+        provider.storage = storage;
+        provider.usage_path = TokenStorage::for_profile(profile)?.usage_path();
+        Ok(provider)
     }
 
     /// Save current authentication to storage
@@ -115,37 +325,44 @@ impl CopilotProvider {
             github_token: self.auth.refresh_token.clone(),
             copilot_token: self.auth.session_token.clone(),
             expires_at: self.auth.expires_at,
-            refresh_in: None, // TODO: Track this
+            refresh_in: self.auth.refresh_in,
             updated_at: now,
         };
 
         self.storage.save(&stored)
     }
 
-    /// Refresh the session token if expired
+    /// Refresh the session token if it is expired or about to expire
     pub async fn ensure_valid_token(&mut self) -> Result<()> {
-        if !self.auth.is_expired() {
+        if !self.auth.needs_refresh() {
             return Ok(());
         }
 
-        println!("🔄 Session token expired, refreshing...");
+        if self.auth.is_expired() {
+            println!("🔄 Session token expired, refreshing...");
+        } else {
+            println!("🔄 Session token expiring soon, refreshing...");
+        }
 
-        let refresh_url = self.auth.refresh_url();
-        let response = self
-            .http_client
-            .get(&refresh_url)
-            .header("Accept", "application/json")
-            .header(
-                "Authorization",
-                format!("Bearer {}", self.auth.refresh_token),
-            )
-            .header("Editor-Version", "vscode/1.105.1")
-            .header("Editor-Plugin-Version", "copilot-chat/0.32.4")
-            .header("Copilot-Integration-Id", "vscode-chat")
-            .send()
-            .await
-            .context("Failed to refresh Copilot token")?;
+        self.refresh_token().await
+    }
+
+    /// Unconditionally exchange the refresh token for a new session token
+    pub async fn refresh_token(&mut self) -> Result<()> {
+        let response = self.send_with_retry(|| self.send_refresh()).await?;
+        self.apply_refresh(response).await
+    }
+
+    /// Refresh once, without backoff, after the API rejected the session token.
+    /// The request has already been answered, so its error is reported rather
+    /// than waiting out retries of a failing refresh.
+    async fn refresh_token_once(&mut self) -> Result<()> {
+        let response = self.send_refresh().await?;
+        self.apply_refresh(response).await
+    }
 
+    /// Store the session token from a refresh response
+    async fn apply_refresh(&mut self, response: reqwest::Response) -> Result<()> {
         if !response.status().is_success() {
             let status = response.status();
             let error_text = response.text().await.unwrap_or_default();
@@ -156,6 +373,8 @@ impl CopilotProvider {
         struct TokenResponse {
             token: String,
             expires_at: u64,
+            #[serde(default)]
+            refresh_in: Option<u64>,
         }
 
         let token_data: TokenResponse = response
@@ -165,6 +384,7 @@ impl CopilotProvider {
 
         self.auth.session_token = token_data.token;
         self.auth.expires_at = token_data.expires_at;
+        self.auth.refresh_in = token_data.refresh_in;
 
         // Save updated token to storage
         self.save_to_storage()?;
@@ -174,29 +394,240 @@ impl CopilotProvider {
         Ok(())
     }
 
-    /// Send a chat completion request
-    pub async fn chat_completion(&mut self, request: ChatRequest) -> Result<ChatResponse> {
-        // Ensure we have a valid token
-        self.ensure_valid_token().await?;
+    /// Send a single token refresh request
+    async fn send_refresh(&self) -> Result<reqwest::Response> {
+        let refresh_url = self.refresh_url();
+        self.http_client
+            .get(&refresh_url)
+            .timeout(self.timeout)
+            .header("Accept", "application/json")
+            .header(
+                "Authorization",
+                format!("Bearer {}", self.auth.refresh_token),
+            )
+            .header("Editor-Version", "vscode/1.105.1")
+            .header("Editor-Plugin-Version", "copilot-chat/0.32.4")
+            .header("Copilot-Integration-Id", "vscode-chat")
+            .send()
+            .await
+            .map_err(|e| self.send_error(&refresh_url, e))
+            .context("Failed to refresh Copilot token")
+    }
+
+    /// Send a request, retrying with exponential backoff on 429 and 5xx responses.
+    ///
+    /// A `Retry-After` header (in seconds) takes precedence over the computed
+    /// backoff. Other statuses, including 401 and 403, are returned immediately.
+    /// After `max_attempts` the last response is returned to the caller.
+    async fn send_with_retry<F, Fut>(&self, send: F) -> Result<reqwest::Response>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<reqwest::Response>>,
+    {
+        let mut attempt = 1;
+        loop {
+            let response = send().await?;
+            if attempt >= self.max_attempts || !is_retryable(response.status()) {
+                return Ok(response);
+            }
+
+            let delay = retry_after(&response).unwrap_or_else(|| {
+                self.retry_base_delay
+                    .saturating_mul(2u32.saturating_pow(attempt - 1))
+            });
+            let delay = delay.min(MAX_RETRY_DELAY);
+
+            println!(
+                "⏳ Copilot API returned {}, retrying in {:?} (attempt {}/{})",
+                response.status(),
+                delay,
+                attempt + 1,
+                self.max_attempts
+            );
+
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
 
-        let url = format!("{}/chat/completions", self.auth.base_url());
+    /// Send a request to an API endpoint with session authentication.
+    ///
+    /// The session token can expire (or be revoked) between the expiry check and
+    /// the request reaching the server. A `401 Unauthorized` therefore triggers a
+    /// single forced token refresh followed by one retry. If the refresh fails the
+    /// original response is returned so the caller reports the original error.
+    ///
+    /// A failed refresh before the request does not stop it from being sent: a
+    /// token inside its refresh window is still valid, and an expired one is
+    /// reported through the API's own `401` (without refreshing a second time).
+    async fn request_authorized<B: Serialize>(
+        &mut self,
+        method: reqwest::Method,
+        path: &str,
+        body: Option<&B>,
+    ) -> Result<reqwest::Response> {
+        let refresh_failed = match self.ensure_valid_token().await {
+            Ok(()) => false,
+            Err(e) => {
+                eprintln!("⚠️  Could not refresh session token: {}", e);
+                true
+            }
+        };
 
         let response = self
+            .send_with_retry(|| self.send_request(method.clone(), path, body))
+            .await?;
+        if response.status() != reqwest::StatusCode::UNAUTHORIZED || refresh_failed {
+            return Ok(response);
+        }
+
+        println!("🔄 Session token rejected, refreshing...");
+
+        if self.refresh_token_once().await.is_err() {
+            return Ok(response);
+        }
+
+        self.send_with_retry(|| self.send_request(method.clone(), path, body))
+            .await
+    }
+
+    /// POST a JSON body to an API endpoint with session authentication
+    async fn post_authorized<B: Serialize>(
+        &mut self,
+        path: &str,
+        body: &B,
+    ) -> Result<reqwest::Response> {
+        self.request_authorized(reqwest::Method::POST, path, Some(body))
+            .await
+    }
+
+    /// GET an API endpoint with session authentication
+    async fn get_authorized(&mut self, path: &str) -> Result<reqwest::Response> {
+        self.request_authorized::<()>(reqwest::Method::GET, path, None)
+            .await
+    }
+
+    /// Send a single authenticated request, with a JSON body if given
+    async fn send_request<B: Serialize>(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        body: Option<&B>,
+    ) -> Result<reqwest::Response> {
+        let url = format!("{}{}", self.base_url(), path);
+
+        let mut request = self
             .http_client
-            .post(&url)
+            .request(method, &url)
+            .timeout(self.timeout)
             .header(
                 "Authorization",
                 format!("Bearer {}", self.auth.session_token),
             )
-            .header("Content-Type", "application/json")
             .header("Accept", "application/json")
             .header("Editor-Version", "vscode/1.105.1")
             .header("Editor-Plugin-Version", "copilot-chat/0.32.4")
-            .header("Copilot-Integration-Id", "vscode-chat")
-            .json(&request)
+            .header("Copilot-Integration-Id", "vscode-chat");
+        if let Some(body) = body {
+            request = request
+                .header("Content-Type", "application/json")
+                .json(body);
+        }
+
+        request
             .send()
             .await
-            .context("Failed to send chat completion request")?;
+            .map_err(|e| self.send_error(&url, e))
+            .with_context(|| format!("Failed to send request to {}", path))
+    }
+
+    /// The models offered by the Copilot API, or the built-in
+    /// [`ModelInfo::available_models`] when the API cannot be reached
+    pub async fn known_models(&mut self) -> Vec<ModelInfo> {
+        match self.list_models().await {
+            Ok(models) if !models.is_empty() => models,
+            _ => ModelInfo::available_models(),
+        }
+    }
+
+    /// List the chat models currently offered by the Copilot API.
+    ///
+    /// Use [`ModelInfo::available_models`] as a fallback when the API cannot be
+    /// reached. Context windows the API does not report are taken from that list.
+    pub async fn list_models(&mut self) -> Result<Vec<ModelInfo>> {
+        let response = self.get_authorized("/models").await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(anyhow!(
+                "Listing models failed: {} - {}",
+                status,
+                error_text
+            ));
+        }
+
+        let models: ModelsResponse = response
+            .json()
+            .await
+            .context("Failed to parse models response")?;
+
+        let mut seen = std::collections::HashSet::new();
+        Ok(models
+            .data
+            .into_iter()
+            .filter(|model| model.capabilities.kind.as_deref().unwrap_or("chat") == "chat")
+            .filter(|model| seen.insert(model.id.clone()))
+            .map(ModelInfo::from)
+            .collect())
+    }
+
+    /// Reject requests whose estimated size exceeds the model's context window,
+    /// rather than letting the API fail with an opaque 400. Unknown models are
+    /// not checked.
+    fn check_context_limit(request: &ChatRequest) -> Result<()> {
+        let model = match ModelInfo::find(&request.model) {
+            Some(model) => model,
+            None => return Ok(()),
+        };
+
+        let estimated = request.estimated_tokens();
+        if !model.fits(estimated) {
+            return Err(anyhow!(
+                "Request is too large for {}: about {} tokens, but the model accepts at most {}",
+                model.name,
+                estimated,
+                model.context_window
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Send a chat completion request
+    pub async fn chat_completion(&mut self, request: ChatRequest) -> Result<ChatResponse> {
+        self.chat_completion_cancellable(request, CancellationToken::new())
+            .await
+    }
+
+    /// Send a chat completion request, abandoning it with a [`Cancelled`] error as
+    /// soon as `cancel` is cancelled
+    pub async fn chat_completion_cancellable(
+        &mut self,
+        request: ChatRequest,
+        cancel: CancellationToken,
+    ) -> Result<ChatResponse> {
+        tokio::select! {
+            biased;
+            _ = cancel.cancelled() => Err(Cancelled::default().into()),
+            response = self.send_chat_completion(request) => response,
+        }
+    }
+
+    async fn send_chat_completion(&mut self, request: ChatRequest) -> Result<ChatResponse> {
+        Self::check_context_limit(&request)?;
+
+        let response = self.post_authorized("/chat/completions", &request).await?;
 
         if !response.status().is_success() {
             let status = response.status();
@@ -213,20 +644,289 @@ impl CopilotProvider {
             .await
             .context("Failed to parse chat response")?;
 
+        self.record_usage(&request.model);
+
         Ok(chat_response)
     }
 
+    /// Send a chat completion request and stream the assistant's reply.
+    ///
+    /// Yields the content of each delta as it arrives; concatenating the items
+    /// gives the full message. The stream ends at the `data: [DONE]` sentinel.
+    pub async fn chat_completion_stream(&mut self, request: ChatRequest) -> Result<ChatStream> {
+        self.chat_completion_stream_cancellable(request, CancellationToken::new())
+            .await
+    }
+
+    /// Like [`chat_completion_stream`](Self::chat_completion_stream), but stops
+    /// when `cancel` is cancelled.
+    ///
+    /// Cancelling before the response starts fails with [`Cancelled`]; cancelling
+    /// mid-stream ends the stream with a [`Cancelled`] item whose `partial` holds
+    /// the content yielded so far.
+    pub async fn chat_completion_stream_cancellable(
+        &mut self,
+        mut request: ChatRequest,
+        cancel: CancellationToken,
+    ) -> Result<ChatStream> {
+        request.stream = Some(true);
+        Self::check_context_limit(&request)?;
+
+        let response = tokio::select! {
+            biased;
+            _ = cancel.cancelled() => return Err(Cancelled::default().into()),
+            response = self.post_authorized("/chat/completions", &request) => response?,
+        };
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(anyhow!(
+                "Chat completion failed: {} - {}",
+                status,
+                error_text
+            ));
+        }
+
+        self.record_usage(&request.model);
+
+        Ok(chat_stream_until(response.bytes_stream(), cancel))
+    }
+
+    /// Embed each input with an embedding model, for semantic code search.
+    ///
+    /// Returns one vector per input, in input order.
+    pub async fn embeddings(&mut self, model: &str, inputs: Vec<String>) -> Result<Vec<Vec<f32>>> {
+        let request = EmbeddingRequest {
+            model: model.to_string(),
+            input: inputs,
+        };
+
+        let response = self.post_authorized("/embeddings", &request).await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(anyhow!("Embeddings failed: {} - {}", status, error_text));
+        }
+
+        let mut embeddings: EmbeddingResponse = response
+            .json()
+            .await
+            .context("Failed to parse embeddings response")?;
+
+        if embeddings.data.len() != request.input.len() {
+            return Err(anyhow!(
+                "Embeddings response has {} vectors for {} inputs",
+                embeddings.data.len(),
+                request.input.len()
+            ));
+        }
+
+        embeddings.data.sort_by_key(|data| data.index);
+        Ok(embeddings
+            .data
+            .into_iter()
+            .map(|data| data.embedding)
+            .collect())
+    }
+
+    /// Add a completed request to the usage ledger.
+    ///
+    /// Models missing from [`ModelInfo::available_models`] are counted at a 1x
+    /// multiplier. Failing to update the ledger does not fail the request.
+    fn record_usage(&self, model: &str) {
+        let multiplier = ModelInfo::find(model)
+            .map(|info| info.multiplier)
+            .unwrap_or(1.0);
+
+        let result = UsageLedger::update(&self.usage_path, |ledger| {
+            ledger.record(model, multiplier, chrono::Utc::now())
+        });
+
+        if let Err(e) = result {
+            eprintln!("⚠️  Could not record Copilot usage: {}", e);
+        }
+    }
+
     /// Get authentication reference
     pub fn auth(&self) -> &CopilotAuth {
         &self.auth
     }
 }
 
+/// Rate limiting and server errors are worth retrying; other failures are not
+fn is_retryable(status: reqwest::StatusCode) -> bool {
+    status == reqwest::StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// Parse a `Retry-After` header given in seconds
+fn retry_after(response: &reqwest::Response) -> Option<Duration> {
+    response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+        .map(Duration::from_secs)
+}
+
 /// Chat message
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChatMessage {
-    pub role: String, // "system", "user", "assistant"
+    pub role: String, // "system", "user", "assistant", "tool"
+
+    /// Message text; empty for assistant messages that only contain tool calls
+    #[serde(default, deserialize_with = "null_as_empty")]
     pub content: String,
+
+    /// Functions the assistant asked to call
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_calls: Option<Vec<ToolCall>>,
+
+    /// For `tool` messages, the id of the call this message answers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
+}
+
+impl ChatMessage {
+    /// A plain text message from `role`
+    pub fn new(role: impl Into<String>, content: impl Into<String>) -> Self {
+        Self {
+            role: role.into(),
+            content: content.into(),
+            tool_calls: None,
+            tool_call_id: None,
+        }
+    }
+
+    /// A system prompt
+    pub fn system(content: impl Into<String>) -> Self {
+        Self::new("system", content)
+    }
+
+    /// A user message
+    pub fn user(content: impl Into<String>) -> Self {
+        Self::new("user", content)
+    }
+
+    /// An assistant message, e.g. an earlier reply in the conversation
+    pub fn assistant(content: impl Into<String>) -> Self {
+        Self::new("assistant", content)
+    }
+}
+
+/// Deserialize a string that may be `null` (as message content is alongside
+/// tool calls)
+fn null_as_empty<'de, D>(deserializer: D) -> std::result::Result<String, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(Option::<String>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+/// A tool the model may call (OpenAI function-calling format)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolDef {
+    /// Always `"function"`
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub function: FunctionDef,
+}
+
+impl ToolDef {
+    /// Define a function tool taking arguments described by a JSON schema
+    pub fn function(
+        name: impl Into<String>,
+        description: impl Into<String>,
+        parameters: serde_json::Value,
+    ) -> Self {
+        Self {
+            kind: "function".to_string(),
+            function: FunctionDef {
+                name: name.into(),
+                description: Some(description.into()),
+                parameters,
+            },
+        }
+    }
+}
+
+/// Function signature offered to the model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionDef {
+    pub name: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// JSON schema of the arguments object
+    pub parameters: serde_json::Value,
+}
+
+/// Whether and which tool the model should call
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ToolChoice {
+    /// `"none"`, `"auto"` or `"required"`
+    Mode(ToolChoiceMode),
+    /// Force a call to one function
+    Function {
+        #[serde(rename = "type")]
+        kind: String,
+        function: FunctionName,
+    },
+}
+
+impl ToolChoice {
+    /// Force the model to call the function `name`
+    pub fn function(name: impl Into<String>) -> Self {
+        ToolChoice::Function {
+            kind: "function".to_string(),
+            function: FunctionName { name: name.into() },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ToolChoiceMode {
+    None,
+    Auto,
+    Required,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FunctionName {
+    pub name: String,
+}
+
+/// A function call requested by the model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolCall {
+    pub id: String,
+
+    /// Always `"function"`
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub function: FunctionCall,
+}
+
+/// Function name and JSON-encoded arguments of a tool call
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionCall {
+    pub name: String,
+    pub arguments: String,
+}
+
+impl FunctionCall {
+    /// Decode the JSON arguments
+    pub fn parse_arguments<T: serde::de::DeserializeOwned>(&self) -> Result<T> {
+        serde_json::from_str(&self.arguments)
+            .with_context(|| format!("Invalid arguments for tool call {}", self.name))
+    }
 }
 
 /// Chat completion request
@@ -247,6 +947,110 @@ pub struct ChatRequest {
     /// Prompt cache key for session continuity
     #[serde(skip_serializing_if = "Option::is_none", rename = "promptCacheKey")]
     pub prompt_cache_key: Option<String>,
+
+    /// Functions the model may call
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tools: Option<Vec<ToolDef>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_choice: Option<ToolChoice>,
+}
+
+impl ChatRequest {
+    /// Start building a request from a model name and messages
+    pub fn builder() -> ChatRequestBuilder {
+        ChatRequestBuilder::default()
+    }
+
+    /// Estimate the prompt size in tokens.
+    ///
+    /// Uses the common heuristic of four characters per token over all message
+    /// contents, which is close to tiktoken's count for English text and code.
+    pub fn estimated_tokens(&self) -> usize {
+        let chars: usize = self
+            .messages
+            .iter()
+            .map(|message| message.content.chars().count())
+            .sum();
+        chars.div_ceil(4)
+    }
+}
+
+/// Builder for a [`ChatRequest`]; messages are sent in the order they are added
+#[derive(Debug, Clone, Default)]
+pub struct ChatRequestBuilder {
+    model: Option<String>,
+    messages: Vec<ChatMessage>,
+    temperature: Option<f32>,
+}
+
+impl ChatRequestBuilder {
+    /// Set the model to send the request to (required)
+    pub fn model(mut self, model: impl Into<String>) -> Self {
+        self.model = Some(model.into());
+        self
+    }
+
+    /// Add a system prompt
+    pub fn system(mut self, content: impl Into<String>) -> Self {
+        self.messages.push(ChatMessage::system(content));
+        self
+    }
+
+    /// Add a user message
+    pub fn user(mut self, content: impl Into<String>) -> Self {
+        self.messages.push(ChatMessage::user(content));
+        self
+    }
+
+    /// Add an assistant message
+    pub fn assistant(mut self, content: impl Into<String>) -> Self {
+        self.messages.push(ChatMessage::assistant(content));
+        self
+    }
+
+    /// Add messages in order, e.g. the conversation so far
+    pub fn messages(mut self, messages: impl IntoIterator<Item = ChatMessage>) -> Self {
+        self.messages.extend(messages);
+        self
+    }
+
+    /// Set the sampling temperature (0.0 to 2.0)
+    pub fn temperature(mut self, temperature: f32) -> Self {
+        self.temperature = Some(temperature);
+        self
+    }
+
+    /// Build the request, failing if the model or messages are missing or the
+    /// temperature is out of range
+    pub fn build(self) -> Result<ChatRequest> {
+        let model = match self.model {
+            Some(model) if !model.is_empty() => model,
+            _ => return Err(anyhow!("Chat request has no model")),
+        };
+        if self.messages.is_empty() {
+            return Err(anyhow!("Chat request has no messages"));
+        }
+        if let Some(temperature) = self.temperature {
+            if !(0.0..=2.0).contains(&temperature) {
+                return Err(anyhow!(
+                    "Temperature must be between 0.0 and 2.0, got {}",
+                    temperature
+                ));
+            }
+        }
+
+        Ok(ChatRequest {
+            model,
+            messages: self.messages,
+            temperature: self.temperature,
+            stream: None,
+            n: None,
+            prompt_cache_key: None,
+            tools: None,
+            tool_choice: None,
+        })
+    }
 }
 
 /// Token usage information
@@ -281,12 +1085,100 @@ pub struct ChatResponse {
     pub usage: Option<TokenUsage>,
 }
 
+/// Embeddings request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbeddingRequest {
+    pub model: String,
+    pub input: Vec<String>,
+}
+
+/// Embedding of one input
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbeddingData {
+    /// Position of the input in the request
+    pub index: usize,
+    pub embedding: Vec<f32>,
+}
+
+/// Token usage of an embeddings request; there are no completion tokens
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbeddingUsage {
+    pub prompt_tokens: u32,
+    pub total_tokens: u32,
+}
+
+/// Embeddings response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbeddingResponse {
+    pub data: Vec<EmbeddingData>,
+    pub model: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage: Option<EmbeddingUsage>,
+}
+
+/// Response of the `/models` endpoint
+#[derive(Debug, Deserialize)]
+struct ModelsResponse {
+    data: Vec<ApiModel>,
+}
+
+/// A model as listed by the `/models` endpoint
+#[derive(Debug, Deserialize)]
+struct ApiModel {
+    id: String,
+    #[serde(default)]
+    capabilities: ApiModelCapabilities,
+    #[serde(default)]
+    billing: Option<ApiModelBilling>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ApiModelCapabilities {
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    #[serde(default)]
+    limits: ApiModelLimits,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ApiModelLimits {
+    max_context_window_tokens: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiModelBilling {
+    #[serde(default)]
+    multiplier: f32,
+}
+
+impl From<ApiModel> for ModelInfo {
+    fn from(model: ApiModel) -> Self {
+        let multiplier = model.billing.map(|b| b.multiplier).unwrap_or(0.0);
+        let context_window = model
+            .capabilities
+            .limits
+            .max_context_window_tokens
+            .or_else(|| ModelInfo::find(&model.id).map(|m| m.context_window))
+            .unwrap_or(0);
+
+        ModelInfo {
+            name: model.id,
+            multiplier,
+            tier: ModelTier::from_multiplier(multiplier),
+            context_window,
+        }
+    }
+}
+
 /// Model information with multiplier
 #[derive(Debug, Clone)]
 pub struct ModelInfo {
     pub name: String,
     pub multiplier: f32,
     pub tier: ModelTier,
+    /// Context window size in tokens (0 if unknown)
+    pub context_window: u32,
 }
 
 /// Model pricing tier
@@ -298,6 +1190,21 @@ pub enum ModelTier {
     Premium,  // High cost (> 1x multiplier)
 }
 
+impl ModelTier {
+    /// Classify a premium-request multiplier
+    pub fn from_multiplier(multiplier: f32) -> Self {
+        if multiplier <= 0.0 {
+            ModelTier::Included
+        } else if multiplier < 1.0 {
+            ModelTier::Budget
+        } else if multiplier == 1.0 {
+            ModelTier::Standard
+        } else {
+            ModelTier::Premium
+        }
+    }
+}
+
 impl ModelInfo {
     /// Get list of available models with their multipliers
     pub fn available_models() -> Vec<ModelInfo> {
@@ -307,94 +1214,112 @@ impl ModelInfo {
                 name: "gpt-4o".to_string(),
                 multiplier: 0.0,
                 tier: ModelTier::Included,
+                context_window: 128_000,
             },
             ModelInfo {
                 name: "gpt-4.1".to_string(),
                 multiplier: 0.0,
                 tier: ModelTier::Included,
+                context_window: 1_047_576,
             },
             ModelInfo {
                 name: "gpt-5-mini".to_string(),
                 multiplier: 0.0,
                 tier: ModelTier::Included,
+                context_window: 400_000,
             },
             ModelInfo {
                 name: "raptor-mini".to_string(),
                 multiplier: 0.0,
                 tier: ModelTier::Included,
+                context_window: 264_000,
             },
             // Budget models
             ModelInfo {
                 name: "grok-code-fast-1".to_string(),
                 multiplier: 0.25,
                 tier: ModelTier::Budget,
+                context_window: 256_000,
             },
             ModelInfo {
                 name: "claude-haiku-4.5".to_string(),
                 multiplier: 0.33,
                 tier: ModelTier::Budget,
+                context_window: 200_000,
             },
             ModelInfo {
                 name: "gpt-5.1-codex-mini".to_string(),
                 multiplier: 0.33,
                 tier: ModelTier::Budget,
+                context_window: 400_000,
             },
             // Standard models
             ModelInfo {
                 name: "claude-sonnet-4".to_string(),
                 multiplier: 1.0,
                 tier: ModelTier::Standard,
+                context_window: 200_000,
             },
             ModelInfo {
                 name: "claude-sonnet-4.5".to_string(),
                 multiplier: 1.0,
                 tier: ModelTier::Standard,
+                context_window: 200_000,
             },
             ModelInfo {
                 name: "gemini-2.5-pro".to_string(),
                 multiplier: 1.0,
                 tier: ModelTier::Standard,
+                context_window: 1_048_576,
             },
             ModelInfo {
                 name: "gemini-3-pro".to_string(),
                 multiplier: 1.0,
                 tier: ModelTier::Standard,
+                context_window: 1_048_576,
             },
             ModelInfo {
                 name: "gpt-5".to_string(),
                 multiplier: 1.0,
                 tier: ModelTier::Standard,
+                context_window: 400_000,
             },
             ModelInfo {
                 name: "gpt-5.1".to_string(),
                 multiplier: 1.0,
                 tier: ModelTier::Standard,
+                context_window: 400_000,
             },
             ModelInfo {
                 name: "gpt-5.1-codex".to_string(),
                 multiplier: 1.0,
                 tier: ModelTier::Standard,
+                context_window: 400_000,
             },
             ModelInfo {
                 name: "gpt-5.1-codex-max".to_string(),
                 multiplier: 1.0,
                 tier: ModelTier::Standard,
+                context_window: 400_000,
             },
             ModelInfo {
                 name: "gpt-5.2".to_string(),
                 multiplier: 1.0,
                 tier: ModelTier::Standard,
+                context_window: 400_000,
             },
             // Premium models
             ModelInfo {
                 name: "claude-opus-4.5".to_string(),
                 multiplier: 3.0,
                 tier: ModelTier::Premium,
+                context_window: 200_000,
             },
             ModelInfo {
                 name: "claude-opus-4.1".to_string(),
                 multiplier: 10.0,
                 tier: ModelTier::Premium,
+                context_window: 200_000,
             },
         ]
     }
@@ -406,15 +1331,118 @@ impl ModelInfo {
             .find(|m| m.name == model_name)
     }
 
+    /// Check that `model_name` is one of `models`, suggesting up to three close
+    /// matches if it is not
+    pub fn check_known(
+        model_name: &str,
+        models: &[ModelInfo],
+    ) -> std::result::Result<(), UnknownModel> {
+        if models.iter().any(|m| m.name == model_name) {
+            return Ok(());
+        }
+
+        let max_distance = (model_name.chars().count() / 3).max(1);
+        let mut close: Vec<(usize, &str)> = models
+            .iter()
+            .map(|m| (edit_distance(model_name, &m.name), m.name.as_str()))
+            .filter(|(distance, _)| *distance <= max_distance)
+            .collect();
+        close.sort();
+        close.dedup();
+
+        Err(UnknownModel {
+            model: model_name.to_string(),
+            suggestions: close
+                .into_iter()
+                .take(3)
+                .map(|(_, name)| name.to_string())
+                .collect(),
+        })
+    }
+
+    /// Check whether a prompt of `tokens` tokens fits in the context window
+    pub fn fits(&self, tokens: usize) -> bool {
+        tokens <= self.context_window as usize
+    }
+
     /// Calculate premium requests for a number of prompts
     pub fn calculate_premium_requests(&self, prompt_count: u32) -> f32 {
         prompt_count as f32 * self.multiplier
     }
 }
 
+/// Levenshtein distance between two strings, ignoring ASCII case
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.to_ascii_lowercase().chars().collect();
+    let b: Vec<char> = b.to_ascii_lowercase().chars().collect();
+
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+pub(crate) mod test_support {
+    //! Provider fixtures shared by the Copilot tests
+
+    use super::*;
+    use wiremock::MockServer;
+
+    pub(crate) fn test_auth(expires_at: u64) -> CopilotAuth {
+        CopilotAuth {
+            refresh_token: "ghu_test".to_string(),
+            session_token: "tid=stale".to_string(),
+            expires_at,
+            refresh_in: None,
+            enterprise_url: None,
+        }
+    }
+
+    /// A chat completion body whose single choice replies `content`
+    pub(crate) fn chat_response_body(content: &str) -> serde_json::Value {
+        serde_json::json!({
+            "id": "chatcmpl-1",
+            "object": "chat.completion",
+            "created": 0,
+            "model": "gpt-4o",
+            "choices": [{
+                "index": 0,
+                "message": {"role": "assistant", "content": content},
+                "finish_reason": "stop"
+            }]
+        })
+    }
+
+    /// A provider sending to `server`, with its token and usage files in `dir`
+    pub(crate) fn test_provider(server: &MockServer, dir: &tempfile::TempDir) -> CopilotProvider {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        CopilotProvider::new(test_auth(now + 3600))
+            .unwrap()
+            .with_storage(TokenStorage::with_path(dir.path().join("auth.json")))
+            .with_usage_path(dir.path().join("copilot-usage.json"))
+            .with_base_url(server.uri())
+            .with_refresh_url(format!("{}/token", server.uri()))
+            .with_retry_base_delay(Duration::from_millis(1))
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use super::test_support::{chat_response_body, test_auth, test_provider};
     use super::*;
+    use wiremock::matchers::{body_json, header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
 
     #[test]
     fn test_auth_expiration() {
@@ -427,6 +1455,7 @@ mod tests {
             refresh_token: "ghu_test".to_string(),
             session_token: "tid=test;exp=123".to_string(),
             expires_at: now - 100, // Expired 100 seconds ago
+            refresh_in: None,
             enterprise_url: None,
         };
 
@@ -440,12 +1469,35 @@ mod tests {
         assert!(!auth2.is_expired());
     }
 
+    #[test]
+    fn test_needs_refresh_inside_refresh_window() {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        let auth = CopilotAuth {
+            refresh_in: Some(300),
+            ..test_auth(now + 120)
+        };
+        assert!(!auth.is_expired());
+        assert!(auth.needs_refresh());
+
+        let fresh = CopilotAuth {
+            refresh_in: Some(300),
+            ..test_auth(now + 3600)
+        };
+        assert!(!fresh.needs_refresh());
+        assert!(!test_auth(now + 120).needs_refresh());
+    }
+
     #[test]
     fn test_base_url() {
         let auth = CopilotAuth {
             refresh_token: "ghu_test".to_string(),
             session_token: "tid=test".to_string(),
             expires_at: 999999999,
+            refresh_in: None,
             enterprise_url: None,
         };
 
@@ -488,4 +1540,737 @@ mod tests {
         let opus = ModelInfo::find("claude-opus-4.1").unwrap();
         assert_eq!(opus.calculate_premium_requests(3), 30.0);
     }
+
+    #[test]
+    fn test_estimated_tokens() {
+        let mut request = test_request();
+        // "Say hello!" is 10 characters
+        assert_eq!(request.estimated_tokens(), 3);
+
+        request.messages.push(ChatMessage {
+            role: "system".to_string(),
+            content: "You are a helpful assistant.".to_string(),
+            tool_calls: None,
+            tool_call_id: None,
+        });
+        assert_eq!(request.estimated_tokens(), 10);
+
+        request.messages.clear();
+        assert_eq!(request.estimated_tokens(), 0);
+    }
+
+    #[test]
+    fn test_model_context_windows() {
+        for model in ModelInfo::available_models() {
+            assert!(
+                model.context_window > 0,
+                "{} has no context window",
+                model.name
+            );
+        }
+
+        let gpt4o = ModelInfo::find("gpt-4o").unwrap();
+        assert_eq!(gpt4o.context_window, 128_000);
+        assert!(gpt4o.fits(128_000));
+        assert!(!gpt4o.fits(128_001));
+
+        let sonnet = ModelInfo::find("claude-sonnet-4.5").unwrap();
+        assert_eq!(sonnet.context_window, 200_000);
+        assert!(!sonnet.fits(200_001));
+    }
+
+    #[test]
+    fn test_unknown_model_suggests_close_names() {
+        let models = ModelInfo::available_models();
+        assert!(ModelInfo::check_known("gpt-4o", &models).is_ok());
+
+        let err = ModelInfo::check_known("gpt-4p", &models).unwrap_err();
+        assert_eq!(err.suggestions[0], "gpt-4o");
+        assert!(err
+            .to_string()
+            .starts_with("Unknown model `gpt-4p`, did you mean `gpt-4o`"));
+
+        let err = ModelInfo::check_known("claude-sonet-4.5", &models).unwrap_err();
+        assert_eq!(err.suggestions[0], "claude-sonnet-4.5");
+
+        let err = ModelInfo::check_known("llama", &models).unwrap_err();
+        assert!(err.suggestions.is_empty());
+        assert!(err.to_string().contains("copilot models"));
+    }
+
+    #[test]
+    fn test_builder_matches_hand_built_request() {
+        let built = ChatRequest::builder()
+            .model("gpt-4o")
+            .system("You are terse.")
+            .user("Say hello!")
+            .assistant("Hello!")
+            .user("Again")
+            .temperature(0.2)
+            .build()
+            .unwrap();
+
+        let message = |role: &str, content: &str| ChatMessage {
+            role: role.to_string(),
+            content: content.to_string(),
+            tool_calls: None,
+            tool_call_id: None,
+        };
+        let expected = ChatRequest {
+            model: "gpt-4o".to_string(),
+            messages: vec![
+                message("system", "You are terse."),
+                message("user", "Say hello!"),
+                message("assistant", "Hello!"),
+                message("user", "Again"),
+            ],
+            temperature: Some(0.2),
+            stream: None,
+            n: None,
+            prompt_cache_key: None,
+            tools: None,
+            tool_choice: None,
+        };
+
+        assert_eq!(
+            serde_json::to_value(&built).unwrap(),
+            serde_json::to_value(&expected).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_builder_rejects_invalid_requests() {
+        let err = ChatRequest::builder().model("gpt-4o").build().unwrap_err();
+        assert!(err.to_string().contains("no messages"));
+
+        let err = ChatRequest::builder().user("Hi").build().unwrap_err();
+        assert!(err.to_string().contains("no model"));
+
+        let err = ChatRequest::builder()
+            .model("gpt-4o")
+            .user("Hi")
+            .temperature(2.5)
+            .build()
+            .unwrap_err();
+        assert!(err.to_string().contains("between 0.0 and 2.0"));
+    }
+
+    #[test]
+    fn test_request_serializes_tools() {
+        let mut request = test_request();
+        let value = serde_json::to_value(&request).unwrap();
+        assert!(value.get("tools").is_none());
+        assert!(value.get("tool_choice").is_none());
+        assert!(value["messages"][0].get("tool_calls").is_none());
+
+        request.tools = Some(vec![ToolDef::function(
+            "get_weather",
+            "Get the current weather for a city",
+            serde_json::json!({
+                "type": "object",
+                "properties": { "city": { "type": "string" } },
+                "required": ["city"]
+            }),
+        )]);
+        request.tool_choice = Some(ToolChoice::Mode(ToolChoiceMode::Auto));
+
+        let value = serde_json::to_value(&request).unwrap();
+        assert_eq!(value["tool_choice"], "auto");
+        assert_eq!(value["tools"][0]["type"], "function");
+        assert_eq!(value["tools"][0]["function"]["name"], "get_weather");
+        assert_eq!(
+            value["tools"][0]["function"]["parameters"]["required"][0],
+            "city"
+        );
+
+        request.tool_choice = Some(ToolChoice::function("get_weather"));
+        let value = serde_json::to_value(&request).unwrap();
+        assert_eq!(
+            value["tool_choice"],
+            serde_json::json!({ "type": "function", "function": { "name": "get_weather" } })
+        );
+    }
+
+    #[test]
+    fn test_response_with_tool_call() {
+        let response: ChatResponse = serde_json::from_value(serde_json::json!({
+            "id": "chatcmpl-2",
+            "object": "chat.completion",
+            "created": 0,
+            "model": "gpt-4o",
+            "choices": [{
+                "index": 0,
+                "message": {
+                    "role": "assistant",
+                    "content": null,
+                    "tool_calls": [{
+                        "id": "call_1",
+                        "type": "function",
+                        "function": {
+                            "name": "get_weather",
+                            "arguments": "{\"city\":\"Berlin\"}"
+                        }
+                    }]
+                },
+                "finish_reason": "tool_calls"
+            }]
+        }))
+        .unwrap();
+
+        let message = &response.choices[0].message;
+        assert_eq!(message.content, "");
+        let calls = message.tool_calls.as_ref().unwrap();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].id, "call_1");
+        assert_eq!(calls[0].function.name, "get_weather");
+
+        let arguments: serde_json::Value = calls[0].function.parse_arguments().unwrap();
+        assert_eq!(arguments["city"], "Berlin");
+    }
+
+    fn test_request() -> ChatRequest {
+        ChatRequest {
+            model: "gpt-4o".to_string(),
+            messages: vec![ChatMessage {
+                role: "user".to_string(),
+                content: "Say hello!".to_string(),
+                tool_calls: None,
+                tool_call_id: None,
+            }],
+            temperature: None,
+            stream: Some(false),
+            n: None,
+            prompt_cache_key: None,
+            tools: None,
+            tool_choice: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unauthorized_triggers_refresh_and_retry() {
+        let server = MockServer::start().await;
+        let dir = tempfile::tempdir().unwrap();
+
+        Mock::given(method("POST"))
+            .and(path("/chat/completions"))
+            .and(header("Authorization", "Bearer tid=stale"))
+            .respond_with(ResponseTemplate::new(401))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/token"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "token": "tid=fresh",
+                "expires_at": 4_000_000_000u64
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/chat/completions"))
+            .and(header("Authorization", "Bearer tid=fresh"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(chat_response_body("Hello!")))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut provider = test_provider(&server, &dir);
+        let response = provider.chat_completion(test_request()).await.unwrap();
+
+        assert_eq!(response.choices[0].message.content, "Hello!");
+        assert_eq!(provider.auth().session_token, "tid=fresh");
+    }
+
+    #[tokio::test]
+    async fn test_unauthorized_retries_only_once() {
+        let server = MockServer::start().await;
+        let dir = tempfile::tempdir().unwrap();
+
+        Mock::given(method("POST"))
+            .and(path("/chat/completions"))
+            .respond_with(ResponseTemplate::new(401))
+            .expect(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/token"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "token": "tid=fresh",
+                "expires_at": 4_000_000_000u64
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut provider = test_provider(&server, &dir);
+        let err = provider.chat_completion(test_request()).await.unwrap_err();
+
+        assert!(err.to_string().contains("401"));
+    }
+
+    #[tokio::test]
+    async fn test_failed_refresh_returns_original_error() {
+        let server = MockServer::start().await;
+        let dir = tempfile::tempdir().unwrap();
+
+        Mock::given(method("POST"))
+            .and(path("/chat/completions"))
+            .respond_with(ResponseTemplate::new(401).set_body_string("bad token"))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/token"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut provider = test_provider(&server, &dir);
+        let err = provider.chat_completion(test_request()).await.unwrap_err();
+
+        assert!(err.to_string().contains("Chat completion failed"));
+        assert!(err.to_string().contains("bad token"));
+    }
+
+    #[tokio::test]
+    async fn test_forbidden_refresh_returns_original_error() {
+        let server = MockServer::start().await;
+        let dir = tempfile::tempdir().unwrap();
+
+        Mock::given(method("POST"))
+            .and(path("/chat/completions"))
+            .respond_with(ResponseTemplate::new(401).set_body_string("bad token"))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/token"))
+            .respond_with(ResponseTemplate::new(403))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut provider = test_provider(&server, &dir);
+        let err = provider.chat_completion(test_request()).await.unwrap_err();
+
+        assert!(err.to_string().contains("Chat completion failed"));
+        assert!(err.to_string().contains("bad token"));
+    }
+
+    #[tokio::test]
+    async fn test_failed_early_refresh_still_sends_request() {
+        let server = MockServer::start().await;
+        let dir = tempfile::tempdir().unwrap();
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        Mock::given(method("GET"))
+            .and(path("/token"))
+            .respond_with(ResponseTemplate::new(403))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/chat/completions"))
+            .and(header("Authorization", "Bearer tid=stale"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(chat_response_body("Hello!")))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        // Inside the refresh window, so the current token is still usable
+        let auth = CopilotAuth {
+            refresh_in: Some(300),
+            ..test_auth(now + 60)
+        };
+        let mut provider = test_provider(&server, &dir);
+        provider.auth = auth;
+
+        let response = provider.chat_completion(test_request()).await.unwrap();
+        assert_eq!(response.choices[0].message.content, "Hello!");
+    }
+
+    #[tokio::test]
+    async fn test_expired_token_with_failed_refresh_reports_api_error() {
+        let server = MockServer::start().await;
+        let dir = tempfile::tempdir().unwrap();
+
+        Mock::given(method("GET"))
+            .and(path("/token"))
+            .respond_with(ResponseTemplate::new(403))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/chat/completions"))
+            .respond_with(ResponseTemplate::new(401).set_body_string("token expired"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut provider = test_provider(&server, &dir);
+        provider.auth = test_auth(0);
+
+        let err = provider.chat_completion(test_request()).await.unwrap_err();
+        assert!(err.to_string().contains("token expired"));
+    }
+
+    #[tokio::test]
+    async fn test_token_refreshed_before_expiry() {
+        let server = MockServer::start().await;
+        let dir = tempfile::tempdir().unwrap();
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        Mock::given(method("GET"))
+            .and(path("/token"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "token": "tid=fresh",
+                "expires_at": now + 1800,
+                "refresh_in": 300
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        // Not expired yet, but inside the 300s refresh window
+        let auth = CopilotAuth {
+            refresh_in: Some(300),
+            ..test_auth(now + 60)
+        };
+        let mut provider = CopilotProvider::new(auth)
+            .unwrap()
+            .with_storage(TokenStorage::with_path(dir.path().join("auth.json")))
+            .with_refresh_url(format!("{}/token", server.uri()));
+
+        provider.ensure_valid_token().await.unwrap();
+
+        assert_eq!(provider.auth().session_token, "tid=fresh");
+        assert_eq!(provider.auth().refresh_in, Some(300));
+    }
+
+    #[tokio::test]
+    async fn test_chat_completion_stream_yields_deltas() {
+        use futures_util::StreamExt;
+        use wiremock::matchers::body_partial_json;
+
+        let server = MockServer::start().await;
+        let dir = tempfile::tempdir().unwrap();
+
+        let body = concat!(
+            "data: {\"choices\":[],\"prompt_filter_results\":[]}\n\n",
+            "data: {\"choices\":[{\"index\":0,\"delta\":{\"role\":\"assistant\"}}]}\n\n",
+            "data: {\"choices\":[{\"index\":0,\"delta\":{\"content\":\"Hel\"}}]}\n\n",
+            "data: {\"choices\":[{\"index\":0,\"delta\":{\"content\":\"lo!\"}}]}\n\n",
+            "data: [DONE]\n\n",
+        );
+        Mock::given(method("POST"))
+            .and(path("/chat/completions"))
+            .and(body_partial_json(serde_json::json!({"stream": true})))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(body, "text/event-stream"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut provider = test_provider(&server, &dir);
+        let deltas: Vec<String> = provider
+            .chat_completion_stream(test_request())
+            .await
+            .unwrap()
+            .map(|delta| delta.unwrap())
+            .collect()
+            .await;
+
+        assert_eq!(deltas, vec!["Hel", "lo!"]);
+        assert_eq!(deltas.concat(), "Hello!");
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_request_is_retried() {
+        let server = MockServer::start().await;
+        let dir = tempfile::tempdir().unwrap();
+
+        Mock::given(method("POST"))
+            .and(path("/chat/completions"))
+            .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "0"))
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/chat/completions"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(chat_response_body("Hello!")))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut provider = test_provider(&server, &dir);
+        let response = provider.chat_completion(test_request()).await.unwrap();
+
+        assert_eq!(response.choices[0].message.content, "Hello!");
+    }
+
+    #[tokio::test]
+    async fn test_server_errors_stop_after_max_attempts() {
+        let server = MockServer::start().await;
+        let dir = tempfile::tempdir().unwrap();
+
+        Mock::given(method("POST"))
+            .and(path("/chat/completions"))
+            .respond_with(ResponseTemplate::new(503))
+            .expect(3)
+            .mount(&server)
+            .await;
+
+        let mut provider = test_provider(&server, &dir).with_max_attempts(3);
+        let err = provider.chat_completion(test_request()).await.unwrap_err();
+
+        assert!(err.to_string().contains("503"));
+    }
+
+    #[tokio::test]
+    async fn test_oversized_request_rejected_before_sending() {
+        let server = MockServer::start().await;
+        let dir = tempfile::tempdir().unwrap();
+
+        Mock::given(method("POST"))
+            .and(path("/chat/completions"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(chat_response_body("Hello!")))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let mut request = test_request();
+        request.messages[0].content = "x".repeat(128_000 * 4 + 4);
+
+        let mut provider = test_provider(&server, &dir);
+        let err = provider.chat_completion(request.clone()).await.unwrap_err();
+        assert!(err.to_string().contains("too large for gpt-4o"));
+
+        let err = match provider.chat_completion_stream(request).await {
+            Ok(_) => panic!("oversized streaming request was sent"),
+            Err(err) => err,
+        };
+        assert!(err.to_string().contains("too large for gpt-4o"));
+    }
+
+    #[tokio::test]
+    async fn test_list_models() {
+        let server = MockServer::start().await;
+        let dir = tempfile::tempdir().unwrap();
+
+        Mock::given(method("GET"))
+            .and(path("/models"))
+            .and(header("Authorization", "Bearer tid=stale"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [
+                    {
+                        "id": "gpt-4o",
+                        "vendor": "Azure OpenAI",
+                        "capabilities": {
+                            "type": "chat",
+                            "limits": { "max_context_window_tokens": 128000 }
+                        },
+                        "billing": { "is_premium": false, "multiplier": 0 }
+                    },
+                    {
+                        "id": "claude-opus-4.5",
+                        "capabilities": { "type": "chat", "limits": {} },
+                        "billing": { "is_premium": true, "multiplier": 3 }
+                    },
+                    {
+                        "id": "new-model",
+                        "capabilities": { "type": "chat" },
+                        "billing": { "multiplier": 0.5 }
+                    },
+                    {
+                        "id": "text-embedding-3-small",
+                        "capabilities": { "type": "embeddings" }
+                    }
+                ]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut provider = test_provider(&server, &dir);
+        let models = provider.list_models().await.unwrap();
+
+        let names: Vec<_> = models.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, vec!["gpt-4o", "claude-opus-4.5", "new-model"]);
+
+        assert_eq!(models[0].tier, ModelTier::Included);
+        assert_eq!(models[0].context_window, 128_000);
+        assert_eq!(models[1].tier, ModelTier::Premium);
+        assert_eq!(models[1].multiplier, 3.0);
+        // Not reported by the API, so taken from the static list
+        assert_eq!(models[1].context_window, 200_000);
+        assert_eq!(models[2].tier, ModelTier::Budget);
+        assert_eq!(models[2].context_window, 0);
+    }
+
+    #[tokio::test]
+    async fn test_chat_completion_records_usage() {
+        let server = MockServer::start().await;
+        let dir = tempfile::tempdir().unwrap();
+
+        Mock::given(method("POST"))
+            .and(path("/chat/completions"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(chat_response_body("Hello!")))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let mut provider = test_provider(&server, &dir);
+        let mut request = test_request();
+        provider.chat_completion(request.clone()).await.unwrap();
+        request.model = "claude-sonnet-4.5".to_string();
+        provider.chat_completion(request).await.unwrap();
+
+        let ledger = UsageLedger::load(&dir.path().join("copilot-usage.json")).unwrap();
+        assert_eq!(ledger.models["gpt-4o"].requests, 1);
+        assert_eq!(ledger.models["claude-sonnet-4.5"].premium_requests, 1.0);
+        assert_eq!(ledger.total_premium_requests(), 1.0);
+    }
+
+    #[tokio::test]
+    async fn test_stalled_request_times_out() {
+        let server = MockServer::start().await;
+        let dir = tempfile::tempdir().unwrap();
+
+        Mock::given(method("POST"))
+            .and(path("/chat/completions"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(chat_response_body("Hello!"))
+                    .set_delay(Duration::from_secs(5)),
+            )
+            .mount(&server)
+            .await;
+
+        let mut provider = test_provider(&server, &dir).with_timeout(Duration::from_millis(100));
+        let err = provider.chat_completion(test_request()).await.unwrap_err();
+
+        let timeout = err.downcast_ref::<RequestTimeout>().unwrap();
+        assert_eq!(timeout.timeout, Duration::from_millis(100));
+        assert!(timeout.url.ends_with("/chat/completions"));
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_request_returns_promptly() {
+        let server = MockServer::start().await;
+        let dir = tempfile::tempdir().unwrap();
+
+        Mock::given(method("POST"))
+            .and(path("/chat/completions"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(chat_response_body("Hello!"))
+                    .set_delay(Duration::from_secs(5)),
+            )
+            .mount(&server)
+            .await;
+
+        let cancel = CancellationToken::new();
+        let trigger = cancel.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            trigger.cancel();
+        });
+
+        let mut provider = test_provider(&server, &dir);
+        let started = std::time::Instant::now();
+        let err = provider
+            .chat_completion_cancellable(test_request(), cancel)
+            .await
+            .unwrap_err();
+
+        assert!(err.downcast_ref::<Cancelled>().is_some());
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn test_requests_go_through_proxy() {
+        let proxy = MockServer::start().await;
+        let dir = tempfile::tempdir().unwrap();
+
+        // A plain-HTTP request through a proxy is sent to the proxy itself
+        Mock::given(method("POST"))
+            .and(path("/chat/completions"))
+            .and(header("Host", "copilot.invalid"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(chat_response_body("Hello!")))
+            .expect(1)
+            .mount(&proxy)
+            .await;
+
+        let mut provider = test_provider(&proxy, &dir)
+            .with_base_url("http://copilot.invalid")
+            .with_proxy(&proxy.uri())
+            .unwrap();
+        let response = provider.chat_completion(test_request()).await.unwrap();
+
+        assert_eq!(response.choices[0].message.content, "Hello!");
+    }
+
+    #[tokio::test]
+    async fn test_forbidden_fails_fast() {
+        let server = MockServer::start().await;
+        let dir = tempfile::tempdir().unwrap();
+
+        Mock::given(method("POST"))
+            .and(path("/chat/completions"))
+            .respond_with(ResponseTemplate::new(403))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut provider = test_provider(&server, &dir);
+        let err = provider.chat_completion(test_request()).await.unwrap_err();
+
+        assert!(err.to_string().contains("403"));
+    }
+
+    #[tokio::test]
+    async fn test_embeddings() {
+        let server = MockServer::start().await;
+        let dir = tempfile::tempdir().unwrap();
+
+        Mock::given(method("POST"))
+            .and(path("/embeddings"))
+            .and(header("Authorization", "Bearer tid=stale"))
+            .and(body_json(serde_json::json!({
+                "model": "text-embedding-3-small",
+                "input": ["fn main() {}", "def main(): pass"]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "object": "list",
+                "data": [
+                    {"object": "embedding", "index": 1, "embedding": [0.4, 0.5, 0.6]},
+                    {"object": "embedding", "index": 0, "embedding": [0.1, 0.2, 0.3]}
+                ],
+                "model": "text-embedding-3-small",
+                "usage": {"prompt_tokens": 8, "total_tokens": 8}
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut provider = test_provider(&server, &dir);
+        let inputs = vec!["fn main() {}".to_string(), "def main(): pass".to_string()];
+        let vectors = provider
+            .embeddings("text-embedding-3-small", inputs)
+            .await
+            .unwrap();
+
+        assert_eq!(vectors.len(), 2);
+        assert!(vectors.iter().all(|vector| vector.len() == 3));
+        assert_eq!(vectors[0], vec![0.1, 0.2, 0.3]);
+        assert_eq!(vectors[1], vec![0.4, 0.5, 0.6]);
+    }
 }
@@ -0,0 +1,198 @@
+//! Interactive multi-turn chat for `copilot chat`
+//!
+//! Each line read is sent as a user message together with the conversation so
+//! far. Lines starting with `/` are commands:
+//!
+//! - `/model <name>` switches the model for the following messages, if the
+//!   model is known
+//! - `/clear` forgets the conversation
+//! - `/quit` ends the session (as does end of input)
+
+use anyhow::Result;
+use std::io::{BufRead, Write};
+
+use super::provider::{ChatMessage, ChatRequest, CopilotProvider, ModelInfo};
+
+/// A chat session: the model in use and the conversation history
+pub struct ChatRepl {
+    model: String,
+    messages: Vec<ChatMessage>,
+    /// Models `/model` may switch to, fetched on first use
+    models: Option<Vec<ModelInfo>>,
+}
+
+impl ChatRepl {
+    pub fn new(model: impl Into<String>) -> Self {
+        Self {
+            model: model.into(),
+            messages: Vec::new(),
+            models: None,
+        }
+    }
+
+    /// Messages exchanged so far
+    pub fn messages(&self) -> &[ChatMessage] {
+        &self.messages
+    }
+
+    /// Read lines from `input` until EOF or `/quit`, writing prompts and replies
+    /// to `output`.
+    ///
+    /// A failed request is reported and dropped from the history, so the session
+    /// can continue.
+    pub async fn run(
+        &mut self,
+        provider: &mut CopilotProvider,
+        mut input: impl BufRead,
+        mut output: impl Write,
+    ) -> Result<()> {
+        loop {
+            write!(output, "{}> ", self.model)?;
+            output.flush()?;
+
+            let mut line = String::new();
+            if input.read_line(&mut line)? == 0 {
+                writeln!(output)?;
+                return Ok(());
+            }
+            let line = line.trim();
+
+            if let Some(command) = line.strip_prefix('/') {
+                let (name, argument) = match command.split_once(char::is_whitespace) {
+                    Some((name, argument)) => (name, argument.trim()),
+                    None => (command, ""),
+                };
+                match (name, argument) {
+                    ("quit", _) => return Ok(()),
+                    ("clear", _) => {
+                        self.messages.clear();
+                        writeln!(output, "🧹 Conversation cleared")?;
+                    }
+                    ("model", "") => writeln!(output, "Model: {}", self.model)?,
+                    ("model", model) => {
+                        let models = match self.models.take() {
+                            Some(models) => models,
+                            None => provider.known_models().await,
+                        };
+                        match ModelInfo::check_known(model, &models) {
+                            Ok(()) => {
+                                self.model = model.to_string();
+                                writeln!(output, "🔀 Switched to {}", self.model)?;
+                            }
+                            Err(e) => writeln!(output, "⚠️  {}", e)?,
+                        }
+                        self.models = Some(models);
+                    }
+                    _ => writeln!(
+                        output,
+                        "Unknown command /{} (try /model <name>, /clear or /quit)",
+                        name
+                    )?,
+                }
+                continue;
+            }
+            if line.is_empty() {
+                continue;
+            }
+
+            self.messages.push(ChatMessage::user(line));
+            let request = ChatRequest::builder()
+                .model(&self.model)
+                .messages(self.messages.iter().cloned())
+                .build()?;
+
+            match provider.chat_completion(request).await {
+                Ok(response) => {
+                    let reply = response
+                        .choices
+                        .into_iter()
+                        .next()
+                        .map(|choice| choice.message.content)
+                        .unwrap_or_default();
+                    writeln!(output, "{}\n", reply)?;
+                    self.messages.push(ChatMessage::assistant(reply));
+                }
+                Err(e) => {
+                    self.messages.pop();
+                    writeln!(output, "⚠️  {}\n", e)?;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::copilot::provider::test_support::{chat_response_body, test_provider};
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn reply(content: &str) -> ResponseTemplate {
+        ResponseTemplate::new(200).set_body_json(chat_response_body(content))
+    }
+
+    #[tokio::test]
+    async fn test_scripted_session() {
+        let server = MockServer::start().await;
+        let dir = tempfile::tempdir().unwrap();
+
+        Mock::given(method("POST"))
+            .and(path("/chat/completions"))
+            .respond_with(reply("Hello!"))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/chat/completions"))
+            .respond_with(reply("Hello again!"))
+            .mount(&server)
+            .await;
+
+        let mut provider = test_provider(&server, &dir);
+
+        let script =
+            "Hi\n\n/model gpt-4p\n/model gpt-4.1\nAgain\n/clear\nFresh start\n/quit\nNever sent\n";
+        let mut repl = ChatRepl::new("gpt-4o");
+        let mut output = Vec::new();
+        repl.run(&mut provider, script.as_bytes(), &mut output)
+            .await
+            .unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("Hello!"));
+        assert!(output.contains("Unknown model `gpt-4p`, did you mean `gpt-4o`"));
+        assert!(output.contains("Switched to gpt-4.1"));
+        assert!(output.contains("Conversation cleared"));
+
+        let requests: Vec<serde_json::Value> = server
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .filter(|request| request.url.path() == "/chat/completions")
+            .map(|request| request.body_json().unwrap())
+            .collect();
+        assert_eq!(requests.len(), 3);
+
+        assert_eq!(requests[0]["model"], "gpt-4o");
+        assert_eq!(requests[0]["messages"].as_array().unwrap().len(), 1);
+
+        // The second turn carries the history and the new model
+        assert_eq!(requests[1]["model"], "gpt-4.1");
+        let messages = requests[1]["messages"].as_array().unwrap();
+        let roles: Vec<_> = messages
+            .iter()
+            .map(|m| m["role"].as_str().unwrap())
+            .collect();
+        assert_eq!(roles, vec!["user", "assistant", "user"]);
+        assert_eq!(messages[1]["content"], "Hello!");
+        assert_eq!(messages[2]["content"], "Again");
+
+        // After /clear only the new message is sent
+        let messages = requests[2]["messages"].as_array().unwrap();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0]["content"], "Fresh start");
+        assert_eq!(repl.messages().len(), 2);
+    }
+}
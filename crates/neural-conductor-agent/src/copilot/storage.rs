@@ -1,6 +1,9 @@
 //! Secure token storage for GitHub Copilot credentials
 //!
-//! Handles persistence of authentication tokens with proper file permissions.
+//! Handles persistence of authentication tokens, either in a file with proper
+//! permissions ([`TokenStorage`]) or in the OS keyring ([`KeyringStorage`]).
+//! [`default_credential_store`] picks the backend from the
+//! `NEURAL_CONDUCTOR_CREDENTIAL_STORE` environment variable.
 
 use anyhow::{anyhow, Context, Result};
 use serde::{Deserialize, Serialize};
@@ -29,30 +32,102 @@ pub struct StoredAuth {
     pub updated_at: u64,
 }
 
+/// Environment variable selecting the credential backend: `file` (default) or `keyring`
+pub const CREDENTIAL_STORE_ENV: &str = "NEURAL_CONDUCTOR_CREDENTIAL_STORE";
+
+/// Keyring service name under which credentials are stored
+const KEYRING_SERVICE: &str = "neural-conductor";
+
+/// Keyring user name under which credentials are stored
+const KEYRING_USER: &str = "copilot";
+
+/// A place to persist Copilot credentials
+pub trait CredentialStore: Send + Sync {
+    /// Save authentication data, replacing any existing data
+    fn save(&self, auth: &StoredAuth) -> Result<()>;
+
+    /// Load authentication data
+    fn load(&self) -> Result<StoredAuth>;
+
+    /// Delete stored authentication data, if any
+    fn delete(&self) -> Result<()>;
+
+    /// Check if authentication data exists
+    fn exists(&self) -> bool;
+
+    /// Human-readable description of where credentials are kept
+    fn location(&self) -> String;
+}
+
+/// Create the credential backend selected by [`CREDENTIAL_STORE_ENV`] for
+/// `profile` (`None` for the unnamed profile)
+pub fn default_credential_store(profile: Option<&str>) -> Result<Box<dyn CredentialStore>> {
+    match std::env::var(CREDENTIAL_STORE_ENV).ok().as_deref() {
+        None | Some("") | Some("file") => Ok(Box::new(TokenStorage::for_profile(profile)?)),
+        Some("keyring") => Ok(Box::new(KeyringStorage::for_profile(profile)?)),
+        Some(other) => Err(anyhow!(
+            "Unknown credential store '{}' in {}; expected 'file' or 'keyring'",
+            other,
+            CREDENTIAL_STORE_ENV
+        )),
+    }
+}
+
 /// Token storage manager
 pub struct TokenStorage {
     storage_path: PathBuf,
 }
 
+/// Directory holding credentials and related state: ~/.config/neural-conductor
+pub fn config_dir() -> Result<PathBuf> {
+    let config_dir =
+        dirs::config_dir().ok_or_else(|| anyhow!("Could not determine config directory"))?;
+
+    Ok(config_dir.join("neural-conductor"))
+}
+
+/// Check that a profile name is usable in file names and keyring entries
+pub fn validate_profile_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        return Err(anyhow!(
+            "Invalid profile name '{}': use letters, digits, '-' and '_'",
+            name
+        ));
+    }
+    Ok(())
+}
+
 impl TokenStorage {
     /// Create a new token storage with the default path
     pub fn new() -> Result<Self> {
-        let storage_path = Self::default_storage_path()?;
-        Ok(Self { storage_path })
+        Self::for_profile(None)
     }
 
-    /// Create a new token storage with a custom path
-    pub fn with_path(path: PathBuf) -> Self {
-        Self { storage_path: path }
+    /// Create a token storage for a named profile in the default directory
+    pub fn for_profile(profile: Option<&str>) -> Result<Self> {
+        Self::in_dir(&config_dir()?, profile)
     }
 
-    /// Get the default storage path: ~/.config/neural-conductor/copilot-auth.json
-    fn default_storage_path() -> Result<PathBuf> {
-        let config_dir =
-            dirs::config_dir().ok_or_else(|| anyhow!("Could not determine config directory"))?;
+    /// Create a token storage for a profile in `dir`: `copilot-auth.json` for the
+    /// unnamed profile, `copilot-auth-<name>.json` for named ones
+    pub fn in_dir(dir: &Path, profile: Option<&str>) -> Result<Self> {
+        let file_name = match profile {
+            Some(name) => {
+                validate_profile_name(name)?;
+                format!("copilot-auth-{}.json", name)
+            }
+            None => "copilot-auth.json".to_string(),
+        };
+        Ok(Self::with_path(dir.join(file_name)))
+    }
 
-        let neural_config = config_dir.join("neural-conductor");
-        Ok(neural_config.join("copilot-auth.json"))
+    /// Create a new token storage with a custom path
+    pub fn with_path(path: PathBuf) -> Self {
+        Self { storage_path: path }
     }
 
     /// Ensure the parent directory exists with proper permissions
@@ -149,6 +224,19 @@ impl TokenStorage {
     pub fn path(&self) -> &Path {
         &self.storage_path
     }
+
+    /// Path of the premium-request usage ledger, next to the auth file
+    /// (`copilot-usage-<name>.json` for named profiles)
+    pub fn usage_path(&self) -> PathBuf {
+        let file_name = self
+            .storage_path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_prefix("copilot-auth"))
+            .map(|suffix| format!("copilot-usage{}", suffix))
+            .unwrap_or_else(|| "copilot-usage.json".to_string());
+        self.storage_path.with_file_name(file_name)
+    }
 }
 
 impl Default for TokenStorage {
@@ -157,6 +245,98 @@ impl Default for TokenStorage {
     }
 }
 
+impl CredentialStore for TokenStorage {
+    fn save(&self, auth: &StoredAuth) -> Result<()> {
+        TokenStorage::save(self, auth)
+    }
+
+    fn load(&self) -> Result<StoredAuth> {
+        TokenStorage::load(self)
+    }
+
+    fn delete(&self) -> Result<()> {
+        TokenStorage::delete(self)
+    }
+
+    fn exists(&self) -> bool {
+        TokenStorage::exists(self)
+    }
+
+    fn location(&self) -> String {
+        self.storage_path.display().to_string()
+    }
+}
+
+/// Credential storage in the OS keyring (macOS Keychain, Windows Credential
+/// Manager, or the Secret Service on Linux)
+pub struct KeyringStorage {
+    entry: keyring::Entry,
+}
+
+impl KeyringStorage {
+    /// Create a keyring storage using the default service and user names
+    pub fn new() -> Result<Self> {
+        Self::for_profile(None)
+    }
+
+    /// Create a keyring storage for a named profile (user `copilot-<name>`)
+    pub fn for_profile(profile: Option<&str>) -> Result<Self> {
+        let user = match profile {
+            Some(name) => {
+                validate_profile_name(name)?;
+                format!("{}-{}", KEYRING_USER, name)
+            }
+            None => KEYRING_USER.to_string(),
+        };
+        let entry = keyring::Entry::new(KEYRING_SERVICE, &user)
+            .context("Failed to open OS keyring entry")?;
+        Ok(Self { entry })
+    }
+
+    /// Create a keyring storage backed by a specific entry
+    pub fn with_entry(entry: keyring::Entry) -> Self {
+        Self { entry }
+    }
+}
+
+impl CredentialStore for KeyringStorage {
+    fn save(&self, auth: &StoredAuth) -> Result<()> {
+        let json = serde_json::to_string(auth).context("Failed to serialize auth data")?;
+        self.entry
+            .set_password(&json)
+            .context("Failed to save auth to OS keyring")
+    }
+
+    fn load(&self) -> Result<StoredAuth> {
+        let json = match self.entry.get_password() {
+            Ok(json) => json,
+            Err(keyring::Error::NoEntry) => {
+                return Err(anyhow!(
+                    "No authentication data found. Please run 'neural-conductor-agent copilot login' first."
+                ))
+            }
+            Err(e) => return Err(e).context("Failed to read auth from OS keyring"),
+        };
+
+        serde_json::from_str(&json).context("Failed to parse stored authentication data")
+    }
+
+    fn delete(&self) -> Result<()> {
+        match self.entry.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(e).context("Failed to delete auth from OS keyring"),
+        }
+    }
+
+    fn exists(&self) -> bool {
+        self.entry.get_password().is_ok()
+    }
+
+    fn location(&self) -> String {
+        format!("OS keyring (service '{}')", KEYRING_SERVICE)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -208,6 +388,95 @@ mod tests {
         assert!(!test_path.exists());
     }
 
+    fn mock_keyring() -> KeyringStorage {
+        let credential = keyring::mock::default_credential_builder()
+            .build(None, KEYRING_SERVICE, KEYRING_USER)
+            .unwrap();
+        KeyringStorage::with_entry(keyring::Entry::new_with_credential(credential))
+    }
+
+    /// Save, load and delete through the trait, as the provider and CLI do
+    fn assert_round_trip(store: &dyn CredentialStore) {
+        let auth = StoredAuth {
+            github_token: "ghu_test123".to_string(),
+            copilot_token: "test_token".to_string(),
+            expires_at: 4_000_000_000,
+            refresh_in: Some(300),
+            updated_at: 1_700_000_000,
+        };
+
+        assert!(!store.exists());
+        assert!(store.load().is_err());
+
+        store.save(&auth).unwrap();
+        assert!(store.exists());
+        let loaded = store.load().unwrap();
+        assert_eq!(loaded.github_token, auth.github_token);
+        assert_eq!(loaded.copilot_token, auth.copilot_token);
+        assert_eq!(loaded.refresh_in, auth.refresh_in);
+
+        store.delete().unwrap();
+        assert!(!store.exists());
+        // Deleting again is not an error
+        store.delete().unwrap();
+    }
+
+    #[test]
+    fn test_file_store_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_round_trip(&TokenStorage::with_path(dir.path().join("auth.json")));
+    }
+
+    #[test]
+    fn test_keyring_store_round_trip() {
+        assert_round_trip(&mock_keyring());
+    }
+
+    fn test_auth(github_token: &str) -> StoredAuth {
+        StoredAuth {
+            github_token: github_token.to_string(),
+            copilot_token: "test_token".to_string(),
+            expires_at: 4_000_000_000,
+            refresh_in: None,
+            updated_at: 1_700_000_000,
+        }
+    }
+
+    #[test]
+    fn test_profiles_are_stored_independently() {
+        let dir = tempfile::tempdir().unwrap();
+        let personal = TokenStorage::in_dir(dir.path(), Some("personal")).unwrap();
+        let work = TokenStorage::in_dir(dir.path(), Some("work")).unwrap();
+        let unnamed = TokenStorage::in_dir(dir.path(), None).unwrap();
+
+        personal.save(&test_auth("ghu_personal")).unwrap();
+        work.save(&test_auth("ghu_work")).unwrap();
+
+        assert_eq!(
+            personal.path(),
+            dir.path().join("copilot-auth-personal.json")
+        );
+        assert_eq!(personal.load().unwrap().github_token, "ghu_personal");
+        assert_eq!(work.load().unwrap().github_token, "ghu_work");
+        assert!(!unnamed.exists());
+
+        assert_eq!(
+            work.usage_path(),
+            dir.path().join("copilot-usage-work.json")
+        );
+        assert_eq!(unnamed.usage_path(), dir.path().join("copilot-usage.json"));
+
+        work.delete().unwrap();
+        assert_eq!(personal.load().unwrap().github_token, "ghu_personal");
+    }
+
+    #[test]
+    fn test_invalid_profile_name() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(TokenStorage::in_dir(dir.path(), Some("../escape")).is_err());
+        assert!(TokenStorage::in_dir(dir.path(), Some("")).is_err());
+    }
+
     #[cfg(unix)]
     #[test]
     fn test_secure_permissions() {
@@ -0,0 +1,260 @@
+//! Server-sent events parsing for streamed chat completions
+//!
+//! The chat completions endpoint streams `data: {json}` lines separated by blank
+//! lines and terminated by `data: [DONE]`. Network chunks can split a line at any
+//! byte, so incomplete lines are buffered until their newline arrives.
+
+use anyhow::{Context, Result};
+use futures_util::stream::{self, BoxStream, Stream, StreamExt};
+use serde::Deserialize;
+use std::collections::VecDeque;
+use tokio_util::sync::CancellationToken;
+
+use super::provider::Cancelled;
+
+/// Stream of incremental assistant message content
+pub type ChatStream = BoxStream<'static, Result<String>>;
+
+/// A parsed server-sent event
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SseEvent {
+    /// Payload of a `data:` line
+    Data(String),
+    /// The `data: [DONE]` sentinel
+    Done,
+}
+
+/// Incremental parser for `data:` lines
+#[derive(Debug, Default)]
+pub struct SseParser {
+    buffer: Vec<u8>,
+}
+
+impl SseParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed a chunk of bytes, returning the events completed by it
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<SseEvent> {
+        self.buffer.extend_from_slice(chunk);
+
+        let mut events = Vec::new();
+        while let Some(newline) = self.buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=newline).collect();
+            if let Some(event) = parse_line(&line) {
+                events.push(event);
+            }
+        }
+
+        events
+    }
+
+    /// Parse whatever remains in the buffer once the body has ended
+    pub fn finish(&mut self) -> Option<SseEvent> {
+        let line = std::mem::take(&mut self.buffer);
+        parse_line(&line)
+    }
+}
+
+fn parse_line(line: &[u8]) -> Option<SseEvent> {
+    let line = String::from_utf8_lossy(line);
+    let data = line.trim_end_matches(['\r', '\n']).strip_prefix("data:")?;
+    let data = data.strip_prefix(' ').unwrap_or(data);
+
+    if data == "[DONE]" {
+        Some(SseEvent::Done)
+    } else if data.is_empty() {
+        None
+    } else {
+        Some(SseEvent::Data(data.to_string()))
+    }
+}
+
+#[derive(Deserialize)]
+struct ChatChunk {
+    #[serde(default)]
+    choices: Vec<ChunkChoice>,
+}
+
+#[derive(Deserialize)]
+struct ChunkChoice {
+    #[serde(default)]
+    delta: ChatDelta,
+}
+
+/// Incremental message content in a streamed chunk
+#[derive(Debug, Default, Deserialize)]
+struct ChatDelta {
+    #[serde(default)]
+    content: Option<String>,
+}
+
+/// Extract the content delta from a `data:` payload.
+///
+/// Chunks without content (role announcements, content filter results) yield `None`.
+fn content_delta(data: &str) -> Result<Option<String>> {
+    let chunk: ChatChunk =
+        serde_json::from_str(data).context("Failed to parse chat completion chunk")?;
+
+    let content: String = chunk
+        .choices
+        .into_iter()
+        .filter_map(|choice| choice.delta.content)
+        .collect();
+
+    Ok(Some(content).filter(|c| !c.is_empty()))
+}
+
+struct State<S> {
+    bytes: S,
+    parser: SseParser,
+    pending: VecDeque<Result<String>>,
+    done: bool,
+    cancel: CancellationToken,
+    /// Content yielded so far, returned in [`Cancelled::partial`]
+    yielded: String,
+}
+
+impl<S> State<S> {
+    fn handle(&mut self, event: SseEvent) {
+        match event {
+            SseEvent::Data(data) => match content_delta(&data) {
+                Ok(Some(content)) => self.pending.push_back(Ok(content)),
+                Ok(None) => {}
+                Err(e) => self.pending.push_back(Err(e)),
+            },
+            SseEvent::Done => self.done = true,
+        }
+    }
+}
+
+/// Turn a streamed response body into a stream of content deltas
+pub fn chat_stream<S, B, E>(bytes: S) -> ChatStream
+where
+    S: Stream<Item = std::result::Result<B, E>> + Send + Unpin + 'static,
+    B: AsRef<[u8]>,
+    E: Into<anyhow::Error>,
+{
+    chat_stream_until(bytes, CancellationToken::new())
+}
+
+/// Like [`chat_stream`], but stops reading the body once `cancel` is cancelled.
+///
+/// The last item is then a [`Cancelled`] error carrying the content yielded so far.
+pub fn chat_stream_until<S, B, E>(bytes: S, cancel: CancellationToken) -> ChatStream
+where
+    S: Stream<Item = std::result::Result<B, E>> + Send + Unpin + 'static,
+    B: AsRef<[u8]>,
+    E: Into<anyhow::Error>,
+{
+    let state = State {
+        bytes,
+        parser: SseParser::new(),
+        pending: VecDeque::new(),
+        done: false,
+        cancel,
+        yielded: String::new(),
+    };
+
+    stream::unfold(state, |mut state| async move {
+        loop {
+            if let Some(item) = state.pending.pop_front() {
+                if let Ok(content) = &item {
+                    state.yielded.push_str(content);
+                }
+                return Some((item, state));
+            }
+            if state.done {
+                return None;
+            }
+
+            let next = tokio::select! {
+                biased;
+                _ = state.cancel.cancelled() => None,
+                next = state.bytes.next() => Some(next),
+            };
+            let next = match next {
+                Some(next) => next,
+                None => {
+                    state.done = true;
+                    let cancelled = Cancelled {
+                        partial: std::mem::take(&mut state.yielded),
+                    };
+                    return Some((Err(cancelled.into()), state));
+                }
+            };
+
+            match next {
+                Some(Ok(chunk)) => {
+                    for event in state.parser.push(chunk.as_ref()) {
+                        if state.done {
+                            break;
+                        }
+                        state.handle(event);
+                    }
+                }
+                Some(Err(e)) => {
+                    state.done = true;
+                    state.pending.push_back(Err(e.into()));
+                }
+                None => {
+                    if let Some(event) = state.parser.finish() {
+                        state.handle(event);
+                    }
+                    state.done = true;
+                }
+            }
+        }
+    })
+    .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lines_split_across_chunks() {
+        let mut parser = SseParser::new();
+
+        assert!(parser.push(b"data: {\"a\":").is_empty());
+        assert_eq!(
+            parser.push(b"1}\n\ndata: [DO"),
+            vec![SseEvent::Data("{\"a\":1}".to_string())]
+        );
+        assert_eq!(parser.push(b"NE]\r\n"), vec![SseEvent::Done]);
+    }
+
+    #[test]
+    fn test_ignores_comments_and_other_fields() {
+        let mut parser = SseParser::new();
+
+        let events = parser.push(b": keep-alive\nevent: message\ndata:{}\n\n");
+
+        assert_eq!(events, vec![SseEvent::Data("{}".to_string())]);
+    }
+
+    #[tokio::test]
+    async fn test_cancelling_mid_stream_returns_partial_content() {
+        let chunks = vec![
+            Ok::<_, std::io::Error>(
+                b"data: {\"choices\":[{\"delta\":{\"content\":\"Hel\"}}]}\n\n".to_vec(),
+            ),
+            Ok(b"data: {\"choices\":[{\"delta\":{\"content\":\"lo\"}}]}\n\n".to_vec()),
+        ];
+        // The body never ends, like a generation that is still running
+        let body = stream::iter(chunks).chain(stream::pending());
+        let cancel = CancellationToken::new();
+        let mut deltas = chat_stream_until(body, cancel.clone());
+
+        assert_eq!(deltas.next().await.unwrap().unwrap(), "Hel");
+        assert_eq!(deltas.next().await.unwrap().unwrap(), "lo");
+
+        cancel.cancel();
+        let err = deltas.next().await.unwrap().unwrap_err();
+        let cancelled = err.downcast_ref::<Cancelled>().unwrap();
+        assert_eq!(cancelled.partial, "Hello");
+        assert!(deltas.next().await.is_none());
+    }
+}
@@ -0,0 +1,213 @@
+//! Premium-request usage tracking
+//!
+//! Records, per model, how many chat requests were sent and how many premium
+//! requests they consumed. Copilot allowances reset at the start of each calendar
+//! month (UTC), so the ledger only holds the current billing period and starts
+//! over when a request is recorded in a new month.
+//!
+//! Several agent processes may record usage at once, so [`UsageLedger::update`]
+//! holds an exclusive lock on a `.lock` file next to the ledger while it reads,
+//! changes and writes it, and the ledger is replaced by renaming a complete
+//! temporary file so readers never see a partial write.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fs::{self, OpenOptions};
+use std::path::{Path, PathBuf};
+
+/// Usage of a single model within a billing period
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModelUsage {
+    /// Number of chat requests sent
+    pub requests: u64,
+
+    /// Premium requests consumed (requests × model multiplier)
+    pub premium_requests: f32,
+}
+
+/// Usage ledger for one billing period
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UsageLedger {
+    /// Billing period as `YYYY-MM`
+    pub period: String,
+
+    /// Usage per model name
+    pub models: BTreeMap<String, ModelUsage>,
+}
+
+impl UsageLedger {
+    /// Load the ledger from disk, starting empty if the file does not exist
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&contents).context("Failed to parse usage ledger")
+    }
+
+    /// Save the ledger to disk, replacing the previous file in one step
+    pub fn save(&self, path: &Path) -> Result<()> {
+        create_parent(path)?;
+
+        let json = serde_json::to_string_pretty(self).context("Failed to serialize usage")?;
+        let temp = sibling(path, ".tmp");
+        fs::write(&temp, json).with_context(|| format!("Failed to write {}", temp.display()))?;
+        fs::rename(&temp, path).with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Load the ledger at `path`, apply `change` and save it, holding an
+    /// exclusive lock so concurrent updates are not lost. Returns the saved
+    /// ledger.
+    pub fn update(path: &Path, change: impl FnOnce(&mut Self)) -> Result<Self> {
+        create_parent(path)?;
+
+        let lock_path = sibling(path, ".lock");
+        let lock = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&lock_path)
+            .with_context(|| format!("Failed to open {}", lock_path.display()))?;
+        lock.lock()
+            .with_context(|| format!("Failed to lock {}", lock_path.display()))?;
+
+        let mut ledger = Self::load(path)?;
+        change(&mut ledger);
+        ledger.save(path)?;
+        // The lock is released when `lock` is dropped
+        Ok(ledger)
+    }
+
+    /// Billing period containing `now`
+    pub fn period_of(now: DateTime<Utc>) -> String {
+        now.format("%Y-%m").to_string()
+    }
+
+    /// Clear the ledger if `now` falls in a later billing period
+    pub fn roll_over(&mut self, now: DateTime<Utc>) {
+        let period = Self::period_of(now);
+        if self.period != period {
+            self.period = period;
+            self.models.clear();
+        }
+    }
+
+    /// Record one request to `model` costing `premium_requests`
+    pub fn record(&mut self, model: &str, premium_requests: f32, now: DateTime<Utc>) {
+        self.roll_over(now);
+
+        let usage = self.models.entry(model.to_string()).or_default();
+        usage.requests += 1;
+        usage.premium_requests += premium_requests;
+    }
+
+    /// Total requests across all models
+    pub fn total_requests(&self) -> u64 {
+        self.models.values().map(|usage| usage.requests).sum()
+    }
+
+    /// Total premium requests across all models
+    pub fn total_premium_requests(&self) -> f32 {
+        self.models
+            .values()
+            .map(|usage| usage.premium_requests)
+            .sum()
+    }
+}
+
+/// Create the directory that will hold `path`
+fn create_parent(path: &Path) -> Result<()> {
+    match path.parent() {
+        Some(parent) => fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory: {}", parent.display())),
+        None => Ok(()),
+    }
+}
+
+/// `path` with `suffix` appended to its file name
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_record_increments_per_model() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 14, 12, 0, 0).unwrap();
+        let mut ledger = UsageLedger::default();
+
+        ledger.record("gpt-4o", 0.0, now);
+        ledger.record("claude-sonnet-4.5", 1.0, now);
+        ledger.record("claude-sonnet-4.5", 1.0, now);
+
+        assert_eq!(ledger.period, "2025-03");
+        assert_eq!(ledger.models["gpt-4o"].requests, 1);
+        assert_eq!(ledger.models["claude-sonnet-4.5"].requests, 2);
+        assert_eq!(ledger.models["claude-sonnet-4.5"].premium_requests, 2.0);
+        assert_eq!(ledger.total_requests(), 3);
+        assert_eq!(ledger.total_premium_requests(), 2.0);
+    }
+
+    #[test]
+    fn test_new_month_starts_new_period() {
+        let end_of_month = Utc.with_ymd_and_hms(2025, 3, 31, 23, 59, 59).unwrap();
+        let start_of_month = Utc.with_ymd_and_hms(2025, 4, 1, 0, 0, 0).unwrap();
+        let mut ledger = UsageLedger::default();
+
+        ledger.record("claude-opus-4.1", 10.0, end_of_month);
+        assert_eq!(ledger.total_premium_requests(), 10.0);
+
+        ledger.record("gpt-5", 1.0, start_of_month);
+        assert_eq!(ledger.period, "2025-04");
+        assert_eq!(ledger.total_requests(), 1);
+        assert_eq!(ledger.total_premium_requests(), 1.0);
+        assert!(!ledger.models.contains_key("claude-opus-4.1"));
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("copilot-usage.json");
+        let now = Utc.with_ymd_and_hms(2025, 3, 14, 12, 0, 0).unwrap();
+
+        assert_eq!(UsageLedger::load(&path).unwrap(), UsageLedger::default());
+
+        let mut ledger = UsageLedger::default();
+        ledger.record("gpt-5", 1.0, now);
+        ledger.save(&path).unwrap();
+
+        assert_eq!(UsageLedger::load(&path).unwrap(), ledger);
+    }
+
+    #[test]
+    fn test_concurrent_updates_are_not_lost() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("copilot-usage.json");
+        let now = Utc.with_ymd_and_hms(2025, 3, 14, 12, 0, 0).unwrap();
+
+        std::thread::scope(|scope| {
+            for _ in 0..16 {
+                scope.spawn(|| {
+                    for _ in 0..10 {
+                        UsageLedger::update(&path, |ledger| ledger.record("gpt-5", 1.0, now))
+                            .unwrap();
+                    }
+                });
+            }
+        });
+
+        let ledger = UsageLedger::load(&path).unwrap();
+        assert_eq!(ledger.total_requests(), 160);
+        assert_eq!(ledger.total_premium_requests(), 160.0);
+    }
+}
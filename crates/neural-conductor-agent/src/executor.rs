@@ -1,8 +1,54 @@
 //! Command execution module
 
 use crate::Result;
+use neural_conductor_shared::protocol::{OutputStream, Response};
 use neural_conductor_shared::SessionId;
-use std::process::Command;
+use std::fmt;
+use std::process::{Command, Output, Stdio};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, BufReader};
+use tokio::process::Child;
+use tokio::sync::mpsc::UnboundedSender;
+
+/// Time a timed-out process gets to exit after SIGTERM before it is killed
+const KILL_GRACE_PERIOD: Duration = Duration::from_secs(2);
+
+/// Error returned when a command exceeds its timeout and is killed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandTimeout {
+    pub command: String,
+    pub timeout: Duration,
+}
+
+impl fmt::Display for CommandTimeout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Command '{}' timed out after {:?}",
+            self.command, self.timeout
+        )
+    }
+}
+
+impl std::error::Error for CommandTimeout {}
+
+/// Environment applied to an executed command
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommandEnv {
+    /// Variables to set, applied in order
+    pub vars: Vec<(String, String)>,
+    /// Clear the inherited environment before setting `vars`
+    pub clear: bool,
+}
+
+impl CommandEnv {
+    fn apply(&self, cmd: &mut Command) {
+        if self.clear {
+            cmd.env_clear();
+        }
+        cmd.envs(self.vars.iter().map(|(k, v)| (k, v)));
+    }
+}
 
 /// Execute a command and return the result
 pub fn execute_command(
@@ -10,9 +56,11 @@ pub fn execute_command(
     command: &str,
     args: &[String],
     workdir: Option<&str>,
+    env: &CommandEnv,
 ) -> Result<(i32, String, String)> {
     let mut cmd = Command::new(command);
     cmd.args(args);
+    env.apply(&mut cmd);
 
     if let Some(dir) = workdir {
         cmd.current_dir(dir);
@@ -20,9 +68,324 @@ pub fn execute_command(
 
     let output = cmd.output()?;
 
+    Ok(into_result(output))
+}
+
+/// Execute a command without blocking the async runtime and return the result
+pub async fn execute_command_async(
+    session_id: &SessionId,
+    command: &str,
+    args: &[String],
+    workdir: Option<&str>,
+    env: &CommandEnv,
+) -> Result<(i32, String, String)> {
+    execute_command_with_timeout(session_id, command, args, workdir, env, None).await
+}
+
+/// Execute a command, killing it if it runs longer than `timeout`.
+///
+/// On timeout the process is sent SIGTERM (on Unix) and, if it has not exited
+/// after a grace period, SIGKILL. The returned error downcasts to
+/// [`CommandTimeout`].
+pub async fn execute_command_with_timeout(
+    _session_id: &SessionId,
+    command: &str,
+    args: &[String],
+    workdir: Option<&str>,
+    env: &CommandEnv,
+    timeout: Option<Duration>,
+) -> Result<(i32, String, String)> {
+    let mut cmd = piped_command(command, args, workdir, env);
+
+    let timeout = match timeout {
+        Some(timeout) => timeout,
+        None => return Ok(into_result(cmd.output().await?)),
+    };
+
+    let mut child = cmd.spawn()?;
+    let stdout = tokio::spawn(read_all(child.stdout.take()));
+    let stderr = tokio::spawn(read_all(child.stderr.take()));
+
+    match tokio::time::timeout(timeout, child.wait()).await {
+        Ok(status) => {
+            let status = status?;
+            Ok(into_result(Output {
+                status,
+                stdout: stdout.await??,
+                stderr: stderr.await??,
+            }))
+        }
+        Err(_) => {
+            stdout.abort();
+            stderr.abort();
+            terminate(&mut child).await?;
+
+            Err(CommandTimeout {
+                command: command.to_string(),
+                timeout,
+            }
+            .into())
+        }
+    }
+}
+
+/// Execute a command, sending its output to `chunks` as
+/// [`Response::CommandOutputChunk`]s while it runs, and return the exit code.
+///
+/// Output is forwarded a line at a time. Timeouts behave as in
+/// [`execute_command_with_timeout`]. The command keeps running if the receiver
+/// is dropped.
+pub async fn execute_command_streaming(
+    session_id: &SessionId,
+    command: &str,
+    args: &[String],
+    workdir: Option<&str>,
+    env: &CommandEnv,
+    timeout: Option<Duration>,
+    chunks: &UnboundedSender<Response>,
+) -> Result<i32> {
+    let mut child = piped_command(command, args, workdir, env).spawn()?;
+    let stdout = tokio::spawn(forward_lines(
+        child.stdout.take(),
+        session_id.clone(),
+        OutputStream::Stdout,
+        chunks.clone(),
+    ));
+    let stderr = tokio::spawn(forward_lines(
+        child.stderr.take(),
+        session_id.clone(),
+        OutputStream::Stderr,
+        chunks.clone(),
+    ));
+
+    let status = match timeout {
+        Some(timeout) => match tokio::time::timeout(timeout, child.wait()).await {
+            Ok(status) => status?,
+            Err(_) => {
+                stdout.abort();
+                stderr.abort();
+                terminate(&mut child).await?;
+
+                return Err(CommandTimeout {
+                    command: command.to_string(),
+                    timeout,
+                }
+                .into());
+            }
+        },
+        None => child.wait().await?,
+    };
+    stdout.await??;
+    stderr.await??;
+
+    Ok(status.code().unwrap_or(-1))
+}
+
+/// Build a command with stdout and stderr piped back to the agent
+fn piped_command(
+    command: &str,
+    args: &[String],
+    workdir: Option<&str>,
+    env: &CommandEnv,
+) -> tokio::process::Command {
+    let mut cmd = tokio::process::Command::new(command);
+    cmd.args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    env.apply(cmd.as_std_mut());
+
+    if let Some(dir) = workdir {
+        cmd.current_dir(dir);
+    }
+
+    cmd
+}
+
+/// Send each line read from `pipe` as an output chunk
+async fn forward_lines<R: AsyncRead + Unpin>(
+    pipe: Option<R>,
+    session_id: SessionId,
+    stream: OutputStream,
+    chunks: UnboundedSender<Response>,
+) -> std::io::Result<()> {
+    let mut reader = match pipe {
+        Some(pipe) => BufReader::new(pipe),
+        None => return Ok(()),
+    };
+
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        if reader.read_until(b'\n', &mut line).await? == 0 {
+            return Ok(());
+        }
+        // A closed receiver only means nobody is listening any more
+        let _ = chunks.send(Response::CommandOutputChunk {
+            session_id: session_id.clone(),
+            stream,
+            data: String::from_utf8_lossy(&line).into_owned(),
+        });
+    }
+}
+
+async fn read_all<R: AsyncRead + Unpin>(pipe: Option<R>) -> std::io::Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    if let Some(mut pipe) = pipe {
+        pipe.read_to_end(&mut buf).await?;
+    }
+    Ok(buf)
+}
+
+/// Stop a child process: SIGTERM first on Unix, SIGKILL after the grace period
+async fn terminate(child: &mut Child) -> Result<()> {
+    #[cfg(unix)]
+    if let Some(pid) = child.id() {
+        // SAFETY: `kill` has no memory-safety preconditions; the pid belongs to
+        // our child, which has not been reaped yet.
+        unsafe {
+            libc::kill(pid as libc::pid_t, libc::SIGTERM);
+        }
+        if tokio::time::timeout(KILL_GRACE_PERIOD, child.wait())
+            .await
+            .is_ok()
+        {
+            return Ok(());
+        }
+    }
+
+    child.kill().await?;
+    Ok(())
+}
+
+/// Convert process output into `(exit_code, stdout, stderr)`
+fn into_result(output: Output) -> (i32, String, String) {
     let exit_code = output.status.code().unwrap_or(-1);
     let stdout = String::from_utf8_lossy(&output.stdout).to_string();
     let stderr = String::from_utf8_lossy(&output.stderr).to_string();
 
-    Ok((exit_code, stdout, stderr))
+    (exit_code, stdout, stderr)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_execute_command_async() {
+        let (exit_code, stdout, _) = execute_command_async(
+            &SessionId::new(),
+            "echo",
+            &["hello".to_string()],
+            None,
+            &CommandEnv::default(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(exit_code, 0);
+        assert!(stdout.contains("hello"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_execute_command_async_in_workdir() {
+        let dir = tempfile::tempdir().unwrap();
+        let workdir = dir.path().canonicalize().unwrap();
+
+        let (_, stdout, _) = execute_command_async(
+            &SessionId::new(),
+            "pwd",
+            &[],
+            Some(workdir.to_str().unwrap()),
+            &CommandEnv::default(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(stdout.trim(), workdir.to_str().unwrap());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_timeout_kills_command() {
+        let start = std::time::Instant::now();
+
+        let err = execute_command_with_timeout(
+            &SessionId::new(),
+            "sleep",
+            &["10".to_string()],
+            None,
+            &CommandEnv::default(),
+            Some(Duration::from_millis(100)),
+        )
+        .await
+        .unwrap_err();
+
+        assert!(start.elapsed() < Duration::from_secs(2));
+        let timeout = err.downcast_ref::<CommandTimeout>().unwrap();
+        assert_eq!(timeout.command, "sleep");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_command_within_timeout() {
+        let (exit_code, stdout, _) = execute_command_with_timeout(
+            &SessionId::new(),
+            "echo",
+            &["hello".to_string()],
+            None,
+            &CommandEnv::default(),
+            Some(Duration::from_secs(5)),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(exit_code, 0);
+        assert_eq!(stdout.trim(), "hello");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_env_vars_are_passed() {
+        let env = CommandEnv {
+            vars: vec![("FOO".to_string(), "bar".to_string())],
+            clear: false,
+        };
+
+        let (_, stdout, _) = execute_command_async(
+            &SessionId::new(),
+            "sh",
+            &["-c".to_string(), "echo $FOO".to_string()],
+            None,
+            &env,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(stdout.trim(), "bar");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_clear_env() {
+        // Set on the command rather than the test process, whose environment is
+        // shared with tests running in parallel
+        let run = |clear| {
+            let mut cmd = Command::new("/bin/sh");
+            cmd.args(["-c", "echo $FOO-$NEURAL_CONDUCTOR_TEST_INHERITED"])
+                .env("NEURAL_CONDUCTOR_TEST_INHERITED", "1");
+            CommandEnv {
+                vars: vec![("FOO".to_string(), "bar".to_string())],
+                clear,
+            }
+            .apply(&mut cmd);
+            into_result(cmd.output().unwrap()).1
+        };
+
+        assert_eq!(run(false).trim(), "bar-1");
+        assert_eq!(run(true).trim(), "bar-");
+    }
 }
@@ -12,18 +12,29 @@ pub mod executor;
 pub mod session_manager;
 
 pub use neural_conductor_shared::{
-    protocol::{Request, Response},
-    AgentInfo, SessionId, TaskStatus,
+    is_compatible_version,
+    protocol::{OutputStream, Request, Response},
+    AgentInfo, SessionId, TaskStatus, PROTOCOL_VERSION,
 };
 
 pub use anyhow::{anyhow, Result};
 
+use executor::CommandEnv;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::UnboundedSender;
+
 /// Agent version
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
+use session_manager::SessionManager;
+
 /// Agent runtime
 pub struct Agent {
     info: AgentInfo,
+    sessions: SessionManager,
+    started_at: Instant,
+    /// Protocol version of a peer whose `Hello` was refused
+    incompatible_peer: Option<String>,
 }
 
 impl Agent {
@@ -40,12 +51,192 @@ impl Agent {
                 platform: std::env::consts::OS.to_string(),
                 version: VERSION.to_string(),
             },
+            sessions: SessionManager::new(),
+            started_at: Instant::now(),
+            incompatible_peer: None,
         }
     }
 
     pub fn info(&self) -> &AgentInfo {
         &self.info
     }
+
+    /// Handle a request from the Conductor server
+    pub async fn handle(&mut self, req: Request) -> Response {
+        self.handle_streaming(req, None).await
+    }
+
+    /// Handle a request, sending the output of `ExecuteCommand { stream: true, .. }`
+    /// to `chunks` while the command runs. Without `chunks`, streamed commands
+    /// return their whole output in the final `CommandResult` instead.
+    pub async fn handle_streaming(
+        &mut self,
+        req: Request,
+        chunks: Option<&UnboundedSender<Response>>,
+    ) -> Response {
+        if let Some(version) = &self.incompatible_peer {
+            if !matches!(req, Request::Hello { .. }) {
+                return Response::Error {
+                    message: format!(
+                        "Protocol version {} is incompatible with agent protocol version {}",
+                        version, PROTOCOL_VERSION
+                    ),
+                };
+            }
+        }
+
+        match req {
+            Request::Hello { protocol_version } => {
+                let compatible = is_compatible_version(&protocol_version, PROTOCOL_VERSION);
+                self.incompatible_peer = (!compatible).then_some(protocol_version);
+                Response::HelloAck {
+                    protocol_version: PROTOCOL_VERSION.to_string(),
+                    agent_info: self.info.clone(),
+                    compatible,
+                }
+            }
+            Request::Ping => Response::Pong {
+                agent_info: self.info.clone(),
+            },
+            Request::Heartbeat => Response::HeartbeatAck {
+                uptime_secs: self.started_at.elapsed().as_secs(),
+                active_sessions: self.sessions.len(),
+            },
+            Request::CreateSession {
+                session_id,
+                workspace_path,
+            } => {
+                if self.sessions.get_session(&session_id).is_some() {
+                    return Response::Error {
+                        message: format!("Session already exists: {}", session_id.0),
+                    };
+                }
+                match self
+                    .sessions
+                    .create_session(session_id.clone(), workspace_path)
+                {
+                    Ok(()) => Response::SessionCreated { session_id },
+                    Err(e) => Response::Error {
+                        message: e.to_string(),
+                    },
+                }
+            }
+            Request::ExecuteCommand {
+                session_id,
+                command,
+                args,
+                workdir,
+                timeout_secs,
+                env,
+                clear_env,
+                stream,
+            } => {
+                // Commands run in the session's workspace unless told otherwise,
+                // and never outside it
+                let workdir = match self.sessions.get_session(&session_id) {
+                    Some(session) => session_manager::resolve_workdir(
+                        &session.workspace_path,
+                        workdir.as_deref(),
+                    ),
+                    None => return unknown_session(&session_id),
+                };
+                let workdir = match workdir {
+                    Ok(workdir) => workdir.to_string_lossy().to_string(),
+                    Err(e) => {
+                        return Response::Error {
+                            message: e.to_string(),
+                        }
+                    }
+                };
+
+                if let Some(session) = self.sessions.get_session_mut(&session_id) {
+                    session.mark_running();
+                }
+                let env = CommandEnv {
+                    vars: env,
+                    clear: clear_env,
+                };
+                let timeout = timeout_secs.map(Duration::from_secs);
+                let result = match chunks.filter(|_| stream) {
+                    Some(chunks) => executor::execute_command_streaming(
+                        &session_id,
+                        &command,
+                        &args,
+                        Some(&workdir),
+                        &env,
+                        timeout,
+                        chunks,
+                    )
+                    .await
+                    .map(|exit_code| (exit_code, String::new(), String::new())),
+                    None => {
+                        executor::execute_command_with_timeout(
+                            &session_id,
+                            &command,
+                            &args,
+                            Some(&workdir),
+                            &env,
+                            timeout,
+                        )
+                        .await
+                    }
+                };
+
+                match result {
+                    Ok((exit_code, stdout, stderr)) => {
+                        if let Some(session) = self.sessions.get_session_mut(&session_id) {
+                            if exit_code == 0 {
+                                session.mark_completed();
+                            } else {
+                                session.mark_failed();
+                            }
+                        }
+
+                        Response::CommandResult {
+                            session_id,
+                            exit_code,
+                            stdout,
+                            stderr,
+                        }
+                    }
+                    Err(e) => {
+                        if let Some(session) = self.sessions.get_session_mut(&session_id) {
+                            session.mark_failed();
+                        }
+                        Response::Error {
+                            message: format!("Failed to execute {}: {}", command, e),
+                        }
+                    }
+                }
+            }
+            Request::TerminateSession { session_id } => {
+                if self.sessions.get_session(&session_id).is_none() {
+                    return unknown_session(&session_id);
+                }
+                match self.sessions.terminate_session(&session_id) {
+                    Ok(_) => Response::SessionTerminated { session_id },
+                    Err(e) => Response::Error {
+                        message: e.to_string(),
+                    },
+                }
+            }
+            Request::GetSessionStatus { session_id } => {
+                match self.sessions.get_session(&session_id) {
+                    Some(session) => Response::SessionStatus {
+                        status: session.status.clone(),
+                        session_id,
+                    },
+                    None => unknown_session(&session_id),
+                }
+            }
+        }
+    }
+}
+
+fn unknown_session(session_id: &SessionId) -> Response {
+    Response::Error {
+        message: format!("Unknown session: {}", session_id.0),
+    }
 }
 
 impl Default for Agent {
@@ -62,3 +253,389 @@ mod hostname {
         Ok(OsString::from("localhost"))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn agent_with_session() -> (Agent, SessionId) {
+        let mut agent = Agent::new();
+        let session_id = SessionId::new();
+        agent
+            .handle(Request::CreateSession {
+                session_id: session_id.clone(),
+                workspace_path: std::env::temp_dir().to_string_lossy().to_string(),
+            })
+            .await;
+        (agent, session_id)
+    }
+
+    async fn status_of(agent: &mut Agent, session_id: &SessionId) -> Response {
+        agent
+            .handle(Request::GetSessionStatus {
+                session_id: session_id.clone(),
+            })
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_ping() {
+        let mut agent = Agent::new();
+
+        match agent.handle(Request::Ping).await {
+            Response::Pong { agent_info } => assert_eq!(agent_info.id, agent.info().id),
+            other => panic!("unexpected response: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hello_with_matching_version_is_compatible() {
+        let mut agent = Agent::new();
+
+        let response = agent
+            .handle(Request::Hello {
+                protocol_version: PROTOCOL_VERSION.to_string(),
+            })
+            .await;
+        match response {
+            Response::HelloAck {
+                protocol_version,
+                compatible,
+                ..
+            } => {
+                assert_eq!(protocol_version, PROTOCOL_VERSION);
+                assert!(compatible);
+            }
+            other => panic!("unexpected response: {:?}", other),
+        }
+        assert!(matches!(
+            agent.handle(Request::Ping).await,
+            Response::Pong { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_hello_with_other_major_version_is_refused() {
+        let mut agent = Agent::new();
+
+        let response = agent
+            .handle(Request::Hello {
+                protocol_version: "99.0.0".to_string(),
+            })
+            .await;
+        assert!(matches!(
+            response,
+            Response::HelloAck {
+                compatible: false,
+                ..
+            }
+        ));
+        match agent.handle(Request::Ping).await {
+            Response::Error { message } => {
+                assert!(message.contains("Protocol version 99.0.0 is incompatible"))
+            }
+            other => panic!("unexpected response: {:?}", other),
+        }
+
+        // A later compatible handshake is accepted
+        agent
+            .handle(Request::Hello {
+                protocol_version: PROTOCOL_VERSION.to_string(),
+            })
+            .await;
+        assert!(matches!(
+            agent.handle(Request::Ping).await,
+            Response::Pong { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_reports_active_sessions() {
+        let (mut agent, _) = agent_with_session().await;
+        let terminated = SessionId::new();
+        for session_id in [SessionId::new(), terminated.clone()] {
+            agent
+                .handle(Request::CreateSession {
+                    session_id,
+                    workspace_path: "/tmp".to_string(),
+                })
+                .await;
+        }
+        agent
+            .handle(Request::TerminateSession {
+                session_id: terminated,
+            })
+            .await;
+
+        match agent.handle(Request::Heartbeat).await {
+            Response::HeartbeatAck {
+                uptime_secs,
+                active_sessions,
+            } => {
+                assert_eq!(active_sessions, 2);
+                assert!(uptime_secs < 60);
+            }
+            other => panic!("unexpected response: {:?}", other),
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_streamed_output_arrives_while_command_runs() {
+        let (mut agent, session_id) = agent_with_session().await;
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+
+        let request = Request::ExecuteCommand {
+            session_id: session_id.clone(),
+            command: "sh".to_string(),
+            args: vec![
+                "-c".to_string(),
+                "echo first; sleep 1; echo oops >&2; echo second".to_string(),
+            ],
+            workdir: None,
+            timeout_secs: None,
+            env: vec![],
+            clear_env: false,
+            stream: true,
+        };
+        let running = tokio::spawn(async move {
+            let response = agent.handle_streaming(request, Some(&sender)).await;
+            (agent, response)
+        });
+
+        // The first line arrives long before the command exits
+        let first = tokio::time::timeout(Duration::from_millis(800), receiver.recv())
+            .await
+            .expect("no output while the command was running");
+        assert!(!running.is_finished());
+        match first {
+            Some(Response::CommandOutputChunk { stream, data, .. }) => {
+                assert_eq!((stream, data.as_str()), (OutputStream::Stdout, "first\n"));
+            }
+            other => panic!("unexpected chunk: {:?}", other),
+        }
+
+        let (_, response) = running.await.unwrap();
+        match response {
+            Response::CommandResult {
+                exit_code,
+                stdout,
+                stderr,
+                ..
+            } => {
+                assert_eq!(exit_code, 0);
+                assert!(stdout.is_empty() && stderr.is_empty());
+            }
+            other => panic!("unexpected response: {:?}", other),
+        }
+
+        let mut rest = Vec::new();
+        while let Ok(chunk) = receiver.try_recv() {
+            match chunk {
+                Response::CommandOutputChunk { stream, data, .. } => rest.push((stream, data)),
+                other => panic!("unexpected chunk: {:?}", other),
+            }
+        }
+        rest.sort_by_key(|(stream, _)| *stream == OutputStream::Stdout);
+        assert_eq!(
+            rest,
+            vec![
+                (OutputStream::Stderr, "oops\n".to_string()),
+                (OutputStream::Stdout, "second\n".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_session() {
+        let mut agent = Agent::new();
+        let session_id = SessionId::new();
+        let request = Request::CreateSession {
+            session_id: session_id.clone(),
+            workspace_path: "/tmp".to_string(),
+        };
+
+        match agent.handle(request.clone()).await {
+            Response::SessionCreated { session_id: id } => assert_eq!(id, session_id),
+            other => panic!("unexpected response: {:?}", other),
+        }
+        assert!(matches!(
+            agent.handle(request).await,
+            Response::Error { .. }
+        ));
+    }
+
+    fn pwd_in(session_id: &SessionId, workdir: &str) -> Request {
+        Request::ExecuteCommand {
+            session_id: session_id.clone(),
+            command: "pwd".to_string(),
+            args: vec![],
+            workdir: Some(workdir.to_string()),
+            timeout_secs: None,
+            env: vec![],
+            clear_env: false,
+            stream: false,
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_workdir_is_confined_to_workspace() {
+        let dir = tempfile::tempdir().unwrap();
+        let workspace = dir.path().join("workspace");
+        std::fs::create_dir_all(workspace.join("src")).unwrap();
+
+        let mut agent = Agent::new();
+        let session_id = SessionId::new();
+        agent
+            .handle(Request::CreateSession {
+                session_id: session_id.clone(),
+                workspace_path: workspace.to_string_lossy().to_string(),
+            })
+            .await;
+
+        match agent.handle(pwd_in(&session_id, "src")).await {
+            Response::CommandResult {
+                exit_code, stdout, ..
+            } => {
+                assert_eq!(exit_code, 0);
+                let expected = workspace.join("src").canonicalize().unwrap();
+                assert_eq!(stdout.trim(), expected.to_str().unwrap());
+            }
+            other => panic!("unexpected response: {:?}", other),
+        }
+
+        match agent.handle(pwd_in(&session_id, "../")).await {
+            Response::Error { message } => {
+                assert!(message.contains("outside the session workspace"))
+            }
+            other => panic!("unexpected response: {:?}", other),
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_execute_command() {
+        let (mut agent, session_id) = agent_with_session().await;
+
+        let response = agent
+            .handle(Request::ExecuteCommand {
+                session_id: session_id.clone(),
+                command: "echo".to_string(),
+                args: vec!["hello".to_string()],
+                workdir: None,
+                timeout_secs: None,
+                env: vec![],
+                clear_env: false,
+                stream: false,
+            })
+            .await;
+
+        match response {
+            Response::CommandResult {
+                exit_code, stdout, ..
+            } => {
+                assert_eq!(exit_code, 0);
+                assert_eq!(stdout.trim(), "hello");
+            }
+            other => panic!("unexpected response: {:?}", other),
+        }
+        assert!(matches!(
+            status_of(&mut agent, &session_id).await,
+            Response::SessionStatus {
+                status: TaskStatus::Completed,
+                ..
+            }
+        ));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_failed_command_marks_session_failed() {
+        let (mut agent, session_id) = agent_with_session().await;
+        // Timestamps have one-second resolution, so start from the past
+        agent
+            .sessions
+            .get_session_mut(&session_id)
+            .unwrap()
+            .updated_at = 0;
+
+        agent
+            .handle(Request::ExecuteCommand {
+                session_id: session_id.clone(),
+                command: "false".to_string(),
+                args: vec![],
+                workdir: None,
+                timeout_secs: None,
+                env: vec![],
+                clear_env: false,
+                stream: false,
+            })
+            .await;
+
+        let session = agent.sessions.get_session(&session_id).unwrap();
+        assert_eq!(session.status, TaskStatus::Failed);
+        assert!(session.updated_at > 0);
+    }
+
+    #[tokio::test]
+    async fn test_execute_command_in_unknown_session() {
+        let mut agent = Agent::new();
+
+        let response = agent
+            .handle(Request::ExecuteCommand {
+                session_id: SessionId::new(),
+                command: "echo".to_string(),
+                args: vec![],
+                workdir: None,
+                timeout_secs: None,
+                env: vec![],
+                clear_env: false,
+                stream: false,
+            })
+            .await;
+
+        assert!(matches!(response, Response::Error { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_get_session_status() {
+        let (mut agent, session_id) = agent_with_session().await;
+
+        match status_of(&mut agent, &session_id).await {
+            Response::SessionStatus {
+                session_id: id,
+                status,
+            } => {
+                assert_eq!(id, session_id);
+                assert_eq!(status, TaskStatus::Pending);
+            }
+            other => panic!("unexpected response: {:?}", other),
+        }
+        assert!(matches!(
+            status_of(&mut agent, &SessionId::new()).await,
+            Response::Error { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_terminate_session() {
+        let (mut agent, session_id) = agent_with_session().await;
+        let request = Request::TerminateSession {
+            session_id: session_id.clone(),
+        };
+
+        assert!(matches!(
+            agent.handle(request.clone()).await,
+            Response::SessionTerminated { .. }
+        ));
+        assert!(matches!(
+            agent.handle(request).await,
+            Response::Error { .. }
+        ));
+        assert!(matches!(
+            status_of(&mut agent, &session_id).await,
+            Response::Error { .. }
+        ));
+    }
+}
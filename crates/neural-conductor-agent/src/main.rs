@@ -55,6 +55,9 @@ fn print_agent_info() {
     println!("  copilot login    Authenticate with GitHub Copilot");
     println!("  copilot status   Show authentication status");
     println!("  copilot test     Test Copilot API connection");
+    println!("  copilot chat     Chat with a model interactively");
+    println!("  copilot models   List available models");
+    println!("  copilot usage    Show premium-request usage this month");
     println!("  copilot logout   Clear stored credentials");
     println!();
     println!("Part of Neural Garage 🧠🔧");
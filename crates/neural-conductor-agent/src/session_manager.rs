@@ -1,8 +1,10 @@
 //! Session management
 
 use crate::Result;
-use neural_conductor_shared::{session::Session, SessionId};
+use anyhow::Context;
+use neural_conductor_shared::{session::Session, SessionId, TaskStatus};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
 /// Manages active sessions
 pub struct SessionManager {
@@ -22,14 +24,63 @@ impl SessionManager {
         Ok(())
     }
 
-    pub fn terminate_session(&mut self, id: &SessionId) -> Result<()> {
-        self.sessions.remove(id);
-        Ok(())
+    /// Remove a session, returning it. A session that was still pending or
+    /// running is marked cancelled.
+    pub fn terminate_session(&mut self, id: &SessionId) -> Result<Session> {
+        let mut session = self
+            .sessions
+            .remove(id)
+            .with_context(|| format!("Unknown session: {}", id.0))?;
+        if matches!(session.status, TaskStatus::Pending | TaskStatus::Running) {
+            session.mark_cancelled();
+        }
+        Ok(session)
     }
 
     pub fn get_session(&self, id: &SessionId) -> Option<&Session> {
         self.sessions.get(id)
     }
+
+    /// Number of active sessions
+    pub fn len(&self) -> usize {
+        self.sessions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sessions.is_empty()
+    }
+
+    pub fn get_session_mut(&mut self, id: &SessionId) -> Option<&mut Session> {
+        self.sessions.get_mut(id)
+    }
+}
+
+/// Resolve a command's working directory inside a session workspace.
+///
+/// A relative `workdir` is taken from the workspace, and `None` means the
+/// workspace itself. Paths are canonicalized before they are compared, so `..`
+/// components and symlinks cannot lead outside the workspace.
+pub fn resolve_workdir(workspace: &str, workdir: Option<&str>) -> Result<PathBuf> {
+    let workspace = Path::new(workspace)
+        .canonicalize()
+        .with_context(|| format!("Invalid session workspace {}", workspace))?;
+    let requested = match workdir {
+        Some(dir) => workspace.join(dir),
+        None => workspace.clone(),
+    };
+    let resolved = requested
+        .canonicalize()
+        .with_context(|| format!("Invalid working directory {}", requested.display()))?;
+
+    if !resolved.starts_with(&workspace) {
+        return Err(anyhow::anyhow!(
+            "Working directory {} is outside the session workspace {}",
+            resolved.display(),
+            workspace.display()
+        ));
+    }
+
+    Ok(resolved)
 }
 
 impl Default for SessionManager {
@@ -37,3 +88,62 @@ impl Default for SessionManager {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_terminate_cancels_unfinished_sessions() {
+        let mut sessions = SessionManager::new();
+        let running = SessionId::new();
+        let completed = SessionId::new();
+        sessions
+            .create_session(running.clone(), "/tmp".to_string())
+            .unwrap();
+        sessions
+            .create_session(completed.clone(), "/tmp".to_string())
+            .unwrap();
+        sessions.get_session_mut(&running).unwrap().mark_running();
+        sessions
+            .get_session_mut(&completed)
+            .unwrap()
+            .mark_completed();
+        // Timestamps have one-second resolution, so start from the past
+        sessions.get_session_mut(&running).unwrap().updated_at = 0;
+
+        let session = sessions.terminate_session(&running).unwrap();
+        assert_eq!(session.status, TaskStatus::Cancelled);
+        assert!(session.updated_at > 0);
+
+        let session = sessions.terminate_session(&completed).unwrap();
+        assert_eq!(session.status, TaskStatus::Completed);
+
+        assert!(sessions.is_empty());
+        assert!(sessions.terminate_session(&running).is_err());
+    }
+
+    #[test]
+    fn test_resolve_workdir_stays_in_workspace() {
+        let dir = tempfile::tempdir().unwrap();
+        let workspace = dir.path().join("workspace");
+        fs::create_dir_all(workspace.join("src")).unwrap();
+        let workspace_str = workspace.to_str().unwrap();
+        let canonical = workspace.canonicalize().unwrap();
+
+        assert_eq!(resolve_workdir(workspace_str, None).unwrap(), canonical);
+        assert_eq!(
+            resolve_workdir(workspace_str, Some("src")).unwrap(),
+            canonical.join("src")
+        );
+        assert_eq!(
+            resolve_workdir(workspace_str, Some("src/..")).unwrap(),
+            canonical
+        );
+
+        assert!(resolve_workdir(workspace_str, Some("..")).is_err());
+        assert!(resolve_workdir(workspace_str, Some(dir.path().to_str().unwrap())).is_err());
+        assert!(resolve_workdir(workspace_str, Some("missing")).is_err());
+    }
+}
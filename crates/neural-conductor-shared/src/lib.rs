@@ -16,6 +16,19 @@ pub use anyhow::{anyhow, Result};
 /// Version of the protocol
 pub const PROTOCOL_VERSION: &str = "0.1.0";
 
+/// Whether peers speaking protocol versions `a` and `b` can talk to each other:
+/// their major versions must match. Unparseable versions are never compatible.
+pub fn is_compatible_version(a: &str, b: &str) -> bool {
+    fn major(version: &str) -> Option<u64> {
+        version.split('.').next()?.trim().parse().ok()
+    }
+
+    match (major(a), major(b)) {
+        (Some(a), Some(b)) => a == b,
+        _ => false,
+    }
+}
+
 /// Agent identification
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AgentInfo {
@@ -31,14 +44,7 @@ pub struct SessionId(pub String);
 
 impl SessionId {
     pub fn new() -> Self {
-        // Simple UUID-like generation for now
-        Self(format!(
-            "session-{}",
-            std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_millis()
-        ))
+        Self(format!("session-{}", uuid::Uuid::new_v4()))
     }
 }
 
@@ -57,3 +63,25 @@ pub enum TaskStatus {
     Failed,
     Cancelled,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_session_ids_are_unique() {
+        let ids: HashSet<SessionId> = (0..10_000).map(|_| SessionId::new()).collect();
+
+        assert_eq!(ids.len(), 10_000);
+        assert!(ids.iter().all(|id| id.0.starts_with("session-")));
+    }
+
+    #[test]
+    fn test_is_compatible_version() {
+        assert!(is_compatible_version("0.1.0", "0.4.2"));
+        assert!(is_compatible_version("1.0.0", "1.2"));
+        assert!(!is_compatible_version("1.0.0", "2.0.0"));
+        assert!(!is_compatible_version("1.0.0", "latest"));
+    }
+}
@@ -6,15 +6,34 @@ use serde::{Deserialize, Serialize};
 /// Request from server to agent
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Request {
+    /// Handshake sent when connecting, before any other request
+    Hello { protocol_version: String },
+
     /// Ping to check agent health
     Ping,
 
+    /// Periodic liveness check; agents that stop acknowledging can be pruned
+    Heartbeat,
+
     /// Execute a command in a session
     ExecuteCommand {
         session_id: SessionId,
         command: String,
         args: Vec<String>,
         workdir: Option<String>,
+        /// Kill the command if it runs longer than this many seconds
+        #[serde(default)]
+        timeout_secs: Option<u64>,
+        /// Environment variables to set for the command
+        #[serde(default)]
+        env: Vec<(String, String)>,
+        /// Start from an empty environment instead of inheriting the agent's
+        #[serde(default)]
+        clear_env: bool,
+        /// Send output as `CommandOutputChunk`s while the command runs; the
+        /// final `CommandResult` then carries only the exit code
+        #[serde(default)]
+        stream: bool,
     },
 
     /// Create a new session
@@ -33,9 +52,32 @@ pub enum Request {
 /// Response from agent to server
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Response {
+    /// Handshake reply. An incompatible agent refuses every later request.
+    HelloAck {
+        protocol_version: String,
+        agent_info: super::AgentInfo,
+        compatible: bool,
+    },
+
     /// Pong response
     Pong { agent_info: super::AgentInfo },
 
+    /// Heartbeat acknowledged
+    HeartbeatAck {
+        /// Seconds since the agent started
+        uptime_secs: u64,
+        /// Sessions created and not yet terminated
+        active_sessions: usize,
+    },
+
+    /// Output of a streamed command, sent as the command produces it (one
+    /// line, or the trailing partial line, per chunk)
+    CommandOutputChunk {
+        session_id: SessionId,
+        stream: OutputStream,
+        data: String,
+    },
+
     /// Command execution result
     CommandResult {
         session_id: SessionId,
@@ -59,3 +101,10 @@ pub enum Response {
     /// Error response
     Error { message: String },
 }
+
+/// Output stream of a command
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+}
@@ -15,10 +15,7 @@ pub struct Session {
 
 impl Session {
     pub fn new(id: SessionId, workspace_path: String) -> Self {
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
+        let now = now();
 
         Self {
             id,
@@ -28,4 +25,70 @@ impl Session {
             updated_at: now,
         }
     }
+
+    /// A command started running
+    pub fn mark_running(&mut self) {
+        self.set_status(TaskStatus::Running);
+    }
+
+    /// The last command exited successfully
+    pub fn mark_completed(&mut self) {
+        self.set_status(TaskStatus::Completed);
+    }
+
+    /// The last command failed, could not be started, or timed out
+    pub fn mark_failed(&mut self) {
+        self.set_status(TaskStatus::Failed);
+    }
+
+    /// The work was cancelled before it finished
+    pub fn mark_cancelled(&mut self) {
+        self.set_status(TaskStatus::Cancelled);
+    }
+
+    fn set_status(&mut self, status: TaskStatus) {
+        self.status = status;
+        self.updated_at = now();
+    }
+}
+
+/// Seconds since the Unix epoch
+fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_transitions_refresh_updated_at() {
+        let mut session = Session::new(SessionId::new(), "/tmp".to_string());
+        assert_eq!(session.status, TaskStatus::Pending);
+        assert_eq!(session.updated_at, session.created_at);
+
+        // Timestamps have one-second resolution, so start from the past
+        session.updated_at = 0;
+        session.mark_running();
+        assert_eq!(session.status, TaskStatus::Running);
+        assert!(session.updated_at > 0);
+
+        session.updated_at = 0;
+        session.mark_completed();
+        assert_eq!(session.status, TaskStatus::Completed);
+        assert!(session.updated_at > 0);
+
+        session.updated_at = 0;
+        session.mark_failed();
+        assert_eq!(session.status, TaskStatus::Failed);
+        assert!(session.updated_at > 0);
+
+        session.updated_at = 0;
+        session.mark_cancelled();
+        assert_eq!(session.status, TaskStatus::Cancelled);
+        assert!(session.updated_at > 0);
+    }
 }
@@ -0,0 +1,36 @@
+//! Errors returned by parsing and scanning
+//!
+//! Reporters still return `anyhow::Result`; [`BuryError`] converts into
+//! `anyhow::Error` with `?` for binaries that don't need to match on it.
+
+use std::path::PathBuf;
+
+/// Result type for parsing and scanning
+pub type Result<T, E = BuryError> = std::result::Result<T, E>;
+
+/// Why a file could not be parsed or a directory could not be scanned
+#[derive(Debug, thiserror::Error)]
+pub enum BuryError {
+    /// The file has no extension, or one no parser handles
+    #[error("Unsupported file extension: {}", path.display())]
+    UnsupportedExtension { path: PathBuf },
+
+    /// tree-sitter produced no syntax tree (e.g. parsing was cancelled)
+    #[error("Failed to parse {}", path.display())]
+    ParseFailed { path: PathBuf },
+
+    /// The grammar is incompatible with the linked tree-sitter version
+    #[error("Failed to load grammar: {0}")]
+    Grammar(#[from] tree_sitter::LanguageError),
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    /// A directory could not be walked
+    #[error(transparent)]
+    Walk(#[from] ignore::Error),
+
+    /// User-provided settings are invalid, such as a malformed exclude glob
+    #[error("Invalid configuration: {0}")]
+    ConfigInvalid(String),
+}
@@ -5,6 +5,7 @@
 //! - File scanning with .gitignore support
 //! - Report generation (JSON, Markdown, Terminal)
 
+pub mod error;
 pub mod parser;
 pub mod report;
 pub mod scanner;
@@ -12,6 +13,10 @@ pub mod scanner;
 pub use anyhow::{anyhow, Result};
 
 /// Re-export common types
-pub use parser::{Language, ParsedFile, Parser, Symbol, SymbolKind};
-pub use report::{Finding, JsonReporter, MarkdownReporter, Reporter};
+pub use error::BuryError;
+pub use parser::{Language, MethodKind, ParsedFile, Parser, Symbol, SymbolKind};
+pub use report::{
+    AnalysisStats, CsvReporter, Finding, GithubActionsReporter, HtmlReporter, JsonLinesReporter,
+    JsonReporter, LspReporter, MarkdownReporter, Reporter, SarifReporter,
+};
 pub use scanner::Scanner;
@@ -0,0 +1,523 @@
+//! Go parser using tree-sitter
+
+use super::pool::{self, Grammar};
+use super::{collect_parse_errors, Location, ParsedFile, Parser, Symbol, SymbolKind};
+use crate::error::{BuryError, Result};
+use std::path::Path;
+use tree_sitter::{Node, Tree};
+
+/// Prefixes of functions the `go test` runner calls, e.g. `TestParse`
+const TEST_FUNCTION_PREFIXES: &[&str] = &["Test", "Benchmark", "Example", "Fuzz"];
+
+/// Go parser.
+///
+/// Functions and methods are definitions; a method belongs to its receiver type,
+/// so it is only live when that type is used. Exported (capitalized) functions,
+/// methods and types, `main`, `init`, and test functions are entry points.
+pub struct GoParser {
+    _private: (),
+}
+
+impl GoParser {
+    pub fn new() -> Result<Self> {
+        // Load the grammar now so a broken grammar fails here, not on first parse
+        pool::with_parser(Grammar::Go, |_| ())?;
+
+        Ok(Self { _private: () })
+    }
+
+    fn extract_definitions(&self, tree: &Tree, source: &str, file_path: &str) -> Vec<Symbol> {
+        let mut definitions = Vec::new();
+        let root = tree.root_node();
+
+        // Functions and methods can only be declared at the top level
+        let mut cursor = root.walk();
+        for node in root.children(&mut cursor) {
+            let kind = match node.kind() {
+                "function_declaration" => SymbolKind::Function,
+                "method_declaration" => match self.receiver_type(node, source) {
+                    Some(class_name) => SymbolKind::Method { class_name },
+                    None => continue,
+                },
+                _ => continue,
+            };
+
+            if let Some(name_node) = node.child_by_field_name("name") {
+                let name = name_node
+                    .utf8_text(source.as_bytes())
+                    .unwrap_or("")
+                    .to_string();
+                if !name.is_empty() {
+                    let pos = name_node.start_position();
+                    definitions.push(Symbol::new(
+                        name,
+                        kind,
+                        Location {
+                            file: file_path.to_string(),
+                            line: pos.row + 1,
+                            column: pos.column,
+                            end_line: node.end_position().row + 1,
+                            end_column: node.end_position().column,
+                        },
+                    ));
+                }
+            }
+        }
+
+        definitions
+    }
+
+    /// Name of a method's receiver type: `Server` for `func (s *Server) Start()`
+    fn receiver_type(&self, node: Node, source: &str) -> Option<String> {
+        let receiver = node.child_by_field_name("receiver")?;
+        let parameter = receiver.named_child(0)?;
+        let mut ty = parameter.child_by_field_name("type")?;
+
+        loop {
+            ty = match ty.kind() {
+                "pointer_type" => ty.named_child(0)?,
+                // `func (l *List[T]) Push(v T)`
+                "generic_type" => ty.child_by_field_name("type")?,
+                "type_identifier" => break,
+                _ => return None,
+            };
+        }
+
+        ty.utf8_text(source.as_bytes()).ok().map(str::to_string)
+    }
+
+    fn extract_usages(&self, tree: &Tree, source: &str, file_path: &str) -> Vec<Symbol> {
+        let mut usages = Vec::new();
+        let root = tree.root_node();
+
+        self.traverse_for_usages(root, source, file_path, &mut usages, None);
+
+        usages
+    }
+
+    /// Collect calls and references, attributing each to the enclosing function
+    /// or method
+    fn traverse_for_usages(
+        &self,
+        node: Node,
+        source: &str,
+        file_path: &str,
+        usages: &mut Vec<Symbol>,
+        scope: Option<&str>,
+    ) {
+        match node.kind() {
+            "call_expression" => {
+                if let Some(func_node) = node.child_by_field_name("function") {
+                    let name = self.extract_call_name(func_node, source);
+                    if !name.is_empty() {
+                        let pos = func_node.start_position();
+                        usages.push(
+                            Symbol::new(
+                                name,
+                                SymbolKind::Function,
+                                Location {
+                                    file: file_path.to_string(),
+                                    line: pos.row + 1,
+                                    column: pos.column,
+                                    end_line: func_node.end_position().row + 1,
+                                    end_column: func_node.end_position().column,
+                                },
+                            )
+                            .with_scope(scope.map(str::to_string))
+                            .with_receiver(self.extract_receiver(func_node, source)),
+                        );
+                    }
+                }
+            }
+            // A method value, e.g. `http.HandleFunc("/", s.handle)`
+            "selector_expression" if !self.is_callee(node) => {
+                if let Some(field) = node.child_by_field_name("field") {
+                    let name = field.utf8_text(source.as_bytes()).unwrap_or("").to_string();
+                    let pos = field.start_position();
+                    usages.push(
+                        Symbol::new(
+                            name,
+                            SymbolKind::Variable,
+                            Location {
+                                file: file_path.to_string(),
+                                line: pos.row + 1,
+                                column: pos.column,
+                                end_line: field.end_position().row + 1,
+                                end_column: field.end_position().column,
+                            },
+                        )
+                        .with_scope(scope.map(str::to_string))
+                        .with_receiver(self.extract_receiver(node, source)),
+                    );
+                }
+            }
+            "identifier" | "type_identifier" if !self.is_binding_or_call(node) => {
+                // Any other reference to a name, e.g. a function passed as a value
+                // or a type in a composite literal
+                let name = node.utf8_text(source.as_bytes()).unwrap_or("").to_string();
+                let pos = node.start_position();
+                usages.push(
+                    Symbol::new(
+                        name,
+                        SymbolKind::Variable,
+                        Location {
+                            file: file_path.to_string(),
+                            line: pos.row + 1,
+                            column: pos.column,
+                            end_line: node.end_position().row + 1,
+                            end_column: node.end_position().column,
+                        },
+                    )
+                    .with_scope(scope.map(str::to_string)),
+                );
+            }
+            // Imported package names are not definitions, and the Go compiler
+            // already rejects unused imports
+            "import_declaration" | "package_clause" => return,
+            _ => {}
+        }
+
+        let child_scope = self.defined_name(node, source).or(scope);
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            // The receiver names the method's own type, which is not a use of it
+            if node.kind() == "method_declaration"
+                && node.child_by_field_name("receiver") == Some(child)
+            {
+                continue;
+            }
+            self.traverse_for_usages(child, source, file_path, usages, child_scope);
+        }
+    }
+
+    /// Whether a node is the callee of a call expression
+    fn is_callee(&self, node: Node) -> bool {
+        node.parent()
+            .filter(|p| p.kind() == "call_expression")
+            .and_then(|p| p.child_by_field_name("function"))
+            == Some(node)
+    }
+
+    /// Whether an identifier is the name of a declaration or the callee of a
+    /// call (already recorded as a usage)
+    fn is_binding_or_call(&self, node: Node) -> bool {
+        let parent = match node.parent() {
+            Some(parent) => parent,
+            None => return false,
+        };
+        let field = match parent.kind() {
+            "function_declaration"
+            | "parameter_declaration"
+            | "variable_parameter_declaration"
+            | "var_spec"
+            | "const_spec"
+            | "type_spec" => "name",
+            "call_expression" => "function",
+            _ => return false,
+        };
+        parent.child_by_field_name(field) == Some(node)
+    }
+
+    /// Name of the function or method defined by this node
+    fn defined_name<'a>(&self, node: Node, source: &'a str) -> Option<&'a str> {
+        match node.kind() {
+            "function_declaration" | "method_declaration" => node
+                .child_by_field_name("name")?
+                .utf8_text(source.as_bytes())
+                .ok()
+                .filter(|n| !n.is_empty()),
+            _ => None,
+        }
+    }
+
+    /// The receiver of an `obj.Method()` call, when it is a plain name
+    fn extract_receiver(&self, node: Node, source: &str) -> Option<String> {
+        if node.kind() != "selector_expression" {
+            return None;
+        }
+        let operand = node.child_by_field_name("operand")?;
+        match operand.kind() {
+            "identifier" => operand
+                .utf8_text(source.as_bytes())
+                .ok()
+                .map(str::to_string),
+            _ => None,
+        }
+    }
+
+    fn extract_call_name(&self, node: Node, source: &str) -> String {
+        match node.kind() {
+            "identifier" => node.utf8_text(source.as_bytes()).unwrap_or("").to_string(),
+            // For pkg.Func() and obj.Method() calls, extract the function name
+            "selector_expression" => node
+                .child_by_field_name("field")
+                .and_then(|field| field.utf8_text(source.as_bytes()).ok())
+                .unwrap_or("")
+                .to_string(),
+            _ => String::new(),
+        }
+    }
+
+    fn extract_entry_points(&self, tree: &Tree, source: &str) -> Vec<String> {
+        let mut entry_points = Vec::new();
+        let root = tree.root_node();
+
+        let mut cursor = root.walk();
+        for node in root.children(&mut cursor) {
+            match node.kind() {
+                "function_declaration" | "method_declaration" => {
+                    let name = node
+                        .child_by_field_name("name")
+                        .and_then(|n| n.utf8_text(source.as_bytes()).ok())
+                        .unwrap_or("");
+                    let is_entry = is_exported(name)
+                        || (node.kind() == "function_declaration"
+                            && (matches!(name, "main" | "init") || is_test_function(name)));
+                    if is_entry {
+                        entry_points.push(name.to_string());
+                    }
+                }
+                // Exported types keep their methods alive
+                "type_declaration" => {
+                    let mut specs = node.walk();
+                    for spec in node.named_children(&mut specs) {
+                        let name = spec
+                            .child_by_field_name("name")
+                            .and_then(|n| n.utf8_text(source.as_bytes()).ok())
+                            .unwrap_or("");
+                        if is_exported(name) {
+                            entry_points.push(name.to_string());
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        entry_points
+    }
+}
+
+/// Go exports identifiers that start with an upper-case letter
+fn is_exported(name: &str) -> bool {
+    name.starts_with(char::is_uppercase)
+}
+
+/// Whether `go test` runs this function: `TestXxx`, `BenchmarkXxx`, `ExampleXxx`
+/// or `FuzzXxx`, where the suffix does not start with a lower-case letter
+fn is_test_function(name: &str) -> bool {
+    TEST_FUNCTION_PREFIXES.iter().any(|prefix| {
+        name.strip_prefix(prefix)
+            .is_some_and(|rest| !rest.starts_with(char::is_lowercase))
+    })
+}
+
+impl Parser for GoParser {
+    fn parse(&self, source: &str, file_path: &Path) -> Result<ParsedFile> {
+        let tree = pool::with_parser(Grammar::Go, |parser| parser.parse(source, None))?
+            .ok_or_else(|| BuryError::ParseFailed {
+                path: file_path.to_path_buf(),
+            })?;
+
+        let file_path_str = file_path.to_string_lossy().to_string();
+
+        let definitions = self.extract_definitions(&tree, source, &file_path_str);
+        let usages = self.extract_usages(&tree, source, &file_path_str);
+        let entry_points = self.extract_entry_points(&tree, source);
+
+        let parse_errors = collect_parse_errors(&tree, &file_path_str);
+
+        Ok(ParsedFile {
+            path: file_path_str,
+            definitions,
+            usages,
+            entry_points,
+            imports: Vec::new(),
+            parse_errors,
+            unreachable: Vec::new(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(source: &str) -> ParsedFile {
+        GoParser::new()
+            .unwrap()
+            .parse(source, Path::new("main.go"))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_parse_simple_function() {
+        let parsed = parse(
+            r#"
+package main
+
+func hello() {
+	println("Hello, world!")
+}
+"#,
+        );
+
+        assert!(parsed.parse_errors.is_empty());
+        assert_eq!(parsed.definitions.len(), 1);
+        assert_eq!(parsed.definitions[0].name, "hello");
+        assert_eq!(parsed.definitions[0].kind, SymbolKind::Function);
+    }
+
+    #[test]
+    fn test_parse_methods_with_receiver_type() {
+        let parsed = parse(
+            r#"
+package store
+
+type cache struct{}
+
+func (c *cache) get(key string) string {
+	return key
+}
+
+func (c cache) size() int {
+	return 0
+}
+
+type List[T any] struct{}
+
+func (l *List[T]) Push(v T) {}
+"#,
+        );
+
+        let definitions: Vec<(&str, &SymbolKind)> = parsed
+            .definitions
+            .iter()
+            .map(|d| (d.name.as_str(), &d.kind))
+            .collect();
+        let method_of = |class_name: &str| SymbolKind::Method {
+            class_name: class_name.to_string(),
+        };
+        assert_eq!(
+            definitions,
+            vec![
+                ("get", &method_of("cache")),
+                ("size", &method_of("cache")),
+                ("Push", &method_of("List")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_function_calls() {
+        let parsed = parse(
+            r#"
+package main
+
+import "fmt"
+
+func foo() int {
+	return 42
+}
+
+func bar() {
+	foo()
+	fmt.Println("test")
+}
+"#,
+        );
+
+        assert_eq!(parsed.definitions.len(), 2);
+        let calls: Vec<(&str, Option<&str>, Option<&str>)> = parsed
+            .usages
+            .iter()
+            .filter(|u| u.kind == SymbolKind::Function)
+            .map(|u| (u.name.as_str(), u.scope.as_deref(), u.receiver.as_deref()))
+            .collect();
+        assert_eq!(
+            calls,
+            vec![
+                ("foo", Some("bar"), None),
+                ("Println", Some("bar"), Some("fmt"))
+            ]
+        );
+        assert!(parsed.imports.is_empty());
+    }
+
+    #[test]
+    fn test_function_and_method_values_are_usages() {
+        let parsed = parse(
+            r#"
+package main
+
+func (s *server) routes() {
+	http.HandleFunc("/", s.index)
+	http.HandleFunc("/health", health)
+}
+"#,
+        );
+
+        let usages: Vec<(&str, Option<&str>)> = parsed
+            .usages
+            .iter()
+            .filter(|u| u.kind == SymbolKind::Variable)
+            .map(|u| (u.name.as_str(), u.receiver.as_deref()))
+            .collect();
+        assert!(usages.contains(&("index", Some("s"))));
+        assert!(usages.contains(&("health", None)));
+        // The receiver type is not a use of the type
+        assert!(!usages.contains(&("server", None)));
+    }
+
+    #[test]
+    fn test_entry_points() {
+        let parsed = parse(
+            r#"
+package main
+
+type Server struct{}
+type config struct{}
+
+func main() {}
+func init() {}
+func Serve() {}
+func (s *Server) Start() {}
+func helper() {}
+func TestServe(t *testing.T) {}
+func BenchmarkServe(b *testing.B) {}
+"#,
+        );
+
+        assert_eq!(
+            parsed.entry_points,
+            vec![
+                "Server",
+                "main",
+                "init",
+                "Serve",
+                "Start",
+                "TestServe",
+                "BenchmarkServe"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_definition_span_covers_body() {
+        let parsed = parse(
+            r#"
+package main
+
+func multi() int {
+	x := 1
+	return x
+}
+"#,
+        );
+
+        let location = &parsed.definitions[0].location;
+        assert_eq!(location.line, 4);
+        assert_eq!(location.end_line, 7);
+    }
+}
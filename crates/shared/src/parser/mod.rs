@@ -1,53 +1,108 @@
 //! Language detection and AST parsing using tree-sitter
 
-use crate::Result;
-use anyhow::anyhow;
+use crate::error::{BuryError, Result};
 use std::path::Path;
 
+mod go;
+mod pool;
 mod python;
+mod rust;
 mod typescript;
 
-pub use python::PythonParser;
+pub use go::GoParser;
+pub use pool::{with_parser, Grammar};
+pub use python::{PythonParser, DEFAULT_ENTRY_POINT_DECORATORS};
+pub use rust::RustParser;
 pub use typescript::TypeScriptParser;
 
 /// Supported languages
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Language {
     Python,
     TypeScript,
     JavaScript,
+    Go,
+    Rust,
 }
 
 impl Language {
     /// Detect language from file extension
     pub fn from_path(path: &Path) -> Result<Self> {
-        let ext = path
-            .extension()
-            .and_then(|e| e.to_str())
-            .ok_or_else(|| anyhow!("No file extension found"))?;
-
-        match ext {
-            "py" => Ok(Language::Python),
-            "ts" | "tsx" => Ok(Language::TypeScript),
-            "js" | "jsx" => Ok(Language::JavaScript),
-            _ => Err(anyhow!("Unsupported file extension: {}", ext)),
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("py") => Ok(Language::Python),
+            Some("ts" | "tsx") => Ok(Language::TypeScript),
+            Some("js" | "jsx" | "mjs" | "cjs") => Ok(Language::JavaScript),
+            Some("go") => Ok(Language::Go),
+            Some("rs") => Ok(Language::Rust),
+            _ => Err(BuryError::UnsupportedExtension {
+                path: path.to_path_buf(),
+            }),
+        }
+    }
+
+    /// Lowercase language name, as used in reports
+    pub fn name(&self) -> &'static str {
+        match self {
+            Language::Python => "python",
+            Language::TypeScript => "typescript",
+            Language::JavaScript => "javascript",
+            Language::Go => "go",
+            Language::Rust => "rust",
+        }
+    }
+
+    /// Get the tree-sitter grammar used to parse this language
+    ///
+    /// TypeScript maps to the plain TypeScript grammar, which rejects JSX; use
+    /// [`Grammar::for_path`] to get the TSX grammar for `.tsx` files.
+    pub fn tree_sitter_language(&self) -> tree_sitter::Language {
+        match self {
+            Language::Python => tree_sitter_python::language(),
+            Language::TypeScript => tree_sitter_typescript::language_typescript(),
+            Language::JavaScript => tree_sitter_javascript::language(),
+            Language::Go => tree_sitter_go::language(),
+            Language::Rust => tree_sitter_rust::language(),
         }
     }
 }
 
 /// Parser trait for language-specific parsing
+///
+/// `parse` takes `&self`: implementations borrow a per-thread tree-sitter parser
+/// from the parser pool, so the grammar is loaded once per thread and reused for
+/// every file.
 pub trait Parser {
     /// Parse source code and extract symbols
     fn parse(&self, source: &str, file_path: &Path) -> Result<ParsedFile>;
 }
 
 /// Parsed file containing symbols
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct ParsedFile {
     pub path: String,
     pub definitions: Vec<Symbol>,
     pub usages: Vec<Symbol>,
     pub entry_points: Vec<String>,
+    /// Names bound by import statements, located at the bound identifier
+    pub imports: Vec<Symbol>,
+    /// Locations tree-sitter could not parse (ERROR or missing nodes). Symbols
+    /// are still extracted from the rest of the file, but may be incomplete.
+    pub parse_errors: Vec<Location>,
+    /// Statements that can never run because they follow a `return` (or
+    /// similar) in the same block
+    #[serde(default)]
+    pub unreachable: Vec<UnreachableCode>,
+}
+
+/// A run of statements after a statement that leaves the block
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct UnreachableCode {
+    /// Keyword of the statement that leaves the block, e.g. `return`
+    pub after: String,
+    /// First line of the unreachable code
+    pub code: String,
+    /// Span from the start of the first to the end of the last unreachable statement
+    pub location: Location,
 }
 
 /// Symbol represents a function, class, method, or variable
@@ -56,14 +111,60 @@ pub struct Symbol {
     pub name: String,
     pub kind: SymbolKind,
     pub location: Location,
+    /// For usages, the name of the innermost enclosing function or method.
+    /// `None` means the usage sits at module level (or directly in a class body).
+    /// For Python definitions, the function a nested function is defined in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    /// For method call usages, the receiver when it is a plain name, e.g. `self`
+    /// in `self.save()`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub receiver: Option<String>,
+    /// For definitions, whether the function or method is declared `async`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_async: bool,
+    /// For method definitions, how the method is bound to its class
+    #[serde(default, skip_serializing_if = "MethodKind::is_instance")]
+    pub method_kind: MethodKind,
+    /// For definitions and imports, whether an ignore comment suppresses
+    /// findings for the symbol
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub suppressed: bool,
+}
+
+/// How a method is accessed, from decorators such as `@property`
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize,
+)]
+pub enum MethodKind {
+    /// Called on an instance: `obj.method()`
+    #[default]
+    Instance,
+    /// Read as an attribute without parentheses: `obj.value`
+    Property,
+    /// `@staticmethod`, called through the class or an instance
+    Static,
+    /// `@classmethod`, called through the class or an instance
+    Class,
+}
+
+impl MethodKind {
+    pub fn is_instance(&self) -> bool {
+        *self == MethodKind::Instance
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub enum SymbolKind {
     Function,
     Class,
-    Method { class_name: String },
+    Method {
+        class_name: String,
+    },
     Variable,
+    Import,
+    /// Code inside a function body rather than a named symbol
+    Statement,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
@@ -71,6 +172,12 @@ pub struct Location {
     pub file: String,
     pub line: usize,
     pub column: usize,
+    /// Last line of the symbol's node (the whole definition, not just its name)
+    #[serde(default)]
+    pub end_line: usize,
+    /// Column where the symbol's node ends on `end_line`
+    #[serde(default)]
+    pub end_column: usize,
 }
 
 impl Symbol {
@@ -79,6 +186,218 @@ impl Symbol {
             name,
             kind,
             location,
+            scope: None,
+            receiver: None,
+            is_async: false,
+            method_kind: MethodKind::Instance,
+            suppressed: false,
         }
     }
+
+    /// Set the enclosing function or method of a usage
+    pub fn with_scope(mut self, scope: Option<String>) -> Self {
+        self.scope = scope;
+        self
+    }
+
+    /// Set the receiver of a method call usage
+    pub fn with_receiver(mut self, receiver: Option<String>) -> Self {
+        self.receiver = receiver;
+        self
+    }
+
+    /// Mark a definition as `async`
+    pub fn with_async(mut self, is_async: bool) -> Self {
+        self.is_async = is_async;
+        self
+    }
+
+    /// Set how a method definition is bound to its class
+    pub fn with_method_kind(mut self, method_kind: MethodKind) -> Self {
+        self.method_kind = method_kind;
+        self
+    }
+}
+
+/// Comment that suppresses findings for the definition it is on or above
+pub(crate) struct IgnoreMarker {
+    /// Line comment prefix, e.g. `#`
+    pub comment: &'static str,
+    /// Text the comment must contain, e.g. `bury: ignore`
+    pub marker: &'static str,
+}
+
+impl IgnoreMarker {
+    fn is_on(&self, line: &str) -> bool {
+        line.find(self.comment)
+            .is_some_and(|start| line[start + self.comment.len()..].contains(self.marker))
+    }
+}
+
+/// Mark symbols as suppressed when an ignore comment is on their line, or among
+/// the comment and decorator lines directly above it
+pub(crate) fn mark_suppressed(symbols: &mut [Symbol], source: &str, ignore: &IgnoreMarker) {
+    let lines: Vec<&str> = source.lines().collect();
+
+    for symbol in symbols {
+        let line = symbol.location.line;
+        if line == 0 || line > lines.len() {
+            continue;
+        }
+        if ignore.is_on(lines[line - 1]) {
+            symbol.suppressed = true;
+            continue;
+        }
+        for above in lines[..line - 1].iter().rev().map(|l| l.trim_start()) {
+            if !above.starts_with(ignore.comment) && !above.starts_with('@') {
+                break;
+            }
+            if ignore.is_on(above) {
+                symbol.suppressed = true;
+                break;
+            }
+        }
+    }
+}
+
+/// Statement node kinds of a language, for finding code that can never run
+pub(crate) struct ControlFlow {
+    /// Nodes whose named children run in sequence
+    pub blocks: &'static [&'static str],
+    /// Statements after which the rest of the block does not run
+    pub exits: &'static [&'static str],
+    /// Named children that are not run in sequence (comments, hoisted
+    /// declarations), so they are never unreachable
+    pub skipped: &'static [&'static str],
+}
+
+/// Find statements that follow an exit (`return`, `raise`, ...) in the same block
+pub(crate) fn collect_unreachable(
+    tree: &tree_sitter::Tree,
+    source: &str,
+    file_path: &str,
+    flow: &ControlFlow,
+) -> Vec<UnreachableCode> {
+    let mut unreachable = Vec::new();
+    collect_unreachable_nodes(tree.root_node(), source, file_path, flow, &mut unreachable);
+    unreachable
+}
+
+fn collect_unreachable_nodes(
+    node: tree_sitter::Node,
+    source: &str,
+    file_path: &str,
+    flow: &ControlFlow,
+    unreachable: &mut Vec<UnreachableCode>,
+) {
+    if flow.blocks.contains(&node.kind()) {
+        let mut cursor = node.walk();
+        let statements: Vec<_> = node.named_children(&mut cursor).collect();
+        let exit = statements
+            .iter()
+            .position(|statement| flow.exits.contains(&statement.kind()));
+        if let Some(exit) = exit {
+            let after: Vec<_> = statements[exit + 1..]
+                .iter()
+                .filter(|statement| !flow.skipped.contains(&statement.kind()))
+                .collect();
+            if let (Some(first), Some(last)) = (after.first(), after.last()) {
+                let keyword = statements[exit].child(0).map(|k| k.kind()).unwrap_or("");
+                let code = first
+                    .utf8_text(source.as_bytes())
+                    .unwrap_or("")
+                    .lines()
+                    .next()
+                    .unwrap_or("")
+                    .trim()
+                    .to_string();
+                let start = first.start_position();
+                let end = last.end_position();
+                unreachable.push(UnreachableCode {
+                    after: keyword.to_string(),
+                    code,
+                    location: Location {
+                        file: file_path.to_string(),
+                        line: start.row + 1,
+                        column: start.column,
+                        end_line: end.row + 1,
+                        end_column: end.column,
+                    },
+                });
+            }
+        }
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_unreachable_nodes(child, source, file_path, flow, unreachable);
+    }
+}
+
+/// Collect the locations of syntax errors in a parsed tree
+pub(crate) fn collect_parse_errors(tree: &tree_sitter::Tree, file_path: &str) -> Vec<Location> {
+    let mut errors = Vec::new();
+    if tree.root_node().has_error() {
+        collect_error_nodes(tree.root_node(), file_path, &mut errors);
+    }
+    errors
+}
+
+fn collect_error_nodes(node: tree_sitter::Node, file_path: &str, errors: &mut Vec<Location>) {
+    if node.is_error() || node.is_missing() {
+        let start = node.start_position();
+        let end = node.end_position();
+        errors.push(Location {
+            file: file_path.to_string(),
+            line: start.row + 1,
+            column: start.column,
+            end_line: end.row + 1,
+            end_column: end.column,
+        });
+        return;
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if child.has_error() {
+            collect_error_nodes(child, file_path, errors);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_path_rejects_unsupported_extensions() {
+        assert_eq!(
+            Language::from_path(Path::new("app.mjs")).unwrap(),
+            Language::JavaScript
+        );
+
+        for path in ["notes.txt", "Makefile"] {
+            match Language::from_path(Path::new(path)) {
+                Err(BuryError::UnsupportedExtension { path: rejected }) => {
+                    assert_eq!(rejected, Path::new(path))
+                }
+                other => panic!(
+                    "expected UnsupportedExtension for {}, got {:?}",
+                    path, other
+                ),
+            }
+        }
+    }
+
+    #[test]
+    fn test_javascript_grammar_parses_jsx() {
+        let mut parser = tree_sitter::Parser::new();
+        parser
+            .set_language(Language::JavaScript.tree_sitter_language())
+            .unwrap();
+        let tree = parser
+            .parse("const a = ok ? <b>yes</b> : <i>no</i>;", None)
+            .unwrap();
+        assert!(!tree.root_node().has_error());
+    }
 }
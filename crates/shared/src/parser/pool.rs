@@ -0,0 +1,138 @@
+//! Per-thread tree-sitter parsers
+//!
+//! `tree_sitter::Parser` is not `Sync`, and loading a grammar into a new parser
+//! for every file is wasteful. Each thread instead keeps one parser per language,
+//! created on first use, so language parsers can be shared across threads (e.g.
+//! on a rayon pool) while no tree-sitter parser ever leaves its thread.
+
+use super::Language;
+use crate::error::Result;
+use std::cell::RefCell;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::path::Path;
+use tree_sitter::Parser as TSParser;
+
+/// Grammar a pooled parser is loaded with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Grammar {
+    Python,
+    TypeScript,
+    /// TypeScript with JSX, for `.tsx` files
+    Tsx,
+    /// Plain JavaScript (with JSX), for `.js`, `.jsx`, `.mjs` and `.cjs` files
+    JavaScript,
+    Go,
+    Rust,
+}
+
+impl Grammar {
+    /// Pick the grammar for a file from its extension
+    ///
+    /// Unlike [`Language::tree_sitter_language`], this tells `.tsx` apart from
+    /// `.ts`: JSX only parses with the TSX grammar.
+    pub fn for_path(path: &Path) -> Result<Self> {
+        Ok(match Language::from_path(path)? {
+            Language::Python => Grammar::Python,
+            Language::TypeScript if path.extension().is_some_and(|e| e == "tsx") => Grammar::Tsx,
+            Language::TypeScript => Grammar::TypeScript,
+            Language::JavaScript => Grammar::JavaScript,
+            Language::Go => Grammar::Go,
+            Language::Rust => Grammar::Rust,
+        })
+    }
+
+    fn language(self) -> tree_sitter::Language {
+        match self {
+            Grammar::Python => tree_sitter_python::language(),
+            Grammar::TypeScript => tree_sitter_typescript::language_typescript(),
+            Grammar::Tsx => tree_sitter_typescript::language_tsx(),
+            Grammar::JavaScript => tree_sitter_javascript::language(),
+            Grammar::Go => tree_sitter_go::language(),
+            Grammar::Rust => tree_sitter_rust::language(),
+        }
+    }
+}
+
+thread_local! {
+    static PARSERS: RefCell<HashMap<Grammar, TSParser>> = RefCell::new(HashMap::new());
+}
+
+/// Run `f` with this thread's parser for `grammar`
+pub fn with_parser<R>(grammar: Grammar, f: impl FnOnce(&mut TSParser) -> R) -> Result<R> {
+    PARSERS.with(|parsers| {
+        let mut parsers = parsers.borrow_mut();
+        let parser = match parsers.entry(grammar) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let mut parser = TSParser::new();
+                parser.set_language(grammar.language())?;
+                entry.insert(parser)
+            }
+        };
+        Ok(f(parser))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Parser, PythonParser, TypeScriptParser};
+    use super::Grammar;
+    use rayon::prelude::*;
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn test_grammar_for_path() {
+        let grammar = |path: &str| Grammar::for_path(Path::new(path)).unwrap();
+        assert_eq!(grammar("app.ts"), Grammar::TypeScript);
+        assert_eq!(grammar("App.tsx"), Grammar::Tsx);
+        assert_eq!(grammar("app.js"), Grammar::JavaScript);
+        assert_eq!(grammar("App.jsx"), Grammar::JavaScript);
+        assert_eq!(grammar("main.py"), Grammar::Python);
+        assert!(Grammar::for_path(Path::new("notes.txt")).is_err());
+    }
+
+    fn sources() -> Vec<(PathBuf, String)> {
+        (0..1000)
+            .map(|i| {
+                if i % 2 == 0 {
+                    (
+                        PathBuf::from(format!("module_{}.py", i)),
+                        format!(
+                            "def handler_{i}():\n    helper_{i}()\n\ndef helper_{i}():\n    pass\n"
+                        ),
+                    )
+                } else {
+                    (
+                        PathBuf::from(format!("module_{}.ts", i)),
+                        format!("export function handler_{i}() {{\n  helper_{i}();\n}}\nfunction helper_{i}() {{}}\n"),
+                    )
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parallel_parse_matches_serial() {
+        let python = PythonParser::new().unwrap();
+        let typescript = TypeScriptParser::new().unwrap();
+        let parse = |(path, source): &(PathBuf, String)| {
+            if path.extension().is_some_and(|e| e == "py") {
+                python.parse(source, path).unwrap()
+            } else {
+                typescript.parse(source, path).unwrap()
+            }
+        };
+        let sources = sources();
+
+        let serial: Vec<_> = sources.iter().map(parse).collect();
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(4)
+            .build()
+            .unwrap();
+        let parallel: Vec<_> = pool.install(|| sources.par_iter().map(parse).collect());
+
+        assert_eq!(parallel.len(), 1000);
+        assert_eq!(parallel, serial);
+    }
+}
@@ -1,22 +1,132 @@
 //! Python parser using tree-sitter
 
-use super::{Location, ParsedFile, Parser, Symbol, SymbolKind};
-use crate::Result;
+use super::pool::{self, Grammar};
+use super::{
+    collect_parse_errors, collect_unreachable, mark_suppressed, ControlFlow, IgnoreMarker,
+    Location, MethodKind, ParsedFile, Parser, Symbol, SymbolKind,
+};
+use crate::error::{BuryError, Result};
 use std::path::Path;
-use tree_sitter::{Node, Parser as TSParser, Tree};
+use tree_sitter::{Node, Tree};
 
-pub struct PythonParser;
+/// Decorators that expose the decorated function to a framework by default.
+///
+/// A pattern matches a decorator's dotted name exactly, or with a leading `*.`
+/// matches any receiver (`*.route` matches `app.route` and `bp.route`). Call
+/// arguments are ignored, so `@app.route("/")` is matched as `app.route`.
+pub const DEFAULT_ENTRY_POINT_DECORATORS: &[&str] = &[
+    "*.route",
+    "*.get",
+    "*.post",
+    "*.put",
+    "*.patch",
+    "*.delete",
+    "*.command",
+    "*.group",
+    "*.task",
+    "*.fixture",
+    "shared_task",
+    "fixture",
+];
+
+/// Statements after `return`, `raise`, `continue` or `break` never run
+const CONTROL_FLOW: ControlFlow = ControlFlow {
+    blocks: &["module", "block"],
+    exits: &[
+        "return_statement",
+        "raise_statement",
+        "continue_statement",
+        "break_statement",
+    ],
+    skipped: &["comment"],
+};
+
+/// `# bury: ignore` on or above a definition suppresses its findings
+const IGNORE: IgnoreMarker = IgnoreMarker {
+    comment: "#",
+    marker: "bury: ignore",
+};
+
+/// Python parser.
+///
+/// Parses with the current thread's tree-sitter parser from the parser pool, so a
+/// `PythonParser` can be shared across threads.
+pub struct PythonParser {
+    entry_point_decorators: Vec<String>,
+}
 
 impl PythonParser {
     pub fn new() -> Result<Self> {
-        Ok(Self)
+        // Load the grammar now so a broken grammar fails here, not on first parse
+        pool::with_parser(Grammar::Python, |_| ())?;
+
+        Ok(Self {
+            entry_point_decorators: DEFAULT_ENTRY_POINT_DECORATORS
+                .iter()
+                .map(|d| d.to_string())
+                .collect(),
+        })
+    }
+
+    /// Add decorator patterns that mark the decorated function or class as an
+    /// entry point (see [`DEFAULT_ENTRY_POINT_DECORATORS`] for the syntax)
+    pub fn with_entry_point_decorators(mut self, patterns: Vec<String>) -> Self {
+        self.entry_point_decorators.extend(patterns);
+        self
+    }
+
+    fn is_entry_point_decorator(&self, name: &str) -> bool {
+        self.entry_point_decorators
+            .iter()
+            .any(|pattern| match pattern.strip_prefix('*') {
+                Some(suffix) => name.ends_with(suffix),
+                None => name == pattern,
+            })
+    }
+
+    /// Dotted name of a decorator, without call arguments
+    fn decorator_name(&self, decorator: Node, source: &str) -> Option<String> {
+        let mut expr = decorator.named_child(0)?;
+        if expr.kind() == "call" {
+            expr = expr.child_by_field_name("function")?;
+        }
+
+        let text = expr.utf8_text(source.as_bytes()).ok()?;
+        Some(text.split_whitespace().collect())
+    }
+
+    /// Binding of a method from its decorators (`@property`, `@staticmethod`, ...)
+    fn method_kind(&self, function: Node, source: &str) -> MethodKind {
+        let decorated = match function.parent() {
+            Some(parent) if parent.kind() == "decorated_definition" => parent,
+            _ => return MethodKind::Instance,
+        };
+
+        let mut cursor = decorated.walk();
+        let kind = decorated
+            .children(&mut cursor)
+            .filter(|child| child.kind() == "decorator")
+            .filter_map(|decorator| self.decorator_name(decorator, source))
+            .find_map(|name| match name.as_str() {
+                "property" | "functools.cached_property" | "cached_property" => {
+                    Some(MethodKind::Property)
+                }
+                // `@value.setter` and `@value.deleter` belong to the `value` property
+                _ if name.ends_with(".setter") || name.ends_with(".deleter") => {
+                    Some(MethodKind::Property)
+                }
+                "staticmethod" => Some(MethodKind::Static),
+                "classmethod" => Some(MethodKind::Class),
+                _ => None,
+            });
+        kind.unwrap_or(MethodKind::Instance)
     }
 
     fn extract_definitions(&self, tree: &Tree, source: &str, file_path: &str) -> Vec<Symbol> {
         let mut definitions = Vec::new();
         let root = tree.root_node();
 
-        self.traverse_for_definitions(root, source, file_path, &mut definitions, None);
+        self.traverse_for_definitions(root, source, file_path, &mut definitions, None, None);
 
         definitions
     }
@@ -28,6 +138,7 @@ impl PythonParser {
         file_path: &str,
         definitions: &mut Vec<Symbol>,
         current_class: Option<String>,
+        current_function: Option<&str>,
     ) {
         let kind = node.kind();
 
@@ -49,15 +160,44 @@ impl PythonParser {
                         SymbolKind::Function
                     };
 
-                    definitions.push(Symbol::new(
-                        name,
-                        symbol_kind,
-                        Location {
-                            file: file_path.to_string(),
-                            line: pos.row + 1,
-                            column: pos.column,
-                        },
-                    ));
+                    // `async def` starts with an `async` keyword token
+                    let is_async = node.child(0).is_some_and(|child| child.kind() == "async");
+                    let method_kind = match current_class {
+                        Some(_) => self.method_kind(node, source),
+                        None => MethodKind::Instance,
+                    };
+
+                    definitions.push(
+                        Symbol::new(
+                            name.clone(),
+                            symbol_kind,
+                            Location {
+                                file: file_path.to_string(),
+                                line: pos.row + 1,
+                                column: pos.column,
+                                end_line: node.end_position().row + 1,
+                                end_column: node.end_position().column,
+                            },
+                        )
+                        .with_scope(current_function.map(str::to_string))
+                        .with_async(is_async)
+                        .with_method_kind(method_kind),
+                    );
+
+                    // Functions defined in the body are closures of this one,
+                    // not methods of the enclosing class
+                    let mut cursor = node.walk();
+                    for child in node.children(&mut cursor) {
+                        self.traverse_for_definitions(
+                            child,
+                            source,
+                            file_path,
+                            definitions,
+                            None,
+                            Some(&name),
+                        );
+                    }
+                    return;
                 }
             }
             "class_definition" => {
@@ -76,6 +216,8 @@ impl PythonParser {
                             file: file_path.to_string(),
                             line: pos.row + 1,
                             column: pos.column,
+                            end_line: node.end_position().row + 1,
+                            end_column: node.end_position().column,
                         },
                     ));
 
@@ -88,6 +230,7 @@ impl PythonParser {
                             file_path,
                             definitions,
                             Some(name.clone()),
+                            None,
                         );
                     }
                     return; // Don't traverse children again below
@@ -105,6 +248,7 @@ impl PythonParser {
                 file_path,
                 definitions,
                 current_class.clone(),
+                current_function,
             );
         }
     }
@@ -113,17 +257,19 @@ impl PythonParser {
         let mut usages = Vec::new();
         let root = tree.root_node();
 
-        self.traverse_for_usages(root, source, file_path, &mut usages);
+        self.traverse_for_usages(root, source, file_path, &mut usages, None);
 
         usages
     }
 
+    /// Collect calls, attributing each to the nearest enclosing `function_definition`
     fn traverse_for_usages(
         &self,
         node: Node,
         source: &str,
         file_path: &str,
         usages: &mut Vec<Symbol>,
+        scope: Option<&str>,
     ) {
         let kind = node.kind();
 
@@ -134,29 +280,219 @@ impl PythonParser {
                     let name = self.extract_call_name(func_node, source);
                     if !name.is_empty() {
                         let pos = func_node.start_position();
-                        usages.push(Symbol::new(
+                        usages.push(
+                            Symbol::new(
+                                name,
+                                SymbolKind::Function, // We don't know if it's a function or method yet
+                                Location {
+                                    file: file_path.to_string(),
+                                    line: pos.row + 1,
+                                    column: pos.column,
+                                    end_line: func_node.end_position().row + 1,
+                                    end_column: func_node.end_position().column,
+                                },
+                            )
+                            .with_scope(scope.map(str::to_string))
+                            .with_receiver(self.extract_receiver(func_node, source)),
+                        );
+                    }
+                }
+            }
+            // An attribute read or assignment, e.g. a property or a module
+            // constant. `obj.method()` is already recorded by its call.
+            "attribute" if !self.is_callee(node) => {
+                if let Some(attr_node) = node.child_by_field_name("attribute") {
+                    let name = attr_node
+                        .utf8_text(source.as_bytes())
+                        .unwrap_or("")
+                        .to_string();
+                    let pos = attr_node.start_position();
+                    usages.push(
+                        Symbol::new(
+                            name,
+                            SymbolKind::Variable,
+                            Location {
+                                file: file_path.to_string(),
+                                line: pos.row + 1,
+                                column: pos.column,
+                                end_line: attr_node.end_position().row + 1,
+                                end_column: attr_node.end_position().column,
+                            },
+                        )
+                        .with_scope(scope.map(str::to_string))
+                        .with_receiver(self.extract_receiver(node, source)),
+                    );
+                }
+            }
+            "identifier" if !self.is_binding_or_call(node) => {
+                // Any other reference to a name, e.g. a function passed as a callback
+                // or a module used as `os.path`
+                let name = node.utf8_text(source.as_bytes()).unwrap_or("").to_string();
+                let pos = node.start_position();
+                usages.push(
+                    Symbol::new(
+                        name,
+                        SymbolKind::Variable,
+                        Location {
+                            file: file_path.to_string(),
+                            line: pos.row + 1,
+                            column: pos.column,
+                            end_line: node.end_position().row + 1,
+                            end_column: node.end_position().column,
+                        },
+                    )
+                    .with_scope(scope.map(str::to_string)),
+                );
+            }
+            // Names in import statements are bindings, not usages
+            "import_statement" | "import_from_statement" | "future_import_statement" => return,
+            _ => {}
+        }
+
+        let child_scope = if kind == "function_definition" {
+            node.child_by_field_name("name")
+                .and_then(|n| n.utf8_text(source.as_bytes()).ok())
+                .or(scope)
+        } else {
+            scope
+        };
+
+        // Traverse children
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            self.traverse_for_usages(child, source, file_path, usages, child_scope);
+        }
+    }
+
+    /// Whether an identifier is the name of a definition, the callee of a call,
+    /// or the attribute name in `obj.attr` (each already recorded as a usage), or
+    /// binds a name: a parameter, a keyword argument name or an assignment or
+    /// loop target
+    fn is_binding_or_call(&self, node: Node) -> bool {
+        let parent = match node.parent() {
+            Some(parent) => parent,
+            None => return false,
+        };
+        let field = match parent.kind() {
+            // `def f(a, *args, b: int)`: type annotations are nested in a `type`
+            "parameters"
+            | "lambda_parameters"
+            | "typed_parameter"
+            | "list_splat_pattern"
+            | "dictionary_splat_pattern" => return true,
+            "function_definition" | "class_definition" => "name",
+            "default_parameter" | "typed_default_parameter" | "keyword_argument" => "name",
+            "assignment" | "for_statement" | "for_in_clause" => "left",
+            "call" => "function",
+            "attribute" => "attribute",
+            _ => return false,
+        };
+        parent.child_by_field_name(field) == Some(node)
+    }
+
+    /// Whether a node is the function being called in a `call`
+    fn is_callee(&self, node: Node) -> bool {
+        node.parent().is_some_and(|call| {
+            call.kind() == "call" && call.child_by_field_name("function") == Some(node)
+        })
+    }
+
+    fn extract_imports(&self, tree: &Tree, source: &str, file_path: &str) -> Vec<Symbol> {
+        let mut imports = Vec::new();
+        let root = tree.root_node();
+
+        self.traverse_for_imports(root, source, file_path, &mut imports);
+
+        imports
+    }
+
+    fn traverse_for_imports(
+        &self,
+        node: Node,
+        source: &str,
+        file_path: &str,
+        imports: &mut Vec<Symbol>,
+    ) {
+        match node.kind() {
+            "import_statement" | "import_from_statement" => {
+                let mut cursor = node.walk();
+                for name_node in node.children_by_field_name("name", &mut cursor) {
+                    let binding = match name_node.kind() {
+                        // `import a as b` / `from x import a as b` binds `b`
+                        "aliased_import" => name_node.child_by_field_name("alias"),
+                        // `import os.path` binds `os`
+                        _ if node.kind() == "import_statement" => name_node.named_child(0),
+                        _ => Some(name_node),
+                    };
+
+                    if let Some(binding) = binding {
+                        let name = binding
+                            .utf8_text(source.as_bytes())
+                            .unwrap_or("")
+                            .to_string();
+                        let pos = binding.start_position();
+                        imports.push(Symbol::new(
                             name,
-                            SymbolKind::Function, // We don't know if it's a function or method yet
+                            SymbolKind::Import,
                             Location {
                                 file: file_path.to_string(),
                                 line: pos.row + 1,
                                 column: pos.column,
+                                end_line: binding.end_position().row + 1,
+                                end_column: binding.end_position().column,
                             },
                         ));
                     }
                 }
+                return;
             }
-            "identifier" => {
-                // Track variable usages (for future enhancement)
-                // For now, we focus on function calls
+            _ => {}
+        }
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            self.traverse_for_imports(child, source, file_path, imports);
+        }
+    }
+
+    /// Collect the original names imported by `from ... import` statements
+    /// outside functions: `from .models import User as Account` re-exports
+    /// `User`. Star imports name nothing and are ignored.
+    fn extract_reexports(&self, node: Node, source: &str, entry_points: &mut Vec<String>) {
+        match node.kind() {
+            "import_from_statement" => {
+                let mut cursor = node.walk();
+                for name_node in node.children_by_field_name("name", &mut cursor) {
+                    let imported = match name_node.kind() {
+                        "aliased_import" => name_node.child_by_field_name("name"),
+                        _ => Some(name_node),
+                    };
+                    let name = imported.and_then(|n| n.utf8_text(source.as_bytes()).ok());
+                    if let Some(name) = name {
+                        entry_points.push(name.to_string());
+                    }
+                }
+                return;
             }
+            "function_definition" => return,
             _ => {}
         }
 
-        // Traverse children
         let mut cursor = node.walk();
         for child in node.children(&mut cursor) {
-            self.traverse_for_usages(child, source, file_path, usages);
+            self.extract_reexports(child, source, entry_points);
+        }
+    }
+
+    /// The receiver of an `obj.method()` call, when it is a plain name
+    fn extract_receiver(&self, node: Node, source: &str) -> Option<String> {
+        if node.kind() != "attribute" {
+            return None;
+        }
+        let object = node.child_by_field_name("object")?;
+        match object.kind() {
+            "identifier" => object.utf8_text(source.as_bytes()).ok().map(str::to_string),
+            _ => None,
         }
     }
 
@@ -183,10 +519,53 @@ impl PythonParser {
         let root = tree.root_node();
 
         self.traverse_for_entry_points(root, source, &mut entry_points);
+        self.extract_exported_names(root, source, &mut entry_points);
 
         entry_points
     }
 
+    /// Collect the names listed in top-level `__all__` assignments (`=` and `+=`)
+    fn extract_exported_names(&self, root: Node, source: &str, entry_points: &mut Vec<String>) {
+        let mut cursor = root.walk();
+        for statement in root.children(&mut cursor) {
+            if statement.kind() != "expression_statement" {
+                continue;
+            }
+
+            let assignment = match statement.named_child(0) {
+                Some(node) if matches!(node.kind(), "assignment" | "augmented_assignment") => node,
+                _ => continue,
+            };
+
+            let target = assignment
+                .child_by_field_name("left")
+                .and_then(|left| left.utf8_text(source.as_bytes()).ok());
+            if target != Some("__all__") {
+                continue;
+            }
+
+            let value = match assignment.child_by_field_name("right") {
+                Some(node) if matches!(node.kind(), "list" | "tuple") => node,
+                _ => continue,
+            };
+
+            let mut value_cursor = value.walk();
+            for element in value.named_children(&mut value_cursor) {
+                if element.kind() != "string" {
+                    continue;
+                }
+                let mut string_cursor = element.walk();
+                let content = element
+                    .named_children(&mut string_cursor)
+                    .find(|child| child.kind() == "string_content")
+                    .and_then(|content| content.utf8_text(source.as_bytes()).ok());
+                if let Some(name) = content {
+                    entry_points.push(name.to_string());
+                }
+            }
+        }
+    }
+
     fn traverse_for_entry_points(&self, node: Node, source: &str, entry_points: &mut Vec<String>) {
         let kind = node.kind();
 
@@ -204,6 +583,26 @@ impl PythonParser {
             }
         }
 
+        // Functions registered with a framework through a decorator
+        if kind == "decorated_definition" {
+            let mut cursor = node.walk();
+            let registered = node
+                .children(&mut cursor)
+                .filter(|child| child.kind() == "decorator")
+                .filter_map(|decorator| self.decorator_name(decorator, source))
+                .any(|name| self.is_entry_point_decorator(&name));
+
+            if registered {
+                let name = node
+                    .child_by_field_name("definition")
+                    .and_then(|definition| definition.child_by_field_name("name"))
+                    .and_then(|name| name.utf8_text(source.as_bytes()).ok());
+                if let Some(name) = name {
+                    entry_points.push(name.to_string());
+                }
+            }
+        }
+
         // Also detect functions that start with "test_" as entry points (pytest convention)
         if kind == "function_definition" {
             if let Some(name_node) = node.child_by_field_name("name") {
@@ -243,26 +642,39 @@ impl PythonParser {
 
 impl Parser for PythonParser {
     fn parse(&self, source: &str, file_path: &Path) -> Result<ParsedFile> {
-        // Parser needs to be mutable, so we need to use interior mutability
-        // For now, we'll create a new parser each time (not ideal but works for MVP)
-        let mut parser = TSParser::new();
-        parser.set_language(tree_sitter_python::language())?;
-
-        let tree = parser
-            .parse(source, None)
-            .ok_or_else(|| anyhow::anyhow!("Failed to parse Python file"))?;
+        let tree = pool::with_parser(Grammar::Python, |parser| parser.parse(source, None))?
+            .ok_or_else(|| BuryError::ParseFailed {
+                path: file_path.to_path_buf(),
+            })?;
 
         let file_path_str = file_path.to_string_lossy().to_string();
 
-        let definitions = self.extract_definitions(&tree, source, &file_path_str);
+        let mut definitions = self.extract_definitions(&tree, source, &file_path_str);
         let usages = self.extract_usages(&tree, source, &file_path_str);
-        let entry_points = self.extract_entry_points(&tree, source);
+        let mut entry_points = self.extract_entry_points(&tree, source);
+
+        // Imports in a package's `__init__.py` are usually re-exports: the
+        // imported names are the package's public API
+        let mut imports = if file_path.file_name().is_some_and(|n| n == "__init__.py") {
+            self.extract_reexports(tree.root_node(), source, &mut entry_points);
+            Vec::new()
+        } else {
+            self.extract_imports(&tree, source, &file_path_str)
+        };
+        mark_suppressed(&mut definitions, source, &IGNORE);
+        mark_suppressed(&mut imports, source, &IGNORE);
+
+        let parse_errors = collect_parse_errors(&tree, &file_path_str);
+        let unreachable = collect_unreachable(&tree, source, &file_path_str, &CONTROL_FLOW);
 
         Ok(ParsedFile {
             path: file_path_str,
             definitions,
             usages,
             entry_points,
+            imports,
+            parse_errors,
+            unreachable,
         })
     }
 }
@@ -323,4 +735,457 @@ def bar():
         assert_eq!(parsed.definitions.len(), 2); // foo, bar
         assert!(!parsed.usages.is_empty()); // At least foo() call
     }
+
+    #[test]
+    fn test_usages_carry_enclosing_function() {
+        let parser = PythonParser::new().unwrap();
+        let source = r#"
+class Service:
+    def run(self):
+        def check():
+            validate()
+        check()
+
+setup()
+"#;
+        let parsed = parser.parse(source, Path::new("test.py")).unwrap();
+        let scope_of = |name: &str| {
+            parsed
+                .usages
+                .iter()
+                .find(|u| u.name == name)
+                .unwrap()
+                .scope
+                .clone()
+        };
+
+        assert_eq!(scope_of("validate").as_deref(), Some("check"));
+        assert_eq!(scope_of("check").as_deref(), Some("run"));
+        assert_eq!(scope_of("setup"), None);
+    }
+
+    #[test]
+    fn test_nested_functions_record_enclosing_function() {
+        let parser = PythonParser::new().unwrap();
+        let source = r#"
+def outer():
+    def inner():
+        def innermost():
+            pass
+
+class Service:
+    def run(self):
+        def check():
+            pass
+"#;
+        let parsed = parser.parse(source, Path::new("test.py")).unwrap();
+        let definition = |name: &str| parsed.definitions.iter().find(|d| d.name == name).unwrap();
+
+        assert_eq!(definition("outer").scope, None);
+        assert_eq!(definition("inner").scope.as_deref(), Some("outer"));
+        assert_eq!(definition("innermost").scope.as_deref(), Some("inner"));
+        assert_eq!(definition("run").scope, None);
+        // A function nested in a method is a closure, not a method
+        assert_eq!(definition("check").kind, SymbolKind::Function);
+        assert_eq!(definition("check").scope.as_deref(), Some("run"));
+    }
+
+    #[test]
+    fn test_init_reexports_are_entry_points() {
+        let parser = PythonParser::new().unwrap();
+        let source = r#"
+from .models import User, Group as Team
+from .utils import *
+import json
+
+def factory():
+    from .internal import helper
+"#;
+
+        let parsed = parser.parse(source, Path::new("pkg/__init__.py")).unwrap();
+        assert_eq!(parsed.entry_points, vec!["User", "Group"]);
+        assert!(parsed.imports.is_empty());
+
+        // Outside `__init__.py`, the same imports are ordinary bindings
+        let parsed = parser.parse(source, Path::new("pkg/app.py")).unwrap();
+        assert!(parsed.entry_points.is_empty());
+    }
+
+    #[test]
+    fn test_extract_imports() {
+        let parser = PythonParser::new().unwrap();
+        let source = r#"
+import os
+import os.path
+import numpy as np
+from typing import List, Dict as D
+from json import *
+"#;
+        let parsed = parser.parse(source, Path::new("test.py")).unwrap();
+        let names: Vec<&str> = parsed.imports.iter().map(|i| i.name.as_str()).collect();
+
+        assert_eq!(names, vec!["os", "os", "np", "List", "D"]);
+        assert!(parsed.imports.iter().all(|i| i.kind == SymbolKind::Import));
+        assert_eq!(parsed.imports[2].location.line, 4);
+    }
+
+    #[test]
+    fn test_parser_is_reused_across_files() {
+        let parser = PythonParser::new().unwrap();
+
+        let first = parser
+            .parse("def first():\n    pass\n", Path::new("a.py"))
+            .unwrap();
+        let second = parser
+            .parse("def second():\n    pass\n", Path::new("b.py"))
+            .unwrap();
+
+        assert_eq!(first.definitions[0].name, "first");
+        assert_eq!(second.definitions[0].name, "second");
+        assert_eq!(second.path, "b.py");
+    }
+
+    #[test]
+    fn test_definition_span_covers_body() {
+        let parser = PythonParser::new().unwrap();
+        let source = r#"
+def multi():
+    x = 1
+    return x
+"#;
+        let parsed = parser.parse(source, Path::new("test.py")).unwrap();
+
+        let location = &parsed.definitions[0].location;
+        assert_eq!(location.line, 2);
+        assert_eq!(location.end_line, 4);
+        assert!(location.end_line > location.line);
+    }
+
+    #[test]
+    fn test_framework_decorators_are_entry_points() {
+        let parser = PythonParser::new().unwrap();
+        let source = r#"
+@app.route("/users", methods=["GET"])
+def list_users():
+    pass
+
+@click.command()
+def cli():
+    pass
+
+@dataclass
+class Config:
+    pass
+
+@staticmethod
+def helper():
+    pass
+"#;
+        let parsed = parser.parse(source, Path::new("test.py")).unwrap();
+
+        assert_eq!(parsed.entry_points, vec!["list_users", "cli"]);
+    }
+
+    #[test]
+    fn test_custom_entry_point_decorators() {
+        let parser = PythonParser::new()
+            .unwrap()
+            .with_entry_point_decorators(vec!["register".to_string()]);
+        let source = r#"
+@register
+def plugin():
+    pass
+"#;
+        let parsed = parser.parse(source, Path::new("test.py")).unwrap();
+
+        assert_eq!(parsed.entry_points, vec!["plugin"]);
+    }
+
+    #[test]
+    fn test_dunder_all_names_are_entry_points() {
+        let parser = PythonParser::new().unwrap();
+        let source = r#"
+__all__ = ["load", 'dump', other]
+__all__ += ("Reader",)
+
+def load():
+    pass
+
+def nested():
+    __all__ = ["ignored"]
+"#;
+        let parsed = parser.parse(source, Path::new("test.py")).unwrap();
+
+        assert_eq!(parsed.entry_points, vec!["load", "dump", "Reader"]);
+    }
+
+    #[test]
+    fn test_syntax_errors_are_recorded() {
+        let parser = PythonParser::new().unwrap();
+        let source = "def ok():\n    pass\n\ndef broken():\n    x = = 1\n";
+
+        let parsed = parser.parse(source, Path::new("test.py")).unwrap();
+
+        assert!(!parsed.parse_errors.is_empty());
+        assert_eq!(parsed.parse_errors[0].line, 5);
+        assert!(parsed.definitions.iter().any(|d| d.name == "ok"));
+    }
+
+    #[test]
+    fn test_valid_source_has_no_parse_errors() {
+        let parser = PythonParser::new().unwrap();
+
+        let parsed = parser
+            .parse("def ok():\n    pass\n", Path::new("test.py"))
+            .unwrap();
+
+        assert!(parsed.parse_errors.is_empty());
+    }
+
+    #[test]
+    fn test_async_functions_and_awaited_calls() {
+        let parser = PythonParser::new().unwrap();
+        let source = r#"
+async def handler():
+    await fetch()
+    async for item in stream():
+        pass
+    async with session() as s:
+        pass
+
+async def fetch():
+    pass
+
+def sync():
+    pass
+"#;
+        let parsed = parser.parse(source, Path::new("test.py")).unwrap();
+
+        let is_async: Vec<(&str, bool)> = parsed
+            .definitions
+            .iter()
+            .map(|d| (d.name.as_str(), d.is_async))
+            .collect();
+        assert_eq!(
+            is_async,
+            vec![("handler", true), ("fetch", true), ("sync", false)]
+        );
+
+        let calls: Vec<&str> = parsed
+            .usages
+            .iter()
+            .filter(|u| u.kind == SymbolKind::Function && u.scope.as_deref() == Some("handler"))
+            .map(|u| u.name.as_str())
+            .collect();
+        assert_eq!(calls, vec!["fetch", "stream", "session"]);
+    }
+
+    #[test]
+    fn test_call_receivers() {
+        let parser = PythonParser::new().unwrap();
+        let source = r#"
+def run(self, client):
+    self.save()
+    client.fetch()
+    load()
+    make().close()
+"#;
+        let parsed = parser.parse(source, Path::new("test.py")).unwrap();
+
+        let receivers: Vec<(&str, Option<&str>)> = parsed
+            .usages
+            .iter()
+            .filter(|u| u.kind == SymbolKind::Function)
+            .map(|u| (u.name.as_str(), u.receiver.as_deref()))
+            .collect();
+        assert_eq!(
+            receivers,
+            vec![
+                ("save", Some("self")),
+                ("fetch", Some("client")),
+                ("load", None),
+                ("close", None),
+                ("make", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_method_kinds_from_decorators() {
+        let parser = PythonParser::new().unwrap();
+        let source = r#"
+class Account:
+    def deposit(self, amount):
+        pass
+
+    @property
+    def balance(self):
+        return 0
+
+    @balance.setter
+    def balance(self, value):
+        pass
+
+    @staticmethod
+    def validate(amount):
+        pass
+
+    @classmethod
+    def open(cls):
+        return cls()
+
+@staticmethod
+def helper():
+    pass
+
+def report(account):
+    return account.balance
+"#;
+        let parsed = parser.parse(source, Path::new("test.py")).unwrap();
+
+        let kinds: Vec<(&str, MethodKind)> = parsed
+            .definitions
+            .iter()
+            .filter(|d| d.kind != SymbolKind::Class)
+            .map(|d| (d.name.as_str(), d.method_kind))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("deposit", MethodKind::Instance),
+                ("balance", MethodKind::Property),
+                ("balance", MethodKind::Property),
+                ("validate", MethodKind::Static),
+                ("open", MethodKind::Class),
+                ("helper", MethodKind::Instance),
+                ("report", MethodKind::Instance),
+            ]
+        );
+
+        // A property read without parentheses is a usage of the property
+        assert!(parsed
+            .usages
+            .iter()
+            .any(|u| u.name == "balance" && u.scope.as_deref() == Some("report")));
+    }
+
+    #[test]
+    fn test_attribute_reads_are_usages() {
+        let parser = PythonParser::new().unwrap();
+        let source = r#"
+def show(account, config):
+    print(account.balance)
+    account.deposit(config.LIMIT)
+    self.total = 0
+"#;
+        let parsed = parser.parse(source, Path::new("test.py")).unwrap();
+
+        let attributes: Vec<(&str, Option<&str>)> = parsed
+            .usages
+            .iter()
+            .filter(|u| u.kind == SymbolKind::Variable && u.receiver.is_some())
+            .map(|u| (u.name.as_str(), u.receiver.as_deref()))
+            .collect();
+        assert_eq!(
+            attributes,
+            vec![
+                ("balance", Some("account")),
+                ("LIMIT", Some("config")),
+                ("total", Some("self")),
+            ]
+        );
+
+        // `account.deposit(...)` is a single call usage
+        let deposits: Vec<&Symbol> = parsed
+            .usages
+            .iter()
+            .filter(|u| u.name == "deposit")
+            .collect();
+        assert_eq!(deposits.len(), 1);
+        assert_eq!(deposits[0].kind, SymbolKind::Function);
+    }
+
+    #[test]
+    fn test_ignore_comment_suppresses_definitions() {
+        let parser = PythonParser::new().unwrap();
+        let source = r#"
+import os  # bury: ignore
+import sys
+
+def plain():
+    pass
+
+def same_line():  # bury: ignore
+    pass
+
+# Called through getattr
+# bury: ignore
+@register
+def above():
+    pass
+
+class Model:
+    # bury: ignore
+    def hook(self):
+        pass
+
+x = 1  # bury: ignore
+def after_code():
+    pass
+"#;
+        let parsed = parser.parse(source, Path::new("app.py")).unwrap();
+
+        let suppressed: Vec<&str> = parsed
+            .definitions
+            .iter()
+            .chain(&parsed.imports)
+            .filter(|s| s.suppressed)
+            .map(|s| s.name.as_str())
+            .collect();
+        assert_eq!(suppressed, vec!["same_line", "above", "hook", "os"]);
+    }
+
+    #[test]
+    fn test_statements_after_exit_are_unreachable() {
+        let parser = PythonParser::new().unwrap();
+        let source = r#"
+def f(x):
+    if x:
+        return 1
+    else:
+        print("else branch")
+    return 2
+    print("dead")
+    cleanup()
+
+def loop(items):
+    for item in items:
+        if item:
+            continue
+            skip(item)
+        break
+        # only a comment after break
+"#;
+        let parsed = parser.parse(source, Path::new("test.py")).unwrap();
+
+        let unreachable: Vec<(&str, &str, usize, usize)> = parsed
+            .unreachable
+            .iter()
+            .map(|u| {
+                (
+                    u.after.as_str(),
+                    u.code.as_str(),
+                    u.location.line,
+                    u.location.end_line,
+                )
+            })
+            .collect();
+        assert_eq!(
+            unreachable,
+            vec![
+                ("return", "print(\"dead\")", 8, 9),
+                ("continue", "skip(item)", 15, 15),
+            ]
+        );
+    }
 }
@@ -0,0 +1,620 @@
+//! Rust parser using tree-sitter
+
+use super::pool::{self, Grammar};
+use super::{collect_parse_errors, Location, ParsedFile, Parser, Symbol, SymbolKind};
+use crate::error::{BuryError, Result};
+use std::path::Path;
+use tree_sitter::{Node, Tree};
+
+/// Rust parser.
+///
+/// Functions, structs, enums and methods of inherent and trait `impl` blocks are
+/// definitions; a method belongs to the type it is implemented on. `main`,
+/// `#[test]` functions, `pub` items and trait method implementations (which are
+/// called through the trait) are entry points. Names inside macro invocations
+/// such as `println!` are recorded as usages, since their arguments are not
+/// parsed as expressions.
+pub struct RustParser {
+    _private: (),
+}
+
+impl RustParser {
+    pub fn new() -> Result<Self> {
+        // Load the grammar now so a broken grammar fails here, not on first parse
+        pool::with_parser(Grammar::Rust, |_| ())?;
+
+        Ok(Self { _private: () })
+    }
+
+    fn extract_definitions(&self, tree: &Tree, source: &str, file_path: &str) -> Vec<Symbol> {
+        let mut definitions = Vec::new();
+        let root = tree.root_node();
+
+        self.traverse_for_definitions(root, source, file_path, &mut definitions, None);
+
+        definitions
+    }
+
+    fn traverse_for_definitions(
+        &self,
+        node: Node,
+        source: &str,
+        file_path: &str,
+        definitions: &mut Vec<Symbol>,
+        current_impl: Option<&str>,
+    ) {
+        let kind = match node.kind() {
+            "function_item" => match current_impl {
+                Some(class_name) => SymbolKind::Method {
+                    class_name: class_name.to_string(),
+                },
+                None => SymbolKind::Function,
+            },
+            "struct_item" | "enum_item" => SymbolKind::Class,
+            "impl_item" => {
+                if let Some(type_name) = self.impl_type(node, source) {
+                    let mut cursor = node.walk();
+                    for child in node.children(&mut cursor) {
+                        self.traverse_for_definitions(
+                            child,
+                            source,
+                            file_path,
+                            definitions,
+                            Some(type_name),
+                        );
+                    }
+                }
+                return;
+            }
+            // Default methods of a trait are called through its implementations
+            "trait_item" => return,
+            _ => {
+                let mut cursor = node.walk();
+                for child in node.children(&mut cursor) {
+                    self.traverse_for_definitions(
+                        child,
+                        source,
+                        file_path,
+                        definitions,
+                        current_impl,
+                    );
+                }
+                return;
+            }
+        };
+
+        if let Some(name_node) = node.child_by_field_name("name") {
+            let name = name_node
+                .utf8_text(source.as_bytes())
+                .unwrap_or("")
+                .to_string();
+            if !name.is_empty() {
+                let pos = name_node.start_position();
+                definitions.push(Symbol::new(
+                    name,
+                    kind,
+                    Location {
+                        file: file_path.to_string(),
+                        line: pos.row + 1,
+                        column: pos.column,
+                        end_line: node.end_position().row + 1,
+                        end_column: node.end_position().column,
+                    },
+                ));
+            }
+        }
+
+        // Items nested in a function body are not methods of the enclosing impl
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            self.traverse_for_definitions(child, source, file_path, definitions, None);
+        }
+    }
+
+    /// Name of the type an `impl` block is for: `Stack` for `impl<T> Stack<T>`
+    fn impl_type<'a>(&self, node: Node, source: &'a str) -> Option<&'a str> {
+        let mut ty = node.child_by_field_name("type")?;
+
+        loop {
+            ty = match ty.kind() {
+                "generic_type" => ty.child_by_field_name("type")?,
+                "scoped_type_identifier" => ty.child_by_field_name("name")?,
+                "type_identifier" => break,
+                _ => return None,
+            };
+        }
+
+        ty.utf8_text(source.as_bytes()).ok()
+    }
+
+    fn extract_usages(&self, tree: &Tree, source: &str, file_path: &str) -> Vec<Symbol> {
+        let mut usages = Vec::new();
+        let root = tree.root_node();
+
+        self.traverse_for_usages(root, source, file_path, &mut usages, None);
+
+        usages
+    }
+
+    /// Collect calls and references, attributing each to the enclosing function
+    /// or method
+    fn traverse_for_usages(
+        &self,
+        node: Node,
+        source: &str,
+        file_path: &str,
+        usages: &mut Vec<Symbol>,
+        scope: Option<&str>,
+    ) {
+        match node.kind() {
+            // Method calls are call expressions on a field expression: `obj.method()`
+            "call_expression" => {
+                if let Some(func_node) = node.child_by_field_name("function") {
+                    let name = self.extract_call_name(func_node, source);
+                    if !name.is_empty() {
+                        let pos = func_node.start_position();
+                        usages.push(
+                            Symbol::new(
+                                name,
+                                SymbolKind::Function,
+                                Location {
+                                    file: file_path.to_string(),
+                                    line: pos.row + 1,
+                                    column: pos.column,
+                                    end_line: func_node.end_position().row + 1,
+                                    end_column: func_node.end_position().column,
+                                },
+                            )
+                            .with_scope(scope.map(str::to_string))
+                            .with_receiver(self.extract_receiver(func_node, source)),
+                        );
+                    }
+                }
+            }
+            "identifier" | "type_identifier" if !self.is_binding_or_call(node) => {
+                // Any other reference to a name, e.g. `Config` in `Config::load()`,
+                // a function passed as a value, or an argument to a macro
+                let name = node.utf8_text(source.as_bytes()).unwrap_or("").to_string();
+                let pos = node.start_position();
+                usages.push(
+                    Symbol::new(
+                        name,
+                        SymbolKind::Variable,
+                        Location {
+                            file: file_path.to_string(),
+                            line: pos.row + 1,
+                            column: pos.column,
+                            end_line: node.end_position().row + 1,
+                            end_column: node.end_position().column,
+                        },
+                    )
+                    .with_scope(scope.map(str::to_string)),
+                );
+            }
+            // `use` declarations bind names, and attributes such as `#[derive(..)]`
+            // name macros rather than items in the crate
+            "use_declaration" | "attribute_item" | "inner_attribute_item" => return,
+            _ => {}
+        }
+
+        let child_scope = self.defined_name(node, source).or(scope);
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            // `impl Foo` implements the type, which is not a use of it
+            if node.kind() == "impl_item" && node.child_by_field_name("type") == Some(child) {
+                continue;
+            }
+            self.traverse_for_usages(child, source, file_path, usages, child_scope);
+        }
+    }
+
+    /// Whether a node is the callee of a call expression, possibly with
+    /// turbofish type arguments (`parse::<u32>()`)
+    fn is_callee(&self, node: Node) -> bool {
+        let parent = match node.parent() {
+            Some(parent) => parent,
+            None => return false,
+        };
+        match parent.kind() {
+            "call_expression" | "generic_function" => {
+                parent.child_by_field_name("function") == Some(node)
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether an identifier is the name of an item or the callee of a call
+    /// (already recorded as a usage)
+    fn is_binding_or_call(&self, node: Node) -> bool {
+        let parent = match node.parent() {
+            Some(parent) => parent,
+            None => return false,
+        };
+        match parent.kind() {
+            "function_item"
+            | "function_signature_item"
+            | "struct_item"
+            | "enum_item"
+            | "enum_variant"
+            | "trait_item"
+            | "type_item"
+            | "const_item"
+            | "static_item"
+            | "mod_item"
+            | "macro_definition" => parent.child_by_field_name("name") == Some(node),
+            // `Config::load()` calls `load`; `Config` is still a usage
+            "scoped_identifier" => {
+                parent.child_by_field_name("name") == Some(node) && self.is_callee(parent)
+            }
+            _ => self.is_callee(node),
+        }
+    }
+
+    /// Name of the function or method defined by this node
+    fn defined_name<'a>(&self, node: Node, source: &'a str) -> Option<&'a str> {
+        match node.kind() {
+            "function_item" => node
+                .child_by_field_name("name")?
+                .utf8_text(source.as_bytes())
+                .ok()
+                .filter(|n| !n.is_empty()),
+            _ => None,
+        }
+    }
+
+    /// The receiver of a call when it is a plain name: `self` in `self.save()`,
+    /// `Config` in `Config::load()`
+    fn extract_receiver(&self, node: Node, source: &str) -> Option<String> {
+        let receiver = match node.kind() {
+            "field_expression" => node.child_by_field_name("value")?,
+            "scoped_identifier" => node.child_by_field_name("path")?,
+            "generic_function" => {
+                return self.extract_receiver(node.child_by_field_name("function")?, source)
+            }
+            _ => return None,
+        };
+        match receiver.kind() {
+            "identifier" | "self" => receiver
+                .utf8_text(source.as_bytes())
+                .ok()
+                .map(str::to_string),
+            _ => None,
+        }
+    }
+
+    fn extract_call_name(&self, node: Node, source: &str) -> String {
+        let name_node = match node.kind() {
+            "identifier" => Some(node),
+            "scoped_identifier" => node.child_by_field_name("name"),
+            "field_expression" => node.child_by_field_name("field"),
+            "generic_function" => {
+                return node
+                    .child_by_field_name("function")
+                    .map(|function| self.extract_call_name(function, source))
+                    .unwrap_or_default()
+            }
+            _ => None,
+        };
+        name_node
+            .and_then(|name| name.utf8_text(source.as_bytes()).ok())
+            .unwrap_or("")
+            .to_string()
+    }
+
+    fn extract_entry_points(&self, tree: &Tree, source: &str) -> Vec<String> {
+        let mut entry_points = Vec::new();
+        let root = tree.root_node();
+
+        self.traverse_for_entry_points(root, source, &mut entry_points, false);
+
+        entry_points
+    }
+
+    fn traverse_for_entry_points(
+        &self,
+        node: Node,
+        source: &str,
+        entry_points: &mut Vec<String>,
+        in_trait_impl: bool,
+    ) {
+        match node.kind() {
+            "function_item" | "struct_item" | "enum_item" => {
+                let name = node
+                    .child_by_field_name("name")
+                    .and_then(|n| n.utf8_text(source.as_bytes()).ok())
+                    .unwrap_or("");
+                let is_entry = is_pub(node, source)
+                    || (node.kind() == "function_item"
+                        && (in_trait_impl || name == "main" || has_test_attribute(node, source)));
+                if is_entry && !name.is_empty() {
+                    entry_points.push(name.to_string());
+                }
+                // Items inside a function body are never exported
+                return;
+            }
+            "impl_item" => {
+                let in_trait_impl = node.child_by_field_name("trait").is_some();
+                if let Some(body) = node.child_by_field_name("body") {
+                    self.traverse_for_entry_points(body, source, entry_points, in_trait_impl);
+                }
+                return;
+            }
+            _ => {}
+        }
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            self.traverse_for_entry_points(child, source, entry_points, in_trait_impl);
+        }
+    }
+}
+
+/// Whether an item is declared plain `pub` (`pub(crate)` items are not exported)
+fn is_pub(node: Node, source: &str) -> bool {
+    let mut cursor = node.walk();
+    let visible = node.children(&mut cursor).any(|child| {
+        child.kind() == "visibility_modifier" && child.utf8_text(source.as_bytes()) == Ok("pub")
+    });
+    visible
+}
+
+/// Whether an item has a `#[test]` attribute (including `#[tokio::test]` and the like)
+fn has_test_attribute(node: Node, source: &str) -> bool {
+    let mut sibling = node.prev_named_sibling();
+    while let Some(attribute) = sibling {
+        match attribute.kind() {
+            "attribute_item" => {
+                let path = attribute
+                    .named_child(0)
+                    .and_then(|attr| attr.named_child(0))
+                    .and_then(|path| path.utf8_text(source.as_bytes()).ok())
+                    .unwrap_or("");
+                if path.rsplit("::").next() == Some("test") {
+                    return true;
+                }
+            }
+            "line_comment" | "block_comment" => {}
+            _ => break,
+        }
+        sibling = attribute.prev_named_sibling();
+    }
+    false
+}
+
+impl Parser for RustParser {
+    fn parse(&self, source: &str, file_path: &Path) -> Result<ParsedFile> {
+        let tree = pool::with_parser(Grammar::Rust, |parser| parser.parse(source, None))?
+            .ok_or_else(|| BuryError::ParseFailed {
+                path: file_path.to_path_buf(),
+            })?;
+
+        let file_path_str = file_path.to_string_lossy().to_string();
+
+        let definitions = self.extract_definitions(&tree, source, &file_path_str);
+        let usages = self.extract_usages(&tree, source, &file_path_str);
+        let entry_points = self.extract_entry_points(&tree, source);
+
+        let parse_errors = collect_parse_errors(&tree, &file_path_str);
+
+        Ok(ParsedFile {
+            path: file_path_str,
+            definitions,
+            usages,
+            entry_points,
+            // rustc already warns about unused imports
+            imports: Vec::new(),
+            parse_errors,
+            unreachable: Vec::new(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(source: &str) -> ParsedFile {
+        RustParser::new()
+            .unwrap()
+            .parse(source, Path::new("lib.rs"))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_parse_simple_function() {
+        let parsed = parse(
+            r#"
+fn hello() {
+    println!("Hello, world!");
+}
+"#,
+        );
+
+        assert!(parsed.parse_errors.is_empty());
+        assert_eq!(parsed.definitions.len(), 1);
+        assert_eq!(parsed.definitions[0].name, "hello");
+        assert_eq!(parsed.definitions[0].kind, SymbolKind::Function);
+    }
+
+    #[test]
+    fn test_parse_types_and_methods() {
+        let parsed = parse(
+            r#"
+struct Stack<T> {
+    items: Vec<T>,
+}
+
+enum Mode {
+    Fast,
+    Slow,
+}
+
+impl<T> Stack<T> {
+    fn push(&mut self, item: T) {
+        fn nested() {}
+        self.items.push(item);
+    }
+}
+
+impl Default for Mode {
+    fn default() -> Self {
+        Mode::Fast
+    }
+}
+
+trait Named {
+    fn name(&self) -> String {
+        String::new()
+    }
+}
+"#,
+        );
+
+        let definitions: Vec<(&str, &SymbolKind)> = parsed
+            .definitions
+            .iter()
+            .map(|d| (d.name.as_str(), &d.kind))
+            .collect();
+        let method_of = |class_name: &str| SymbolKind::Method {
+            class_name: class_name.to_string(),
+        };
+        assert_eq!(
+            definitions,
+            vec![
+                ("Stack", &SymbolKind::Class),
+                ("Mode", &SymbolKind::Class),
+                ("push", &method_of("Stack")),
+                ("nested", &SymbolKind::Function),
+                ("default", &method_of("Mode")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_function_calls() {
+        let parsed = parse(
+            r#"
+fn foo() -> u32 {
+    42
+}
+
+fn bar(config: Config) {
+    foo();
+    config.validate();
+    let parsed = Config::load::<Toml>();
+    println!("{}", compute(parsed));
+}
+"#,
+        );
+
+        let calls: Vec<(&str, Option<&str>, Option<&str>)> = parsed
+            .usages
+            .iter()
+            .filter(|u| u.kind == SymbolKind::Function)
+            .map(|u| (u.name.as_str(), u.scope.as_deref(), u.receiver.as_deref()))
+            .collect();
+        assert_eq!(
+            calls,
+            vec![
+                ("foo", Some("bar"), None),
+                ("validate", Some("bar"), Some("config")),
+                ("load", Some("bar"), Some("Config")),
+            ]
+        );
+
+        let references: Vec<&str> = parsed
+            .usages
+            .iter()
+            .filter(|u| u.kind == SymbolKind::Variable)
+            .map(|u| u.name.as_str())
+            .collect();
+        // `Config` in `Config::load` and the names passed to `println!`
+        assert!(references.contains(&"Config"));
+        assert!(references.contains(&"compute"));
+        assert!(!references.contains(&"load"));
+        assert!(!references.contains(&"foo"));
+    }
+
+    #[test]
+    fn test_impl_type_is_not_a_usage() {
+        let parsed = parse(
+            r#"
+struct Unused;
+
+impl Unused {
+    fn run(&self) {}
+}
+"#,
+        );
+
+        assert!(parsed.usages.iter().all(|u| u.name != "Unused"));
+    }
+
+    #[test]
+    fn test_entry_points() {
+        let parsed = parse(
+            r#"
+pub struct Server;
+struct Internal;
+
+fn main() {}
+pub fn serve() {}
+pub(crate) fn internal() {}
+fn helper() {}
+
+impl Server {
+    pub fn start(&self) {}
+    fn stop(&self) {}
+}
+
+impl std::fmt::Display for Internal {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_serve() {}
+
+    #[tokio::test]
+    // async runtime
+    async fn test_async() {}
+
+    fn fixture() {}
+}
+"#,
+        );
+
+        assert_eq!(
+            parsed.entry_points,
+            vec![
+                "Server",
+                "main",
+                "serve",
+                "start",
+                "fmt",
+                "test_serve",
+                "test_async"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_definition_span_covers_body() {
+        let parsed = parse(
+            r#"
+fn multi() -> u32 {
+    let x = 1;
+    x
+}
+"#,
+        );
+
+        let location = &parsed.definitions[0].location;
+        assert_eq!(location.line, 2);
+        assert_eq!(location.end_line, 5);
+    }
+}
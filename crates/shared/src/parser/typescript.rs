@@ -1,15 +1,62 @@
 //! TypeScript/JavaScript parser using tree-sitter
 
-use super::{Location, ParsedFile, Parser, Symbol, SymbolKind};
-use crate::Result;
+use super::pool::{self, Grammar};
+use super::{
+    collect_parse_errors, collect_unreachable, mark_suppressed, ControlFlow, IgnoreMarker,
+    Location, ParsedFile, Parser, Symbol, SymbolKind,
+};
+use crate::error::{BuryError, Result};
 use std::path::Path;
-use tree_sitter::{Node, Parser as TSParser, Tree};
+use tree_sitter::{Node, Tree};
+
+/// Statements after `return`, `throw`, `break` or `continue` never run. Function
+/// declarations are hoisted, and type declarations are not executed at all.
+const CONTROL_FLOW: ControlFlow = ControlFlow {
+    blocks: &[
+        "program",
+        "statement_block",
+        "switch_case",
+        "switch_default",
+    ],
+    exits: &[
+        "return_statement",
+        "throw_statement",
+        "break_statement",
+        "continue_statement",
+    ],
+    skipped: &[
+        "comment",
+        "empty_statement",
+        "function_declaration",
+        "generator_function_declaration",
+        "interface_declaration",
+        "type_alias_declaration",
+    ],
+};
 
-pub struct TypeScriptParser;
+/// `// bury-ignore` on or above a definition suppresses its findings
+const IGNORE: IgnoreMarker = IgnoreMarker {
+    comment: "//",
+    marker: "bury-ignore",
+};
+
+/// TypeScript parser.
+///
+/// Parses with the current thread's tree-sitter parser from the parser pool, so a
+/// `TypeScriptParser` can be shared across threads. `.tsx` files use the TSX
+/// grammar, and JavaScript files the JavaScript grammar, which accepts JSX and
+/// none of TypeScript's type syntax. JSX element names are recorded as usages of
+/// the component.
+pub struct TypeScriptParser {
+    _private: (),
+}
 
 impl TypeScriptParser {
     pub fn new() -> Result<Self> {
-        Ok(Self)
+        // Load the grammar now so a broken grammar fails here, not on first parse
+        pool::with_parser(Grammar::TypeScript, |_| ())?;
+
+        Ok(Self { _private: () })
     }
 
     fn extract_definitions(&self, tree: &Tree, source: &str, file_path: &str) -> Vec<Symbol> {
@@ -49,6 +96,8 @@ impl TypeScriptParser {
                                 file: file_path.to_string(),
                                 line: pos.row + 1,
                                 column: pos.column,
+                                end_line: node.end_position().row + 1,
+                                end_column: node.end_position().column,
                             },
                         ));
                     }
@@ -83,6 +132,8 @@ impl TypeScriptParser {
                                 file: file_path.to_string(),
                                 line: pos.row + 1,
                                 column: pos.column,
+                                end_line: node.end_position().row + 1,
+                                end_column: node.end_position().column,
                             },
                         ));
                     }
@@ -105,6 +156,8 @@ impl TypeScriptParser {
                                 file: file_path.to_string(),
                                 line: pos.row + 1,
                                 column: pos.column,
+                                end_line: node.end_position().row + 1,
+                                end_column: node.end_position().column,
                             },
                         ));
 
@@ -143,6 +196,8 @@ impl TypeScriptParser {
                                         file: file_path.to_string(),
                                         line: pos.row + 1,
                                         column: pos.column,
+                                        end_line: node.end_position().row + 1,
+                                        end_column: node.end_position().column,
                                     },
                                 ));
                             }
@@ -170,17 +225,20 @@ impl TypeScriptParser {
         let mut usages = Vec::new();
         let root = tree.root_node();
 
-        self.traverse_for_usages(root, source, file_path, &mut usages);
+        self.traverse_for_usages(root, source, file_path, &mut usages, None);
 
         usages
     }
 
+    /// Collect calls and instantiations, attributing each to the nearest enclosing
+    /// named function, method, or function-valued variable
     fn traverse_for_usages(
         &self,
         node: Node,
         source: &str,
         file_path: &str,
         usages: &mut Vec<Symbol>,
+        scope: Option<&str>,
     ) {
         let kind = node.kind();
 
@@ -191,15 +249,21 @@ impl TypeScriptParser {
                     let name = self.extract_call_name(func_node, source);
                     if !name.is_empty() {
                         let pos = func_node.start_position();
-                        usages.push(Symbol::new(
-                            name,
-                            SymbolKind::Function,
-                            Location {
-                                file: file_path.to_string(),
-                                line: pos.row + 1,
-                                column: pos.column,
-                            },
-                        ));
+                        usages.push(
+                            Symbol::new(
+                                name,
+                                SymbolKind::Function,
+                                Location {
+                                    file: file_path.to_string(),
+                                    line: pos.row + 1,
+                                    column: pos.column,
+                                    end_line: func_node.end_position().row + 1,
+                                    end_column: func_node.end_position().column,
+                                },
+                            )
+                            .with_scope(scope.map(str::to_string))
+                            .with_receiver(self.extract_receiver(func_node, source)),
+                        );
                     }
                 }
             }
@@ -212,25 +276,213 @@ impl TypeScriptParser {
                         .to_string();
                     if !name.is_empty() {
                         let pos = class_node.start_position();
-                        usages.push(Symbol::new(
-                            name,
-                            SymbolKind::Class,
-                            Location {
-                                file: file_path.to_string(),
-                                line: pos.row + 1,
-                                column: pos.column,
-                            },
-                        ));
+                        usages.push(
+                            Symbol::new(
+                                name,
+                                SymbolKind::Class,
+                                Location {
+                                    file: file_path.to_string(),
+                                    line: pos.row + 1,
+                                    column: pos.column,
+                                    end_line: class_node.end_position().row + 1,
+                                    end_column: class_node.end_position().column,
+                                },
+                            )
+                            .with_scope(scope.map(str::to_string)),
+                        );
+                    }
+                }
+            }
+            "jsx_opening_element" | "jsx_self_closing_element" => {
+                // `<Header />` uses the `Header` component; lowercase names are
+                // HTML tags. Member names like `<UI.Card>` are handled as
+                // ordinary expressions.
+                if let Some(name_node) = node.child_by_field_name("name") {
+                    let name = name_node.utf8_text(source.as_bytes()).unwrap_or("");
+                    if name_node.kind() == "identifier"
+                        && name.starts_with(|c: char| c.is_ascii_uppercase())
+                    {
+                        let pos = name_node.start_position();
+                        usages.push(
+                            Symbol::new(
+                                name.to_string(),
+                                SymbolKind::Class,
+                                Location {
+                                    file: file_path.to_string(),
+                                    line: pos.row + 1,
+                                    column: pos.column,
+                                    end_line: name_node.end_position().row + 1,
+                                    end_column: name_node.end_position().column,
+                                },
+                            )
+                            .with_scope(scope.map(str::to_string)),
+                        );
                     }
                 }
             }
+            "identifier" | "type_identifier" | "shorthand_property_identifier"
+                if !self.is_binding_or_call(node) =>
+            {
+                // Any other reference to a name, e.g. a function passed as a callback
+                // or an imported type used in an annotation
+                let name = node.utf8_text(source.as_bytes()).unwrap_or("").to_string();
+                let pos = node.start_position();
+                usages.push(
+                    Symbol::new(
+                        name,
+                        SymbolKind::Variable,
+                        Location {
+                            file: file_path.to_string(),
+                            line: pos.row + 1,
+                            column: pos.column,
+                            end_line: node.end_position().row + 1,
+                            end_column: node.end_position().column,
+                        },
+                    )
+                    .with_scope(scope.map(str::to_string)),
+                );
+            }
+            // Names in import statements are bindings, not usages
+            "import_statement" => return,
             _ => {}
         }
 
+        let child_scope = self.defined_name(node, source).or(scope);
+
         // Traverse children
         let mut cursor = node.walk();
         for child in node.children(&mut cursor) {
-            self.traverse_for_usages(child, source, file_path, usages);
+            self.traverse_for_usages(child, source, file_path, usages, child_scope);
+        }
+    }
+
+    /// Whether an identifier is the name of a declaration, the callee of a call
+    /// or `new` expression, a JSX element name (already recorded as a usage), an
+    /// exported name (recorded as an entry point), or binds a parameter
+    fn is_binding_or_call(&self, node: Node) -> bool {
+        let parent = match node.parent() {
+            Some(parent) => parent,
+            None => return false,
+        };
+        let field = match parent.kind() {
+            "function_declaration"
+            | "generator_function_declaration"
+            | "function"
+            | "class_declaration"
+            | "abstract_class_declaration"
+            | "class"
+            | "variable_declarator"
+            | "interface_declaration"
+            | "type_alias_declaration"
+            | "enum_declaration" => "name",
+            "required_parameter" | "optional_parameter" => "pattern",
+            // `x => ...` and `catch (e)`
+            "arrow_function" | "catch_clause" => "parameter",
+            "rest_pattern" | "export_specifier" => return true,
+            // `export default foo`
+            "export_statement" => "value",
+            "call_expression" => "function",
+            "new_expression" => "constructor",
+            // JSX element names are recorded (or skipped) with their element
+            "jsx_opening_element" | "jsx_self_closing_element" | "jsx_closing_element" => "name",
+            _ => return false,
+        };
+        parent.child_by_field_name(field) == Some(node)
+    }
+
+    fn extract_imports(&self, tree: &Tree, source: &str, file_path: &str) -> Vec<Symbol> {
+        let mut imports = Vec::new();
+        let root = tree.root_node();
+
+        self.traverse_for_imports(root, source, file_path, &mut imports);
+
+        imports
+    }
+
+    fn traverse_for_imports(
+        &self,
+        node: Node,
+        source: &str,
+        file_path: &str,
+        imports: &mut Vec<Symbol>,
+    ) {
+        let binding = match node.kind() {
+            // `import D from "d"`
+            "identifier" if node.parent().map(|p| p.kind()) == Some("import_clause") => Some(node),
+            // `import * as ns from "n"`
+            "namespace_import" => node.named_child(0),
+            // `import { a, b as c } from "x"` binds `a` and `c`
+            "import_specifier" => node
+                .child_by_field_name("alias")
+                .or_else(|| node.child_by_field_name("name")),
+            _ => None,
+        };
+
+        if let Some(binding) = binding {
+            let name = binding
+                .utf8_text(source.as_bytes())
+                .unwrap_or("")
+                .to_string();
+            let pos = binding.start_position();
+            imports.push(Symbol::new(
+                name,
+                SymbolKind::Import,
+                Location {
+                    file: file_path.to_string(),
+                    line: pos.row + 1,
+                    column: pos.column,
+                    end_line: binding.end_position().row + 1,
+                    end_column: binding.end_position().column,
+                },
+            ));
+            return;
+        }
+
+        // Imports only appear in import statements at the top level
+        if !matches!(
+            node.kind(),
+            "program" | "import_statement" | "import_clause" | "named_imports"
+        ) {
+            return;
+        }
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            self.traverse_for_imports(child, source, file_path, imports);
+        }
+    }
+
+    /// Name of the function defined by this node, if it is a definition that
+    /// owns the calls in its body
+    fn defined_name<'a>(&self, node: Node, source: &'a str) -> Option<&'a str> {
+        let name_node = match node.kind() {
+            "function_declaration" | "method_definition" => node.child_by_field_name("name"),
+            // A named function expression assigned to a variable is owned by the variable
+            "function" if node.parent().map(|p| p.kind()) != Some("variable_declarator") => {
+                node.child_by_field_name("name")
+            }
+            "variable_declarator" => node
+                .child_by_field_name("value")
+                .filter(|v| matches!(v.kind(), "function" | "arrow_function"))
+                .and_then(|_| node.child_by_field_name("name")),
+            _ => None,
+        }?;
+
+        name_node
+            .utf8_text(source.as_bytes())
+            .ok()
+            .filter(|n| !n.is_empty())
+    }
+
+    /// The receiver of an `obj.method()` call, when it is a plain name
+    fn extract_receiver(&self, node: Node, source: &str) -> Option<String> {
+        if node.kind() != "member_expression" {
+            return None;
+        }
+        let object = node.child_by_field_name("object")?;
+        match object.kind() {
+            "identifier" | "this" => object.utf8_text(source.as_bytes()).ok().map(str::to_string),
+            _ => None,
         }
     }
 
@@ -292,6 +544,34 @@ impl TypeScriptParser {
                     }
                 }
             }
+
+            // `export default foo`
+            if let Some(value) = node.child_by_field_name("value") {
+                if value.kind() == "identifier" {
+                    if let Ok(name) = value.utf8_text(source.as_bytes()) {
+                        entry_points.push(name.to_string());
+                    }
+                }
+            }
+
+            // `export { foo, bar as baz }` and `export { foo } from './mod'` expose
+            // the local (or re-exported) name. `export * from './mod'` names no
+            // symbols, so it cannot be resolved without following the import.
+            let mut cursor = node.walk();
+            for clause in node
+                .children(&mut cursor)
+                .filter(|child| child.kind() == "export_clause")
+            {
+                let mut clause_cursor = clause.walk();
+                for specifier in clause.named_children(&mut clause_cursor) {
+                    let name = specifier
+                        .child_by_field_name("name")
+                        .and_then(|name| name.utf8_text(source.as_bytes()).ok());
+                    if let Some(name) = name {
+                        entry_points.push(name.to_string());
+                    }
+                }
+            }
         }
 
         // Detect test functions (describe, it, test)
@@ -327,26 +607,37 @@ impl TypeScriptParser {
 
 impl Parser for TypeScriptParser {
     fn parse(&self, source: &str, file_path: &Path) -> Result<ParsedFile> {
-        // Parser needs to be mutable, so we need to use interior mutability
-        // For now, we'll create a new parser each time (not ideal but works for MVP)
-        let mut parser = TSParser::new();
-        parser.set_language(tree_sitter_typescript::language_typescript())?;
-
-        let tree = parser
-            .parse(source, None)
-            .ok_or_else(|| anyhow::anyhow!("Failed to parse TypeScript file"))?;
+        let grammar = match Grammar::for_path(file_path) {
+            Ok(grammar @ (Grammar::Tsx | Grammar::JavaScript)) => grammar,
+            _ => Grammar::TypeScript,
+        };
+        let tree =
+            pool::with_parser(grammar, |parser| parser.parse(source, None))?.ok_or_else(|| {
+                BuryError::ParseFailed {
+                    path: file_path.to_path_buf(),
+                }
+            })?;
 
         let file_path_str = file_path.to_string_lossy().to_string();
 
-        let definitions = self.extract_definitions(&tree, source, &file_path_str);
+        let mut definitions = self.extract_definitions(&tree, source, &file_path_str);
         let usages = self.extract_usages(&tree, source, &file_path_str);
         let entry_points = self.extract_entry_points(&tree, source);
+        let mut imports = self.extract_imports(&tree, source, &file_path_str);
+        mark_suppressed(&mut definitions, source, &IGNORE);
+        mark_suppressed(&mut imports, source, &IGNORE);
+
+        let parse_errors = collect_parse_errors(&tree, &file_path_str);
+        let unreachable = collect_unreachable(&tree, source, &file_path_str, &CONTROL_FLOW);
 
         Ok(ParsedFile {
             path: file_path_str,
             definitions,
             usages,
             entry_points,
+            imports,
+            parse_errors,
+            unreachable,
         })
     }
 }
@@ -429,4 +720,205 @@ function bar() {
         assert_eq!(parsed.definitions.len(), 2); // foo, bar
         assert!(!parsed.usages.is_empty()); // At least foo() call
     }
+
+    #[test]
+    fn test_extract_imports() {
+        let parser = TypeScriptParser::new().unwrap();
+        let source = r#"
+import { foo, bar as baz } from "x";
+import D from "d";
+import * as ns from "n";
+import "side-effect";
+"#;
+        let parsed = parser.parse(source, Path::new("test.ts")).unwrap();
+        let names: Vec<&str> = parsed.imports.iter().map(|i| i.name.as_str()).collect();
+
+        assert_eq!(names, vec!["foo", "baz", "D", "ns"]);
+        assert!(parsed.imports.iter().all(|i| i.kind == SymbolKind::Import));
+    }
+
+    #[test]
+    fn test_definition_span_covers_body() {
+        let parser = TypeScriptParser::new().unwrap();
+        let source = r#"
+const multi = () => {
+    const x = 1;
+    return x;
+};
+"#;
+        let parsed = parser.parse(source, Path::new("test.ts")).unwrap();
+
+        let location = &parsed.definitions[0].location;
+        assert_eq!(location.line, 2);
+        assert_eq!(location.end_line, 5);
+        assert!(location.end_line > location.line);
+    }
+
+    #[test]
+    fn test_export_default_and_export_clause_are_entry_points() {
+        let parser = TypeScriptParser::new().unwrap();
+        let source = r#"
+function foo() {}
+function bar() {}
+function qux() {}
+export default foo;
+export { bar, qux as renamed };
+export { util } from './util';
+export * from './types';
+"#;
+        let parsed = parser.parse(source, Path::new("test.ts")).unwrap();
+
+        assert_eq!(parsed.entry_points, vec!["foo", "bar", "qux", "util"]);
+    }
+
+    #[test]
+    fn test_tsx_component_references_are_usages() {
+        let parser = TypeScriptParser::new().unwrap();
+        let source = r#"
+function Button(props: { label: string }) {
+    return <button>{props.label}</button>;
+}
+
+export function App() {
+    return <div><Button label="ok" /><UI.Card></UI.Card></div>;
+}
+"#;
+        let parsed = parser.parse(source, Path::new("app.tsx")).unwrap();
+
+        assert!(parsed.parse_errors.is_empty());
+        let usages: Vec<(&str, Option<&str>)> = parsed
+            .usages
+            .iter()
+            .map(|u| (u.name.as_str(), u.scope.as_deref()))
+            .collect();
+        assert!(usages.contains(&("Button", Some("App"))));
+        assert!(usages.contains(&("UI", Some("App"))));
+    }
+
+    #[test]
+    fn test_javascript_uses_javascript_grammar() {
+        let parser = TypeScriptParser::new().unwrap();
+        // JSX in a `.js` file, and a `<`/`>` comparison the TypeScript grammar
+        // reads as a type argument list
+        let source = r#"
+import { Header } from "./header";
+
+function helper(a, b, c) {
+    return a < b > (c);
+}
+
+export function Page() {
+    helper(1, 2, 3);
+    return <main><Header /></main>;
+}
+"#;
+        let parsed = parser.parse(source, Path::new("page.js")).unwrap();
+
+        assert!(parsed.parse_errors.is_empty());
+        let usages: Vec<(&str, Option<&str>)> = parsed
+            .usages
+            .iter()
+            .map(|u| (u.name.as_str(), u.scope.as_deref()))
+            .collect();
+        assert!(usages.contains(&("helper", Some("Page"))));
+        assert!(usages.contains(&("Header", Some("Page"))));
+
+        let as_typescript = parser.parse(source, Path::new("page.ts")).unwrap();
+        assert!(!as_typescript.parse_errors.is_empty());
+    }
+
+    #[test]
+    fn test_jsx_component_usages_skip_html_tags() {
+        let parser = TypeScriptParser::new().unwrap();
+        let source = r#"
+export function Layout() {
+    return (
+        <div>
+            <Header />
+            <Sidebar>
+                <span>menu</span>
+            </Sidebar>
+        </div>
+    );
+}
+"#;
+        let parsed = parser.parse(source, Path::new("layout.tsx")).unwrap();
+
+        let names: Vec<&str> = parsed.usages.iter().map(|u| u.name.as_str()).collect();
+        assert_eq!(names, vec!["Header", "Sidebar"]);
+        assert!(parsed
+            .usages
+            .iter()
+            .all(|u| u.scope.as_deref() == Some("Layout")));
+    }
+
+    #[test]
+    fn test_ignore_comment_suppresses_definitions() {
+        let parser = TypeScriptParser::new().unwrap();
+        let source = r#"
+// bury-ignore
+export function exported() {}
+
+// Loaded by the plugin registry
+// bury-ignore
+function plugin() {}
+
+function plain() {}
+
+class Service {
+    @Inject() // bury-ignore
+    handler() {}
+}
+"#;
+        let parsed = parser.parse(source, Path::new("app.ts")).unwrap();
+
+        let suppressed: Vec<&str> = parsed
+            .definitions
+            .iter()
+            .filter(|s| s.suppressed)
+            .map(|s| s.name.as_str())
+            .collect();
+        assert_eq!(suppressed, vec!["exported", "plugin", "handler"]);
+    }
+
+    #[test]
+    fn test_statements_after_exit_are_unreachable() {
+        let parser = TypeScriptParser::new().unwrap();
+        let source = r#"
+function f(x: number) {
+    if (x) {
+        return 1;
+    } else {
+        console.log("else branch");
+    }
+    return helper();
+    function helper() { return 2; }
+    console.log("dead");
+}
+
+function g(kind: string) {
+    switch (kind) {
+        case "a":
+            throw new Error("a");
+            console.log("after throw");
+        default:
+            break;
+    }
+}
+"#;
+        let parsed = parser.parse(source, Path::new("test.ts")).unwrap();
+
+        let unreachable: Vec<(&str, &str, usize)> = parsed
+            .unreachable
+            .iter()
+            .map(|u| (u.after.as_str(), u.code.as_str(), u.location.line))
+            .collect();
+        assert_eq!(
+            unreachable,
+            vec![
+                ("return", "console.log(\"dead\");", 10),
+                ("throw", "console.log(\"after throw\");", 17),
+            ]
+        );
+    }
 }
@@ -0,0 +1,68 @@
+//! CSV reporter (one row per finding, for spreadsheets)
+
+use super::{rule_id, AnalysisStats, Finding, Reporter};
+use crate::Result;
+
+const HEADER: [&str; 7] = [
+    "kind",
+    "name",
+    "file",
+    "line",
+    "column",
+    "reason",
+    "confidence",
+];
+
+pub struct CsvReporter;
+
+impl<T: Finding> Reporter<T> for CsvReporter {
+    fn report(&self, findings: &[T], _stats: &AnalysisStats) -> Result<String> {
+        let mut writer = ::csv::Writer::from_writer(Vec::new());
+        writer.write_record(HEADER)?;
+
+        for finding in findings {
+            writer.write_record([
+                rule_id(&finding.kind()),
+                finding.name(),
+                finding.file(),
+                finding.line().to_string(),
+                finding.column().to_string(),
+                finding.reason(),
+                finding.confidence(),
+            ])?;
+        }
+
+        let bytes = writer.into_inner().map_err(|e| e.into_error())?;
+        Ok(String::from_utf8(bytes)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::test_support::TestFinding;
+
+    #[test]
+    fn test_quoted_fields_round_trip() {
+        let output = CsvReporter
+            .report(
+                &[TestFinding {
+                    kind: "Method { class_name: \"Service\" }",
+                    reason: "Not reachable, \"really\"",
+                    ..Default::default()
+                }],
+                &AnalysisStats::default(),
+            )
+            .unwrap();
+
+        assert!(output.contains("\"Not reachable, \"\"really\"\"\""));
+
+        let mut reader = ::csv::Reader::from_reader(output.as_bytes());
+        assert_eq!(reader.headers().unwrap(), HEADER.as_slice());
+        let records: Vec<::csv::StringRecord> = reader.records().map(|r| r.unwrap()).collect();
+        assert_eq!(records.len(), 1);
+        assert_eq!(&records[0][0], "method");
+        assert_eq!(&records[0][3], "3");
+        assert_eq!(&records[0][5], "Not reachable, \"really\"");
+    }
+}
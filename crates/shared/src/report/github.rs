@@ -0,0 +1,77 @@
+//! GitHub Actions reporter (workflow commands that annotate pull requests)
+//!
+//! Each finding becomes a `::warning` command, followed by a `::notice` with the
+//! total count. See <https://docs.github.com/actions/using-workflows/workflow-commands-for-github-actions>.
+
+use super::{AnalysisStats, Finding, Reporter};
+use crate::Result;
+
+pub struct GithubActionsReporter;
+
+impl<T: Finding> Reporter<T> for GithubActionsReporter {
+    fn report(&self, findings: &[T], stats: &AnalysisStats) -> Result<String> {
+        let mut output = String::new();
+
+        for finding in findings {
+            output.push_str(&format!(
+                "::warning file={},line={},col={}::{}\n",
+                escape_property(&finding.file().replace('\\', "/")),
+                finding.line(),
+                // Workflow command columns are 1-based, ours are 0-based
+                finding.column() + 1,
+                escape_data(&format!("{}: {}", finding.name(), finding.reason())),
+            ));
+        }
+
+        output.push_str(&format!(
+            "::notice::{} findings ({} files scanned, {} definitions)",
+            findings.len(),
+            stats.total_files_scanned,
+            stats.total_definitions
+        ));
+
+        Ok(output)
+    }
+}
+
+/// Escape a command message
+fn escape_data(text: &str) -> String {
+    text.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escape a command property value, which additionally cannot contain `:` or `,`
+fn escape_property(text: &str) -> String {
+    escape_data(text).replace(':', "%3A").replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::test_support::TestFinding;
+
+    #[test]
+    fn test_workflow_commands_are_escaped() {
+        let output = GithubActionsReporter
+            .report(
+                &[TestFinding {
+                    file: "src/a,b.py",
+                    reason: "100% unreachable\r\nsee docs",
+                    ..Default::default()
+                }],
+                &AnalysisStats::default(),
+            )
+            .unwrap();
+
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(
+            lines[0],
+            "::warning file=src/a%2Cb.py,line=3,col=5::unused: 100%25 unreachable%0D%0Asee docs"
+        );
+        assert_eq!(
+            lines[1],
+            "::notice::1 findings (0 files scanned, 0 definitions)"
+        );
+    }
+}
@@ -0,0 +1,181 @@
+//! Aggregation of findings by directory
+
+use super::Finding;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Component, Path};
+
+/// Number of findings in a single directory
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DirectoryGroup {
+    /// Directory path, truncated to the requested depth ("." for the root)
+    pub directory: String,
+    /// Number of findings in this directory and its subdirectories
+    pub findings: usize,
+}
+
+/// Group findings by directory, truncating paths relative to `root` (the
+/// analyzed path) to `depth` components.
+///
+/// A depth of 1 groups by top-level directory (`src`, `tests`, ...), a depth of 2
+/// by second-level directory (`src/api`, `src/db`, ...), and so on. Files shallower
+/// than `depth` are grouped under their own directory. Groups are sorted by
+/// descending finding count, then by directory name.
+pub fn group_by_directory<T: Finding>(
+    findings: &[T],
+    root: &Path,
+    depth: usize,
+) -> Vec<DirectoryGroup> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+
+    for finding in findings {
+        let directory = directory_prefix(&relative_path(&finding.file(), root), depth);
+        *counts.entry(directory).or_insert(0) += 1;
+    }
+
+    let mut groups: Vec<DirectoryGroup> = counts
+        .into_iter()
+        .map(|(directory, findings)| DirectoryGroup {
+            directory,
+            findings,
+        })
+        .collect();
+
+    groups.sort_by(|a, b| {
+        b.findings
+            .cmp(&a.findings)
+            .then_with(|| a.directory.cmp(&b.directory))
+    });
+
+    groups
+}
+
+/// `file` relative to `root`, with `/` separators. Files outside `root` keep
+/// their path, and a `root` that is the file itself gives its file name.
+pub fn relative_path(file: &str, root: &Path) -> String {
+    let path = Path::new(file);
+    let relative = match path.strip_prefix(root) {
+        Ok(relative) if relative.as_os_str().is_empty() => {
+            path.file_name().map(Path::new).unwrap_or(path)
+        }
+        Ok(relative) => relative,
+        Err(_) => path,
+    };
+
+    relative
+        .components()
+        .filter(|c| !matches!(c, Component::CurDir))
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Get the first `depth` directory components of a relative file path
+fn directory_prefix(file: &str, depth: usize) -> String {
+    let parent = Path::new(file).parent().unwrap_or_else(|| Path::new(""));
+
+    let components: Vec<String> = parent
+        .components()
+        .take(depth)
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect();
+
+    if components.is_empty() {
+        ".".to_string()
+    } else {
+        components.join("/")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::test_support::TestFinding;
+
+    fn findings(files: &[&'static str]) -> Vec<TestFinding> {
+        files
+            .iter()
+            .map(|&file| TestFinding {
+                file,
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_group_by_top_level_directory() {
+        let findings = findings(&[
+            "./packages/api/handlers.py",
+            "./packages/api/models.py",
+            "./packages/web/app.ts",
+            "./scripts/build.py",
+            "./setup.py",
+        ]);
+
+        let groups = group_by_directory(&findings, Path::new("."), 1);
+
+        assert_eq!(
+            groups,
+            vec![
+                DirectoryGroup {
+                    directory: "packages".to_string(),
+                    findings: 3
+                },
+                DirectoryGroup {
+                    directory: ".".to_string(),
+                    findings: 1
+                },
+                DirectoryGroup {
+                    directory: "scripts".to_string(),
+                    findings: 1
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_group_by_nested_directory() {
+        let findings = findings(&[
+            "packages/api/handlers.py",
+            "packages/api/v1/routes.py",
+            "packages/web/app.ts",
+        ]);
+
+        let groups = group_by_directory(&findings, Path::new("."), 2);
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].directory, "packages/api");
+        assert_eq!(groups[0].findings, 2);
+        assert_eq!(groups[1].directory, "packages/web");
+        assert_eq!(groups[1].findings, 1);
+    }
+
+    #[test]
+    fn test_depth_counts_from_the_analyzed_root() {
+        let findings = findings(&[
+            "./packages/api/handlers.py",
+            "./packages/web/app.ts",
+            "./packages/setup.py",
+        ]);
+
+        let groups = group_by_directory(&findings, Path::new("./packages"), 1);
+
+        let directories: Vec<&str> = groups.iter().map(|g| g.directory.as_str()).collect();
+        assert_eq!(directories, vec![".", "api", "web"]);
+    }
+
+    #[test]
+    fn test_absolute_paths_group_below_the_root() {
+        let findings = findings(&[
+            "/home/dev/project/src/api/handlers.py",
+            "/home/dev/project/src/db.py",
+            "/home/dev/project/tests/test_api.py",
+        ]);
+
+        let groups = group_by_directory(&findings, Path::new("/home/dev/project"), 1);
+
+        assert_eq!(groups[0].directory, "src");
+        assert_eq!(groups[0].findings, 2);
+        assert_eq!(groups[1].directory, "tests");
+    }
+}
@@ -0,0 +1,216 @@
+//! HTML reporter (a self-contained page for sharing results)
+//!
+//! The page inlines its CSS and JavaScript. Findings are grouped by file in
+//! collapsible sections of one table; column headers sort the rows within each
+//! file, and the filter box and confidence selector hide non-matching rows.
+
+use super::{rule_id, AnalysisStats, Finding, Reporter};
+use crate::Result;
+use std::collections::BTreeMap;
+
+pub struct HtmlReporter;
+
+const STYLE: &str = r#"
+body { font-family: system-ui, sans-serif; margin: 2rem; color: #222; }
+h1 { margin-bottom: 0.25rem; }
+.summary { color: #555; margin-bottom: 1.5rem; }
+.controls { margin-bottom: 1rem; display: flex; gap: 0.5rem; }
+.controls input { flex: 1; max-width: 24rem; padding: 0.25rem 0.5rem; }
+table { border-collapse: collapse; width: 100%; }
+th, td { text-align: left; padding: 0.3rem 0.6rem; border-bottom: 1px solid #eee; }
+thead th { cursor: pointer; user-select: none; background: #f6f6f6; }
+thead th[data-order="asc"]::after { content: " \25B2"; }
+thead th[data-order="desc"]::after { content: " \25BC"; }
+tr.group th { cursor: pointer; background: #fafafa; font-family: monospace; }
+tr.group th::before { content: "\25BE  "; }
+tbody.collapsed tr.group th::before { content: "\25B8  "; }
+tbody.collapsed tr.finding { display: none; }
+tr.finding.hidden { display: none; }
+td.name { font-family: monospace; }
+pre.snippet { margin: 0.4rem 0 0; padding: 0.4rem; background: #f6f6f6; overflow-x: auto; }
+.High { color: #b00020; font-weight: bold; }
+.Medium { color: #a36a00; }
+.Low { color: #1a5fb4; }
+"#;
+
+const SCRIPT: &str = r#"
+const table = document.getElementById("findings");
+const filter = document.getElementById("filter");
+const confidence = document.getElementById("confidence");
+
+function applyFilters() {
+  const text = filter.value.toLowerCase();
+  for (const row of table.querySelectorAll("tr.finding")) {
+    const matchesText = row.textContent.toLowerCase().includes(text);
+    const matchesConfidence = !confidence.value || row.dataset.confidence === confidence.value;
+    row.classList.toggle("hidden", !(matchesText && matchesConfidence));
+  }
+}
+filter.addEventListener("input", applyFilters);
+confidence.addEventListener("change", applyFilters);
+
+for (const group of table.querySelectorAll("tr.group")) {
+  group.addEventListener("click", () => group.parentElement.classList.toggle("collapsed"));
+}
+
+table.querySelectorAll("thead th").forEach((header, column) => {
+  header.addEventListener("click", () => {
+    const order = header.dataset.order === "asc" ? "desc" : "asc";
+    table.querySelectorAll("thead th").forEach((th) => delete th.dataset.order);
+    header.dataset.order = order;
+    const numeric = header.dataset.type === "number";
+    for (const body of table.tBodies) {
+      const rows = Array.from(body.querySelectorAll("tr.finding"));
+      rows.sort((a, b) => {
+        const x = a.cells[column].textContent;
+        const y = b.cells[column].textContent;
+        const cmp = numeric ? Number(x) - Number(y) : x.localeCompare(y);
+        return order === "asc" ? cmp : -cmp;
+      });
+      rows.forEach((row) => body.appendChild(row));
+    }
+  });
+});
+"#;
+
+impl<T: Finding> Reporter<T> for HtmlReporter {
+    fn report(&self, findings: &[T], stats: &AnalysisStats) -> Result<String> {
+        let mut output = String::new();
+
+        output.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n");
+        output.push_str("<meta charset=\"utf-8\">\n");
+        output.push_str("<title>Analysis Report</title>\n");
+        output.push_str(&format!("<style>{}</style>\n", STYLE));
+        output.push_str("</head>\n<body>\n");
+
+        output.push_str("<h1>Analysis Report</h1>\n");
+        output.push_str(&format!(
+            "<p class=\"summary\">{} files scanned &middot; {} definitions &middot; {} findings</p>\n",
+            stats.total_files_scanned,
+            stats.total_definitions,
+            findings.len()
+        ));
+
+        if findings.is_empty() {
+            output.push_str("<p>✅ No issues found!</p>\n</body>\n</html>\n");
+            return Ok(output);
+        }
+
+        output.push_str("<div class=\"controls\">\n");
+        output.push_str("<input id=\"filter\" type=\"search\" placeholder=\"Filter findings\">\n");
+        output.push_str("<select id=\"confidence\">\n");
+        output.push_str("<option value=\"\">All confidence levels</option>\n");
+        for level in ["High", "Medium", "Low"] {
+            output.push_str(&format!("<option>{}</option>\n", level));
+        }
+        output.push_str("</select>\n</div>\n");
+
+        output.push_str("<table id=\"findings\">\n<thead>\n<tr>");
+        output.push_str("<th>File</th><th data-type=\"number\">Line</th><th>Kind</th>");
+        output.push_str("<th>Name</th><th>Confidence</th><th>Reason</th>");
+        output.push_str("</tr>\n</thead>\n");
+
+        let mut by_file: BTreeMap<String, Vec<&T>> = BTreeMap::new();
+        for finding in findings {
+            by_file.entry(finding.file()).or_default().push(finding);
+        }
+
+        for (file, mut file_findings) in by_file {
+            file_findings.sort_by_key(|f| (f.line(), f.column()));
+
+            output.push_str("<tbody>\n");
+            output.push_str(&format!(
+                "<tr class=\"group\"><th colspan=\"6\">{} ({})</th></tr>\n",
+                escape(&file),
+                file_findings.len()
+            ));
+            for finding in file_findings {
+                let confidence = escape(&finding.confidence());
+                let snippet = match finding.snippet() {
+                    Some(snippet) => format!("<pre class=\"snippet\">{}</pre>", escape(&snippet)),
+                    None => String::new(),
+                };
+                output.push_str(&format!(
+                    "<tr class=\"finding\" data-confidence=\"{confidence}\">\
+                     <td>{}</td><td>{}</td><td>{}</td><td class=\"name\">{}</td>\
+                     <td class=\"{confidence}\">{confidence}</td><td>{}{}</td></tr>\n",
+                    escape(&file),
+                    finding.line(),
+                    escape(&rule_id(&finding.kind())),
+                    escape(&finding.name()),
+                    escape(&finding.reason()),
+                    snippet,
+                ));
+            }
+            output.push_str("</tbody>\n");
+        }
+
+        output.push_str("</table>\n");
+        output.push_str(&format!("<script>{}</script>\n", SCRIPT));
+        output.push_str("</body>\n</html>\n");
+
+        Ok(output)
+    }
+}
+
+/// Escape text for use in HTML element content and quoted attributes
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::test_support::TestFinding;
+
+    #[test]
+    fn test_one_row_per_finding() {
+        let findings = [
+            TestFinding {
+                file: "src/a.py",
+                name: "unused",
+                ..Default::default()
+            },
+            TestFinding {
+                file: "src/a.py",
+                name: "stale",
+                ..Default::default()
+            },
+            TestFinding {
+                file: "src/b.py",
+                name: "<script>",
+                ..Default::default()
+            },
+        ];
+
+        let output = HtmlReporter
+            .report(&findings, &AnalysisStats::default())
+            .unwrap();
+
+        assert!(output.contains("<table id=\"findings\">"));
+        assert_eq!(output.matches("<tr class=\"finding\"").count(), 3);
+        assert_eq!(output.matches("<tr class=\"group\"").count(), 2);
+        assert!(output.contains("&lt;script&gt;"));
+        assert!(!output.contains("<link") && !output.contains("<script src"));
+    }
+
+    #[test]
+    fn test_no_findings() {
+        let output =
+            Reporter::<TestFinding>::report(&HtmlReporter, &[], &AnalysisStats::default()).unwrap();
+
+        assert!(output.contains("No issues found"));
+        assert!(!output.contains("<table"));
+    }
+}
@@ -1,34 +1,92 @@
-//! JSON reporter (LLM-friendly format)
+//! JSON reporters (LLM-friendly format)
+//!
+//! [`JsonReporter`] writes one pretty-printed document. [`JsonLinesReporter`]
+//! writes one compact object per finding followed by a `{"summary": ...}` line,
+//! and can stream to any writer so large result sets are never held as a
+//! single string.
 
-use super::{Finding, Reporter};
+use super::{AnalysisStats, DirectoryGroup, Finding, Reporter};
 use crate::Result;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::Write;
 
 pub struct JsonReporter;
 
 impl<T: Finding> Reporter<T> for JsonReporter {
-    fn report(&self, findings: &[T]) -> Result<String> {
-        let items: Vec<GenericItem> = findings
-            .iter()
-            .map(|f| GenericItem {
-                kind: f.kind(),
-                name: f.name(),
-                file: f.file(),
-                line: f.line(),
-                column: f.column(),
-                reason: f.reason(),
-                confidence: f.confidence(),
-            })
-            .collect();
+    fn report(&self, findings: &[T], stats: &AnalysisStats) -> Result<String> {
+        Ok(serde_json::to_string_pretty(&GenericReport::new(
+            findings, stats,
+        ))?)
+    }
+}
+
+impl JsonReporter {
+    /// Report findings with an extra top-level `name` key holding `section`,
+    /// for tool-specific data that belongs next to the findings
+    pub fn report_with_section<T: Finding, S: Serialize>(
+        &self,
+        findings: &[T],
+        stats: &AnalysisStats,
+        name: &str,
+        section: &S,
+    ) -> Result<String> {
+        let report = SectionedReport {
+            report: GenericReport::new(findings, stats),
+            sections: BTreeMap::from([(name, section)]),
+        };
+
+        Ok(serde_json::to_string_pretty(&report)?)
+    }
+
+    /// Report finding counts aggregated by directory
+    pub fn report_directories(&self, groups: &[DirectoryGroup]) -> Result<String> {
+        let report = DirectoryReport {
+            summary: DirectorySummary {
+                total_findings: groups.iter().map(|g| g.findings).sum(),
+                total_directories: groups.len(),
+            },
+            directories: groups.to_vec(),
+        };
+
+        Ok(serde_json::to_string_pretty(&report)?)
+    }
+}
 
-        let report = GenericReport {
+/// JSON Lines reporter: one finding per line, then a summary line
+pub struct JsonLinesReporter;
+
+impl JsonLinesReporter {
+    /// Write each finding as it is serialized, without buffering the report
+    pub fn write<T: Finding, W: Write>(
+        &self,
+        findings: &[T],
+        stats: &AnalysisStats,
+        mut writer: W,
+    ) -> Result<()> {
+        for finding in findings {
+            serde_json::to_writer(&mut writer, &GenericItem::new(finding))?;
+            writer.write_all(b"\n")?;
+        }
+
+        let summary = SummaryLine {
             summary: Summary {
                 total_findings: findings.len(),
+                stats: stats.clone(),
             },
-            findings: items,
         };
+        serde_json::to_writer(&mut writer, &summary)?;
+        writer.write_all(b"\n")?;
+        writer.flush()?;
+        Ok(())
+    }
+}
 
-        Ok(serde_json::to_string_pretty(&report)?)
+impl<T: Finding> Reporter<T> for JsonLinesReporter {
+    fn report(&self, findings: &[T], stats: &AnalysisStats) -> Result<String> {
+        let mut output = Vec::new();
+        self.write(findings, stats, &mut output)?;
+        Ok(String::from_utf8(output)?)
     }
 }
 
@@ -39,9 +97,38 @@ struct GenericReport {
     findings: Vec<GenericItem>,
 }
 
+impl GenericReport {
+    fn new<T: Finding>(findings: &[T], stats: &AnalysisStats) -> Self {
+        Self {
+            summary: Summary {
+                total_findings: findings.len(),
+                stats: stats.clone(),
+            },
+            findings: findings.iter().map(GenericItem::new).collect(),
+        }
+    }
+}
+
+/// [`GenericReport`] followed by extra top-level sections
+#[derive(Serialize)]
+struct SectionedReport<'a, S> {
+    #[serde(flatten)]
+    report: GenericReport,
+    #[serde(flatten)]
+    sections: BTreeMap<&'a str, &'a S>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct Summary {
     total_findings: usize,
+    #[serde(flatten)]
+    stats: AnalysisStats,
+}
+
+/// Last line of JSON Lines output
+#[derive(Debug, Serialize, Deserialize)]
+struct SummaryLine {
+    summary: Summary,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -54,3 +141,96 @@ struct GenericItem {
     reason: String,
     confidence: String,
 }
+
+impl GenericItem {
+    fn new<T: Finding>(f: &T) -> Self {
+        Self {
+            kind: f.kind(),
+            name: f.name(),
+            file: f.file(),
+            line: f.line(),
+            column: f.column(),
+            reason: f.reason(),
+            confidence: f.confidence(),
+        }
+    }
+}
+
+/// Findings aggregated by directory
+#[derive(Debug, Serialize, Deserialize)]
+struct DirectoryReport {
+    summary: DirectorySummary,
+    directories: Vec<DirectoryGroup>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct DirectorySummary {
+    total_findings: usize,
+    total_directories: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::test_support::TestFinding;
+    use crate::Language;
+
+    #[test]
+    fn test_summary_includes_stats() {
+        let mut stats = AnalysisStats {
+            total_files_scanned: 3,
+            ..Default::default()
+        };
+        stats.record_file(Language::Python, 4);
+        stats.record_file(Language::Python, 1);
+        stats.record_file(Language::TypeScript, 2);
+
+        let output = JsonReporter
+            .report(&[TestFinding::default()], &stats)
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+
+        let summary = &json["summary"];
+        assert_eq!(summary["total_findings"], 1);
+        assert_eq!(summary["total_files_scanned"], 3);
+        assert_eq!(summary["total_definitions"], 7);
+        assert_eq!(summary["languages"]["python"], 2);
+        assert_eq!(summary["languages"]["typescript"], 1);
+    }
+
+    #[test]
+    fn test_report_with_section_adds_a_top_level_key() {
+        let stats = AnalysisStats::default();
+
+        let output = JsonReporter
+            .report_with_section(&[TestFinding::default()], &stats, "extra", &vec![1, 2])
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+
+        assert_eq!(json["summary"]["total_findings"], 1);
+        assert_eq!(json["findings"][0]["name"], "unused");
+        assert_eq!(json["extra"], serde_json::json!([1, 2]));
+    }
+
+    #[test]
+    fn test_json_lines_each_line_is_valid_json() {
+        let stats = AnalysisStats {
+            total_files_scanned: 2,
+            ..Default::default()
+        };
+
+        let output = JsonLinesReporter
+            .report(&[TestFinding::default(), TestFinding::default()], &stats)
+            .unwrap();
+        let lines: Vec<serde_json::Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0]["name"], "unused");
+        assert_eq!(lines[1]["line"], 3);
+        assert_eq!(lines[2]["summary"]["total_findings"], 2);
+        assert_eq!(lines[2]["summary"]["total_files_scanned"], 2);
+    }
+}
@@ -0,0 +1,118 @@
+//! LSP diagnostics reporter (for editor integrations)
+//!
+//! Emits a JSON array of objects shaped like the Language Server Protocol
+//! `Diagnostic`. LSP positions are 0-based for both lines and characters, and a
+//! diagnostic carries no file of its own, so the file path goes in `data.file`.
+//! See <https://microsoft.github.io/language-server-protocol/specifications/lsp/3.17/specification/#diagnostic>.
+
+use super::{rule_id, severity, AnalysisStats, Finding, Reporter};
+use crate::Result;
+use serde_json::{json, Value};
+
+/// `DiagnosticTag.Unnecessary`: editors render the range faded out
+const TAG_UNNECESSARY: u8 = 1;
+
+/// Emits LSP diagnostics attributed to the named tool
+pub struct LspReporter {
+    source: String,
+}
+
+impl LspReporter {
+    pub fn new(source: impl Into<String>) -> Self {
+        Self {
+            source: source.into(),
+        }
+    }
+}
+
+impl<T: Finding> Reporter<T> for LspReporter {
+    fn report(&self, findings: &[T], _stats: &AnalysisStats) -> Result<String> {
+        let diagnostics: Vec<Value> = findings
+            .iter()
+            .map(|finding| {
+                json!({
+                    "range": {
+                        // LSP lines are 0-based, ours are 1-based
+                        "start": {
+                            "line": finding.line().saturating_sub(1),
+                            "character": finding.column(),
+                        },
+                        "end": {
+                            "line": finding.end_line().saturating_sub(1),
+                            "character": finding.end_column(),
+                        },
+                    },
+                    "severity": lsp_severity(&finding.confidence()),
+                    "code": rule_id(&finding.kind()),
+                    "source": self.source,
+                    "message": format!("{}: {}", finding.name(), finding.reason()),
+                    "tags": [TAG_UNNECESSARY],
+                    "data": {
+                        "file": finding.file().replace('\\', "/"),
+                    },
+                })
+            })
+            .collect();
+
+        Ok(serde_json::to_string_pretty(&diagnostics)?)
+    }
+}
+
+/// `DiagnosticSeverity` (1 = Error, 2 = Warning, 3 = Information) from a confidence label
+fn lsp_severity(confidence: &str) -> u8 {
+    match severity(confidence) {
+        "error" => 1,
+        "warning" => 2,
+        _ => 3,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::test_support::TestFinding;
+
+    #[test]
+    fn test_diagnostics_cover_the_whole_symbol() {
+        let findings = [
+            TestFinding {
+                file: "src\\app.py",
+                end_line: Some(7),
+                end_column: Some(12),
+                ..Default::default()
+            },
+            TestFinding {
+                file: "src\\app.py",
+                end_column: Some(12),
+                confidence: "Low",
+                ..Default::default()
+            },
+        ];
+        let output = LspReporter::new("bury")
+            .report(&findings, &AnalysisStats::default())
+            .unwrap();
+        let diagnostics: Value = serde_json::from_str(&output).unwrap();
+
+        let multi_line = &diagnostics[0];
+        assert_eq!(
+            multi_line["range"]["start"],
+            json!({"line": 2, "character": 4})
+        );
+        assert_eq!(
+            multi_line["range"]["end"],
+            json!({"line": 6, "character": 12})
+        );
+        assert_ne!(multi_line["range"]["start"], multi_line["range"]["end"]);
+        assert_eq!(multi_line["severity"], 1);
+        assert_eq!(multi_line["code"], "function");
+        assert_eq!(multi_line["source"], "bury");
+        assert_eq!(
+            multi_line["message"],
+            "unused: Not reachable from any entry point"
+        );
+        assert_eq!(multi_line["data"]["file"], "src/app.py");
+
+        assert_eq!(diagnostics[1]["severity"], 3);
+        assert_eq!(diagnostics[1]["range"]["end"]["line"], 2);
+    }
+}
@@ -1,16 +1,22 @@
 //! Markdown reporter (human-readable format)
+//!
+//! Findings are grouped under one heading per file, after a table of per-file
+//! counts.
 
-use super::{Finding, Reporter};
+use super::{rule_id, AnalysisStats, DirectoryGroup, Finding, Reporter};
 use crate::Result;
+use std::collections::BTreeMap;
 
 pub struct MarkdownReporter;
 
 impl<T: Finding> Reporter<T> for MarkdownReporter {
-    fn report(&self, findings: &[T]) -> Result<String> {
+    fn report(&self, findings: &[T], stats: &AnalysisStats) -> Result<String> {
         let mut output = String::new();
 
         output.push_str("# Analysis Report\n\n");
         output.push_str("## Summary\n\n");
+        output.push_str(&format!("- Files scanned: {}\n", stats.total_files_scanned));
+        output.push_str(&format!("- Definitions: {}\n", stats.total_definitions));
         output.push_str(&format!("- Total findings: {}\n\n", findings.len()));
 
         if findings.is_empty() {
@@ -18,16 +24,113 @@ impl<T: Finding> Reporter<T> for MarkdownReporter {
             return Ok(output);
         }
 
-        output.push_str("## Details\n\n");
-
+        let mut by_file: BTreeMap<String, Vec<&T>> = BTreeMap::new();
         for finding in findings {
-            output.push_str(&format!("### {}:{}\n", finding.file(), finding.line()));
-            output.push_str(&format!("**Type:** {}\n", finding.kind()));
-            output.push_str(&format!("**Name:** `{}`\n", finding.name()));
-            output.push_str(&format!("**Reason:** {}\n", finding.reason()));
-            output.push_str(&format!("**Confidence:** {}\n\n", finding.confidence()));
+            by_file.entry(finding.file()).or_default().push(finding);
+        }
+
+        output.push_str("| File | Findings |\n");
+        output.push_str("|------|----------|\n");
+        for (file, file_findings) in &by_file {
+            output.push_str(&format!("| `{}` | {} |\n", file, file_findings.len()));
+        }
+        output.push('\n');
+
+        for (file, mut file_findings) in by_file {
+            file_findings.sort_by_key(|f| (f.line(), f.column()));
+
+            output.push_str(&format!("## `{}`\n\n", file));
+            for finding in file_findings {
+                output.push_str(&format!(
+                    "- {}:{} `{}` ({}, {}): {}\n",
+                    finding.line(),
+                    finding.column() + 1,
+                    finding.name(),
+                    rule_id(&finding.kind()),
+                    finding.confidence(),
+                    finding.reason(),
+                ));
+                if let Some(snippet) = finding.snippet() {
+                    output.push_str("\n  ```\n");
+                    for line in snippet.lines() {
+                        output.push_str(&format!("  {}\n", line));
+                    }
+                    output.push_str("  ```\n\n");
+                }
+            }
+            output.push('\n');
+        }
+
+        Ok(output)
+    }
+}
+
+impl MarkdownReporter {
+    /// Report finding counts aggregated by directory
+    pub fn report_directories(&self, groups: &[DirectoryGroup]) -> Result<String> {
+        let mut output = String::new();
+        let total: usize = groups.iter().map(|g| g.findings).sum();
+
+        output.push_str("# Analysis Report\n\n");
+        output.push_str("## Summary\n\n");
+        output.push_str(&format!("- Total findings: {}\n", total));
+        output.push_str(&format!("- Directories: {}\n\n", groups.len()));
+
+        if groups.is_empty() {
+            output.push_str("✅ No issues found!\n");
+            return Ok(output);
+        }
+
+        output.push_str("## Findings by Directory\n\n");
+        output.push_str("| Directory | Findings |\n");
+        output.push_str("|-----------|----------|\n");
+
+        for group in groups {
+            output.push_str(&format!("| `{}` | {} |\n", group.directory, group.findings));
         }
 
         Ok(output)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::test_support::TestFinding;
+
+    #[test]
+    fn test_findings_grouped_under_one_heading_per_file() {
+        let findings = [
+            TestFinding {
+                file: "src/a.py",
+                name: "stale",
+                line: 9,
+                ..Default::default()
+            },
+            TestFinding {
+                file: "src/b.py",
+                name: "orphan",
+                line: 1,
+                ..Default::default()
+            },
+            TestFinding {
+                file: "src/a.py",
+                name: "unused",
+                line: 3,
+                ..Default::default()
+            },
+        ];
+
+        let output = MarkdownReporter
+            .report(&findings, &AnalysisStats::default())
+            .unwrap();
+
+        assert_eq!(output.matches("## `src/a.py`").count(), 1);
+        assert!(output.contains("| `src/a.py` | 2 |\n| `src/b.py` | 1 |"));
+        assert!(output.contains(
+            "## `src/a.py`\n\n\
+             - 3:5 `unused` (function, High): Not reachable from any entry point\n\
+             - 9:5 `stale` (function, High): Not reachable from any entry point\n"
+        ));
+    }
+}
@@ -1,13 +1,28 @@
 //! Report generation in various formats
 
-use crate::Result;
-use serde::Serialize;
+use crate::{Language, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
+pub mod csv;
+pub mod github;
+pub mod group;
+pub mod html;
 pub mod json;
+pub mod lsp;
 pub mod markdown;
+pub mod sarif;
+pub mod terminal;
 
-pub use json::JsonReporter;
+pub use csv::CsvReporter;
+pub use github::GithubActionsReporter;
+pub use group::{group_by_directory, relative_path, DirectoryGroup};
+pub use html::HtmlReporter;
+pub use json::{JsonLinesReporter, JsonReporter};
+pub use lsp::LspReporter;
 pub use markdown::MarkdownReporter;
+pub use sarif::SarifReporter;
+pub use terminal::TerminalReporter;
 
 /// Trait for analysis findings that can be reported
 pub trait Finding: Serialize {
@@ -21,13 +36,135 @@ pub trait Finding: Serialize {
     fn line(&self) -> usize;
     /// Get the column number
     fn column(&self) -> usize;
+    /// Get the line where the finding ends (defaults to the start line)
+    fn end_line(&self) -> usize {
+        self.line()
+    }
+    /// Get the column where the finding ends (defaults to the start column)
+    fn end_column(&self) -> usize {
+        self.column()
+    }
     /// Get the reason/description
     fn reason(&self) -> String;
     /// Get the confidence level
     fn confidence(&self) -> String;
+    /// Get the source lines around the finding, if they were collected
+    fn snippet(&self) -> Option<String> {
+        None
+    }
+}
+
+/// Statistics about the analyzed codebase, included in report summaries
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AnalysisStats {
+    /// Number of files found by the scanner
+    pub total_files_scanned: usize,
+    /// Number of definitions (functions, classes, methods) extracted
+    pub total_definitions: usize,
+    /// Number of parsed files per language (e.g. "python", "typescript")
+    pub languages: BTreeMap<String, usize>,
+}
+
+impl AnalysisStats {
+    /// Record a successfully parsed file and its definitions
+    pub fn record_file(&mut self, language: Language, definitions: usize) {
+        self.total_definitions += definitions;
+        *self
+            .languages
+            .entry(language.name().to_string())
+            .or_insert(0) += 1;
+    }
+}
+
+/// Short identifier for a finding kind, e.g. `Method { class_name: .. }` -> `method`
+pub(crate) fn rule_id(kind: &str) -> String {
+    kind.split(|c: char| !c.is_alphanumeric())
+        .next()
+        .unwrap_or_default()
+        .to_lowercase()
+}
+
+/// Severity level (`error`, `warning`, `note`) from a confidence label
+pub(crate) fn severity(confidence: &str) -> &'static str {
+    match confidence {
+        "High" => "error",
+        "Medium" => "warning",
+        _ => "note",
+    }
 }
 
 /// Reporter trait for outputting analysis results
 pub trait Reporter<T: Finding> {
-    fn report(&self, findings: &[T]) -> Result<String>;
+    fn report(&self, findings: &[T], stats: &AnalysisStats) -> Result<String>;
+}
+
+#[cfg(test)]
+pub(crate) mod test_support {
+    //! Finding fixture shared by the reporter tests
+
+    use super::Finding;
+    use serde::Serialize;
+
+    /// A finding with every field set directly; override what a test needs with
+    /// `TestFinding { file: "...", ..Default::default() }`
+    #[derive(Debug, Clone, Serialize)]
+    pub(crate) struct TestFinding {
+        pub kind: &'static str,
+        pub name: &'static str,
+        pub file: &'static str,
+        pub line: usize,
+        pub column: usize,
+        /// Defaults to `line`
+        pub end_line: Option<usize>,
+        /// Defaults to `column`
+        pub end_column: Option<usize>,
+        pub reason: &'static str,
+        pub confidence: &'static str,
+    }
+
+    impl Default for TestFinding {
+        fn default() -> Self {
+            Self {
+                kind: "Function",
+                name: "unused",
+                file: "src/app.py",
+                line: 3,
+                column: 4,
+                end_line: None,
+                end_column: None,
+                reason: "Not reachable from any entry point",
+                confidence: "High",
+            }
+        }
+    }
+
+    impl Finding for TestFinding {
+        fn kind(&self) -> String {
+            self.kind.to_string()
+        }
+        fn name(&self) -> String {
+            self.name.to_string()
+        }
+        fn file(&self) -> String {
+            self.file.to_string()
+        }
+        fn line(&self) -> usize {
+            self.line
+        }
+        fn column(&self) -> usize {
+            self.column
+        }
+        fn end_line(&self) -> usize {
+            self.end_line.unwrap_or(self.line)
+        }
+        fn end_column(&self) -> usize {
+            self.end_column.unwrap_or(self.column)
+        }
+        fn reason(&self) -> String {
+            self.reason.to_string()
+        }
+        fn confidence(&self) -> String {
+            self.confidence.to_string()
+        }
+    }
 }
@@ -0,0 +1,114 @@
+//! SARIF 2.1.0 reporter (for GitHub code scanning and other SARIF consumers)
+
+use super::{rule_id, severity, AnalysisStats, Finding, Reporter};
+use crate::Result;
+use serde_json::{json, Value};
+use std::collections::BTreeSet;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_VERSION: &str = "2.1.0";
+
+/// Emits a SARIF log with a single run for the named tool
+pub struct SarifReporter {
+    tool_name: String,
+    tool_version: String,
+}
+
+impl SarifReporter {
+    pub fn new(tool_name: impl Into<String>, tool_version: impl Into<String>) -> Self {
+        Self {
+            tool_name: tool_name.into(),
+            tool_version: tool_version.into(),
+        }
+    }
+}
+
+impl<T: Finding> Reporter<T> for SarifReporter {
+    fn report(&self, findings: &[T], stats: &AnalysisStats) -> Result<String> {
+        let rules: BTreeSet<String> = findings.iter().map(|f| rule_id(&f.kind())).collect();
+
+        let results: Vec<Value> = findings
+            .iter()
+            .map(|finding| {
+                json!({
+                    "ruleId": rule_id(&finding.kind()),
+                    "level": severity(&finding.confidence()),
+                    "message": {
+                        "text": format!("{}: {}", finding.name(), finding.reason()),
+                    },
+                    "locations": [{
+                        "physicalLocation": {
+                            "artifactLocation": {
+                                "uri": finding.file().replace('\\', "/"),
+                            },
+                            "region": {
+                                "startLine": finding.line().max(1),
+                                // SARIF columns are 1-based, ours are 0-based
+                                "startColumn": finding.column() + 1,
+                            },
+                        },
+                    }],
+                })
+            })
+            .collect();
+
+        let log = json!({
+            "$schema": SARIF_SCHEMA,
+            "version": SARIF_VERSION,
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": self.tool_name,
+                        "version": self.tool_version,
+                        "rules": rules.iter().map(|id| json!({ "id": id })).collect::<Vec<_>>(),
+                    },
+                },
+                "results": results,
+                "properties": stats,
+            }],
+        });
+
+        Ok(serde_json::to_string_pretty(&log)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::test_support::TestFinding;
+
+    #[test]
+    fn test_output_matches_sarif_schema() {
+        let schema: Value =
+            serde_json::from_str(include_str!("../../tests/fixtures/sarif-schema-2.1.0.json"))
+                .unwrap();
+        let validator = jsonschema::validator_for(&schema).unwrap();
+
+        let findings = [
+            TestFinding::default(),
+            TestFinding {
+                kind: "Method { class_name: \"Service\" }",
+                confidence: "Medium",
+                ..Default::default()
+            },
+        ];
+        let output = SarifReporter::new("bury", "0.1.0")
+            .report(&findings, &AnalysisStats::default())
+            .unwrap();
+        let log: Value = serde_json::from_str(&output).unwrap();
+
+        let errors: Vec<String> = validator.iter_errors(&log).map(|e| e.to_string()).collect();
+        assert!(errors.is_empty(), "schema errors: {:?}", errors);
+
+        let run = &log["runs"][0];
+        assert_eq!(run["tool"]["driver"]["name"], "bury");
+        assert_eq!(run["results"][0]["ruleId"], "function");
+        assert_eq!(run["results"][0]["level"], "error");
+        assert_eq!(run["results"][1]["ruleId"], "method");
+        assert_eq!(run["results"][1]["level"], "warning");
+
+        let region = &run["results"][0]["locations"][0]["physicalLocation"]["region"];
+        assert_eq!(region["startLine"], 3);
+        assert_eq!(region["startColumn"], 5);
+    }
+}
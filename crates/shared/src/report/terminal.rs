@@ -0,0 +1,204 @@
+//! Terminal reporter (colored, grouped by file)
+//!
+//! Each finding is printed as `path:line:col`, which most editors and terminals
+//! recognize as a clickable location. Color is disabled when `NO_COLOR` is set
+//! or stdout is not a terminal.
+
+use super::{rule_id, severity, AnalysisStats, Finding, Reporter};
+use crate::Result;
+use owo_colors::OwoColorize;
+use std::collections::BTreeMap;
+use std::env;
+use std::io::{self, IsTerminal};
+use std::path::{Path, PathBuf};
+
+pub struct TerminalReporter {
+    color: bool,
+    base: Option<PathBuf>,
+}
+
+impl TerminalReporter {
+    /// Create a reporter that colors output only when writing to a terminal
+    pub fn new() -> Self {
+        let no_color = env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+
+        Self {
+            color: !no_color && io::stdout().is_terminal(),
+            base: env::current_dir().ok(),
+        }
+    }
+
+    /// Force color on or off
+    pub fn with_color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Display file paths relative to this directory (defaults to the current directory)
+    pub fn with_base(mut self, base: impl Into<PathBuf>) -> Self {
+        self.base = Some(base.into());
+        self
+    }
+
+    fn display_path(&self, file: &str) -> String {
+        let path = Path::new(file);
+        let relative = self
+            .base
+            .as_deref()
+            .and_then(|base| path.strip_prefix(base).ok())
+            .unwrap_or(path);
+        let relative = relative.strip_prefix(".").unwrap_or(relative);
+
+        relative.display().to_string()
+    }
+
+    fn paint_severity(&self, level: &'static str) -> String {
+        let label = format!("{:<7}", level);
+        if !self.color {
+            return label;
+        }
+        match level {
+            "error" => label.red().bold().to_string(),
+            "warning" => label.yellow().bold().to_string(),
+            _ => label.blue().to_string(),
+        }
+    }
+
+    fn bold(&self, text: &str) -> String {
+        if self.color {
+            text.bold().to_string()
+        } else {
+            text.to_string()
+        }
+    }
+
+    fn dimmed(&self, text: &str) -> String {
+        if self.color {
+            text.dimmed().to_string()
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+impl Default for TerminalReporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Finding> Reporter<T> for TerminalReporter {
+    fn report(&self, findings: &[T], stats: &AnalysisStats) -> Result<String> {
+        let mut output = String::new();
+
+        let mut by_file: BTreeMap<String, Vec<&T>> = BTreeMap::new();
+        for finding in findings {
+            by_file
+                .entry(self.display_path(&finding.file()))
+                .or_default()
+                .push(finding);
+        }
+
+        for (file, mut file_findings) in by_file {
+            file_findings.sort_by_key(|f| (f.line(), f.column()));
+
+            output.push_str(&self.bold(&file));
+            output.push('\n');
+
+            for finding in file_findings {
+                let location = format!("{}:{}:{}", file, finding.line(), finding.column() + 1);
+                output.push_str(&format!(
+                    "  {}  {} {} `{}` {}\n",
+                    location,
+                    self.paint_severity(severity(&finding.confidence())),
+                    rule_id(&finding.kind()),
+                    finding.name(),
+                    self.dimmed(&finding.reason()),
+                ));
+                if let Some(snippet) = finding.snippet() {
+                    for line in snippet.lines() {
+                        output.push_str(&format!("      {}\n", self.dimmed(line)));
+                    }
+                }
+            }
+            output.push('\n');
+        }
+
+        let summary = format!(
+            "{} files scanned, {} definitions",
+            stats.total_files_scanned, stats.total_definitions
+        );
+        if findings.is_empty() {
+            output.push_str(&format!("✅ No issues found ({})", summary));
+        } else {
+            output.push_str(&format!(
+                "{} ({})",
+                self.bold(&format!("{} findings", findings.len())),
+                summary
+            ));
+        }
+
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::test_support::TestFinding;
+
+    fn findings() -> Vec<TestFinding> {
+        vec![
+            TestFinding {
+                kind: "Method { class_name: \"Service\" }",
+                file: "/repo/src/b.py",
+                line: 9,
+                ..Default::default()
+            },
+            TestFinding {
+                kind: "Method { class_name: \"Service\" }",
+                file: "/repo/src/a.py",
+                line: 7,
+                ..Default::default()
+            },
+            TestFinding {
+                kind: "Method { class_name: \"Service\" }",
+                file: "/repo/src/a.py",
+                line: 2,
+                ..Default::default()
+            },
+        ]
+    }
+
+    #[test]
+    fn test_groups_by_file_with_clickable_locations() {
+        let output = TerminalReporter::new()
+            .with_color(false)
+            .with_base("/repo")
+            .report(&findings(), &AnalysisStats::default())
+            .unwrap();
+
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[0], "src/a.py");
+        assert!(lines[1].starts_with("  src/a.py:2:5  error   method `unused`"));
+        assert!(lines[2].starts_with("  src/a.py:7:5"));
+        assert_eq!(lines[4], "src/b.py");
+        assert!(output.ends_with("3 findings (0 files scanned, 0 definitions)"));
+    }
+
+    #[test]
+    fn test_color_can_be_toggled() {
+        let stats = AnalysisStats::default();
+        let colored = TerminalReporter::new()
+            .with_color(true)
+            .report(&findings(), &stats)
+            .unwrap();
+        let plain = TerminalReporter::new()
+            .with_color(false)
+            .report(&findings(), &stats)
+            .unwrap();
+
+        assert!(colored.contains("\x1b["));
+        assert!(!plain.contains("\x1b["));
+    }
+}
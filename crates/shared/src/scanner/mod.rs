@@ -1,12 +1,52 @@
-//! File system scanner with .gitignore support
+//! File system scanner with .gitignore and .buryignore support
+//!
+//! `.buryignore` files use `.gitignore` syntax and are read from the scan root and
+//! any subdirectory. Unlike `.gitignore`, they apply even outside a git repository.
+//! Their rules take precedence over `.gitignore`, so a `!pattern` in `.buryignore`
+//! re-includes a file that `.gitignore` excludes.
 
-use crate::Result;
-use ignore::WalkBuilder;
+use crate::error::{BuryError, Result};
+use ignore::overrides::OverrideBuilder;
+use ignore::{DirEntry, WalkBuilder, WalkState};
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Name of the bury-specific ignore file
+pub const IGNORE_FILENAME: &str = ".buryignore";
+
+/// File extensions scanned when none are configured
+pub const DEFAULT_EXTENSIONS: &[&str] = &["py", "ts", "tsx", "js", "jsx", "go", "rs"];
+
+/// Files larger than this (1 MiB) are skipped by default: they are usually
+/// generated bundles or minified code
+pub const DEFAULT_MAX_FILE_SIZE: u64 = 1024 * 1024;
 
 /// Scanner finds source files to analyze
 pub struct Scanner {
     root: PathBuf,
+    extensions: Vec<String>,
+    excludes: Vec<String>,
+    respect_gitignore: bool,
+    include_hidden: bool,
+    max_file_size: Option<u64>,
+}
+
+/// Files found by [`Scanner::scan_detailed`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScanOutcome {
+    /// Files to analyze, sorted
+    pub files: Vec<PathBuf>,
+    /// Files with a supported extension left out for exceeding the maximum file
+    /// size, sorted
+    pub too_large: Vec<PathBuf>,
+}
+
+/// How the walk treats a directory entry
+enum Candidate {
+    File,
+    TooLarge,
+    Skip,
 }
 
 impl Scanner {
@@ -14,30 +54,178 @@ impl Scanner {
     pub fn new(root: impl AsRef<Path>) -> Self {
         Self {
             root: root.as_ref().to_path_buf(),
+            extensions: DEFAULT_EXTENSIONS.iter().map(|e| e.to_string()).collect(),
+            excludes: Vec::new(),
+            respect_gitignore: true,
+            include_hidden: true,
+            max_file_size: Some(DEFAULT_MAX_FILE_SIZE),
+        }
+    }
+
+    /// Only scan files with these extensions (with or without a leading dot),
+    /// replacing the default set. An empty list keeps the defaults.
+    pub fn with_extensions(mut self, extensions: Vec<String>) -> Self {
+        if !extensions.is_empty() {
+            self.extensions = extensions
+                .into_iter()
+                .map(|e| e.trim_start_matches('.').to_string())
+                .collect();
+        }
+        self
+    }
+
+    /// Skip paths matching these globs (`.gitignore` syntax, relative to the root)
+    pub fn with_excludes(mut self, globs: Vec<String>) -> Self {
+        self.excludes = globs;
+        self
+    }
+
+    /// Honor `.gitignore`, `.ignore`, `.git/info/exclude` and the global git
+    /// excludes (on by default). `.buryignore` and excludes always apply.
+    pub fn respect_gitignore(mut self, respect: bool) -> Self {
+        self.respect_gitignore = respect;
+        self
+    }
+
+    /// Scan hidden files and directories, like `.github/` (on by default)
+    pub fn include_hidden(mut self, include: bool) -> Self {
+        self.include_hidden = include;
+        self
+    }
+
+    /// Skip files larger than `bytes` ([`DEFAULT_MAX_FILE_SIZE`] by default);
+    /// 0 disables the limit
+    pub fn with_max_file_size(mut self, bytes: u64) -> Self {
+        self.max_file_size = (bytes > 0).then_some(bytes);
+        self
+    }
+
+    /// Configure the directory walker shared by serial and parallel scans
+    fn walk_builder(&self) -> Result<WalkBuilder> {
+        let mut overrides = OverrideBuilder::new(&self.root);
+        for glob in &self.excludes {
+            overrides
+                .add(&format!("!{}", glob))
+                .map_err(|e| BuryError::ConfigInvalid(format!("exclude `{}`: {}", glob, e)))?;
         }
+
+        let mut builder = WalkBuilder::new(&self.root);
+        builder
+            .hidden(!self.include_hidden)
+            .git_ignore(self.respect_gitignore)
+            .git_global(self.respect_gitignore)
+            .git_exclude(self.respect_gitignore)
+            .ignore(self.respect_gitignore)
+            .add_custom_ignore_filename(IGNORE_FILENAME)
+            .overrides(overrides.build()?);
+        Ok(builder)
     }
 
-    /// Scan for Python and TypeScript files
+    /// Scan for files with a supported extension
     pub fn scan(&self) -> Result<Vec<PathBuf>> {
         let mut files = Vec::new();
 
-        for result in WalkBuilder::new(&self.root)
-            .hidden(false) // Include hidden files
-            .git_ignore(true) // Respect .gitignore
-            .build()
-        {
+        for result in self.walk_builder()?.build() {
             let entry = result?;
-            let path = entry.path();
 
-            if self.is_supported_file(path) {
-                files.push(path.to_path_buf());
+            if let Candidate::File = self.candidate(&entry) {
+                files.push(entry.into_path());
             }
         }
 
         Ok(files)
     }
 
-    /// Check if file is a supported language
+    /// Scan for files with a supported extension using multiple threads.
+    ///
+    /// Applies the same filtering as [`Scanner::scan`]. Results are sorted, since
+    /// the parallel walker visits files in a nondeterministic order.
+    pub fn scan_parallel(&self) -> Result<Vec<PathBuf>> {
+        Ok(self.scan_detailed()?.files)
+    }
+
+    /// Like [`Scanner::scan_parallel`], also listing the files skipped for
+    /// exceeding the maximum file size
+    pub fn scan_detailed(&self) -> Result<ScanOutcome> {
+        let files = Mutex::new(Vec::new());
+        let too_large = Mutex::new(Vec::new());
+        let error = Mutex::new(None);
+
+        self.walk_builder()?.build_parallel().run(|| {
+            Box::new(|result| match result {
+                Ok(entry) => {
+                    match self.candidate(&entry) {
+                        Candidate::File => files.lock().unwrap().push(entry.into_path()),
+                        Candidate::TooLarge => too_large.lock().unwrap().push(entry.into_path()),
+                        Candidate::Skip => {}
+                    }
+                    WalkState::Continue
+                }
+                Err(e) => {
+                    *error.lock().unwrap() = Some(e);
+                    WalkState::Quit
+                }
+            })
+        });
+
+        if let Some(e) = error.into_inner().unwrap() {
+            return Err(e.into());
+        }
+
+        let mut outcome = ScanOutcome {
+            files: files.into_inner().unwrap(),
+            too_large: too_large.into_inner().unwrap(),
+        };
+        outcome.files.sort();
+        outcome.too_large.sort();
+        Ok(outcome)
+    }
+
+    /// Like [`Scanner::scan_detailed`], but only for the listed `paths`: a file
+    /// is kept only if a full scan would find it, so ignore files, excludes and
+    /// the size limit apply as usual. Only the directories leading to the listed
+    /// files are walked. Paths that don't exist or lie outside the root are
+    /// dropped.
+    pub fn scan_only(&self, paths: &[PathBuf]) -> Result<ScanOutcome> {
+        let listed: HashSet<PathBuf> = paths.iter().filter_map(|p| p.canonicalize().ok()).collect();
+        let ancestors: HashSet<PathBuf> = listed
+            .iter()
+            .flat_map(|p| p.ancestors().skip(1))
+            .map(Path::to_path_buf)
+            .collect();
+
+        let mut builder = self.walk_builder()?;
+        builder.filter_entry(move |entry| match entry.path().canonicalize() {
+            Ok(path) => listed.contains(&path) || ancestors.contains(&path),
+            Err(_) => false,
+        });
+
+        let mut outcome = ScanOutcome::default();
+        for result in builder.build() {
+            let entry = result?;
+            match self.candidate(&entry) {
+                Candidate::File => outcome.files.push(entry.into_path()),
+                Candidate::TooLarge => outcome.too_large.push(entry.into_path()),
+                Candidate::Skip => {}
+            }
+        }
+        outcome.files.sort();
+        outcome.too_large.sort();
+        Ok(outcome)
+    }
+
+    /// Classify a walked entry, checking the size only of supported files
+    fn candidate(&self, entry: &DirEntry) -> Candidate {
+        if !self.is_supported_file(entry.path()) {
+            return Candidate::Skip;
+        }
+        match (self.max_file_size, entry.metadata()) {
+            (Some(max), Ok(metadata)) if metadata.len() > max => Candidate::TooLarge,
+            _ => Candidate::File,
+        }
+    }
+
+    /// Check if file has one of the configured extensions
     fn is_supported_file(&self, path: &Path) -> bool {
         if !path.is_file() {
             return false;
@@ -45,7 +233,7 @@ impl Scanner {
 
         path.extension()
             .and_then(|ext| ext.to_str())
-            .map(|ext| matches!(ext, "py" | "ts" | "tsx" | "js" | "jsx"))
+            .map(|ext| self.extensions.iter().any(|e| e == ext))
             .unwrap_or(false)
     }
 }
@@ -78,7 +266,222 @@ mod tests {
         assert!(scanner.is_supported_file(&py_file));
         assert!(scanner.is_supported_file(&ts_file));
         assert!(scanner.is_supported_file(&tsx_file));
-        assert!(!scanner.is_supported_file(&rs_file));
+        assert!(scanner.is_supported_file(&rs_file));
         assert!(!scanner.is_supported_file(&txt_file));
     }
+
+    #[test]
+    fn test_scan_parallel_matches_serial_scan() {
+        use std::fs;
+        use tempfile::tempdir;
+
+        let dir = tempdir().unwrap();
+        for sub in ["a", "a/b", "c"] {
+            fs::create_dir_all(dir.path().join(sub)).unwrap();
+            for name in ["one.py", "two.ts", "three.js", "notes.txt"] {
+                fs::write(dir.path().join(sub).join(name), "").unwrap();
+            }
+        }
+
+        let scanner = Scanner::new(dir.path());
+
+        let mut serial = scanner.scan().unwrap();
+        serial.sort();
+        let parallel = scanner.scan_parallel().unwrap();
+
+        assert_eq!(serial.len(), 9);
+        assert_eq!(serial, parallel);
+    }
+
+    #[test]
+    fn test_with_extensions_restricts_scan() {
+        use std::fs;
+        use tempfile::tempdir;
+
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("app.py"), "").unwrap();
+        fs::write(dir.path().join("app.ts"), "").unwrap();
+        fs::write(dir.path().join("lib.mjs"), "").unwrap();
+
+        let python_only = Scanner::new(dir.path()).with_extensions(vec!["py".to_string()]);
+        let files = python_only.scan().unwrap();
+        assert_eq!(files, vec![dir.path().join("app.py")]);
+
+        let with_mjs = Scanner::new(dir.path()).with_extensions(vec![".mjs".to_string()]);
+        assert!(with_mjs.is_supported_file(&dir.path().join("lib.mjs")));
+        assert!(!with_mjs.is_supported_file(&dir.path().join("app.ts")));
+
+        let defaults = Scanner::new(dir.path()).with_extensions(Vec::new());
+        assert_eq!(defaults.scan().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_buryignore_excludes_files() {
+        use std::fs;
+        use tempfile::tempdir;
+
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("generated")).unwrap();
+        fs::create_dir_all(dir.path().join("src")).unwrap();
+        fs::write(dir.path().join("generated/schema.py"), "").unwrap();
+        fs::write(dir.path().join("src/app.py"), "").unwrap();
+        fs::write(dir.path().join(IGNORE_FILENAME), "generated/\n").unwrap();
+
+        let scanner = Scanner::new(dir.path());
+
+        assert_eq!(scanner.scan().unwrap(), vec![dir.path().join("src/app.py")]);
+        assert_eq!(
+            scanner.scan_parallel().unwrap(),
+            vec![dir.path().join("src/app.py")]
+        );
+    }
+
+    #[test]
+    fn test_with_excludes_skips_matching_paths() {
+        use std::fs;
+        use tempfile::tempdir;
+
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("migrations")).unwrap();
+        fs::write(dir.path().join("migrations/0001_initial.py"), "").unwrap();
+        fs::write(dir.path().join("app.py"), "").unwrap();
+        fs::write(dir.path().join("app_test.py"), "").unwrap();
+
+        let scanner = Scanner::new(dir.path())
+            .with_excludes(vec!["migrations/".to_string(), "*_test.py".to_string()]);
+
+        assert_eq!(scanner.scan().unwrap(), vec![dir.path().join("app.py")]);
+        assert_eq!(
+            scanner.scan_parallel().unwrap(),
+            vec![dir.path().join("app.py")]
+        );
+    }
+
+    #[test]
+    fn test_invalid_exclude_is_a_config_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let scanner = Scanner::new(dir.path()).with_excludes(vec!["src/[".to_string()]);
+
+        match scanner.scan() {
+            Err(BuryError::ConfigInvalid(message)) => assert!(message.contains("src/[")),
+            other => panic!("expected ConfigInvalid, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_missing_root_is_a_walk_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let scanner = Scanner::new(dir.path().join("missing"));
+
+        assert!(matches!(scanner.scan(), Err(BuryError::Walk(_))));
+        assert!(matches!(scanner.scan_parallel(), Err(BuryError::Walk(_))));
+    }
+
+    #[test]
+    fn test_gitignore_can_be_disabled() {
+        use std::fs;
+
+        let dir = tempfile::tempdir().unwrap();
+        // .gitignore only applies inside a git repository
+        fs::create_dir(dir.path().join(".git")).unwrap();
+        fs::create_dir(dir.path().join("vendor")).unwrap();
+        fs::write(dir.path().join(".gitignore"), "vendor/\n").unwrap();
+        fs::write(dir.path().join("vendor/lib.py"), "").unwrap();
+        fs::write(dir.path().join("app.py"), "").unwrap();
+
+        let scanner = Scanner::new(dir.path());
+        assert_eq!(scanner.scan().unwrap(), vec![dir.path().join("app.py")]);
+
+        let mut files = scanner.respect_gitignore(false).scan().unwrap();
+        files.sort();
+        assert_eq!(
+            files,
+            vec![dir.path().join("app.py"), dir.path().join("vendor/lib.py")]
+        );
+    }
+
+    #[test]
+    fn test_hidden_files_can_be_skipped() {
+        use std::fs;
+
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join(".github")).unwrap();
+        fs::write(dir.path().join(".github/check.py"), "").unwrap();
+        fs::write(dir.path().join("app.py"), "").unwrap();
+
+        assert_eq!(Scanner::new(dir.path()).scan_parallel().unwrap().len(), 2);
+        assert_eq!(
+            Scanner::new(dir.path())
+                .include_hidden(false)
+                .scan_parallel()
+                .unwrap(),
+            vec![dir.path().join("app.py")]
+        );
+    }
+
+    #[test]
+    fn test_files_over_the_size_limit_are_skipped() {
+        use std::fs;
+
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("under.js"), "x".repeat(99)).unwrap();
+        fs::write(dir.path().join("exact.js"), "x".repeat(100)).unwrap();
+        fs::write(dir.path().join("over.js"), "x".repeat(101)).unwrap();
+
+        let scanner = Scanner::new(dir.path()).with_max_file_size(100);
+        let outcome = scanner.scan_detailed().unwrap();
+
+        assert_eq!(
+            outcome.files,
+            vec![dir.path().join("exact.js"), dir.path().join("under.js")]
+        );
+        assert_eq!(outcome.too_large, vec![dir.path().join("over.js")]);
+        assert_eq!(scanner.scan().unwrap().len(), 2);
+        assert_eq!(
+            Scanner::new(dir.path())
+                .with_max_file_size(0)
+                .scan_parallel()
+                .unwrap()
+                .len(),
+            3
+        );
+    }
+
+    #[test]
+    fn test_scan_only_applies_ignore_rules_to_listed_files() {
+        use std::fs;
+
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("generated")).unwrap();
+        fs::create_dir_all(dir.path().join("src")).unwrap();
+        fs::write(
+            dir.path().join(IGNORE_FILENAME),
+            "generated/
+",
+        )
+        .unwrap();
+        fs::write(dir.path().join("generated/schema.py"), "").unwrap();
+        fs::write(dir.path().join("src/app.py"), "").unwrap();
+        fs::write(dir.path().join("src/other.py"), "").unwrap();
+        fs::write(dir.path().join("src/app_test.py"), "").unwrap();
+        fs::write(dir.path().join("src/bundle.js"), "x".repeat(200)).unwrap();
+
+        let scanner = Scanner::new(dir.path())
+            .with_excludes(vec!["*_test.py".to_string()])
+            .with_max_file_size(100);
+        let listed = [
+            "generated/schema.py",
+            "src/app.py",
+            "src/app_test.py",
+            "src/bundle.js",
+            "src/deleted.py",
+        ]
+        .iter()
+        .map(|p| dir.path().join(p))
+        .collect::<Vec<_>>();
+        let outcome = scanner.scan_only(&listed).unwrap();
+
+        assert_eq!(outcome.files, vec![dir.path().join("src/app.py")]);
+        assert_eq!(outcome.too_large, vec![dir.path().join("src/bundle.js")]);
+    }
 }